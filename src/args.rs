@@ -1,24 +1,149 @@
-use std::{
-    error::Error,
-    fmt::{self, Display, Formatter},
-    fs,
+use {
+    crate::{
+        diagnostics::DiagnosticsFormat,
+        interpreter::{Evaluation, Overflow},
+    },
+    std::{
+        error::Error,
+        fmt::{self, Display, Formatter},
+        fs,
+    },
 };
 
+/// What to do with the program.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Command {
+    /// Evaluate it and print the result of `main`
+    Run,
+    /// Type check it and print the signature of every function, inferred where left out
+    Check,
+    /// Type check it and translate it to C
+    Build,
+}
+
 #[derive(Debug)]
 pub struct Args {
+    pub command: Command,
     pub file_path: String,
+    /// Where `build` writes the C file
+    pub output: Option<String>,
+    pub diagnostics_format: DiagnosticsFormat,
+    /// What integer arithmetic does when it overflows 64 bits
+    pub overflow: Overflow,
+    /// When function arguments are evaluated
+    pub evaluation: Evaluation,
+    /// How many results of function calls to cache, if any
+    pub memoise: Option<usize>,
+    /// Print the optimised AST instead of running the program
+    pub print_optimised: bool,
+    /// Error code to explain instead of running a program
+    pub explain: Option<String>,
 }
 
 impl Args {
     pub fn build(args: &mut impl Iterator<Item = String>) -> Result<Args, ApplicationError> {
         args.next(); // Program name
 
-        let file_path = match args.next() {
-            Some(arg) => arg,
-            None => return Err(ApplicationError::Args("no file provided".to_string())),
+        let mut command = Command::Run;
+        let mut file_path: Option<String> = None;
+        let mut output: Option<String> = None;
+        let mut diagnostics_format = DiagnosticsFormat::Human;
+        let mut overflow = Overflow::default();
+        let mut evaluation = Evaluation::default();
+        let mut memoise: Option<usize> = None;
+        let mut print_optimised = false;
+        let mut explain: Option<String> = None;
+
+        while let Some(arg) = args.next() {
+            let (option, value) = match arg.split_once('=') {
+                Some((option, value)) if arg.starts_with("--") => {
+                    (option.to_string(), Some(value.to_string()))
+                }
+                _ => (arg.clone(), None),
+            };
+            match option.as_str() {
+                "--diagnostics-format" => {
+                    let value = value.or_else(|| args.next()).ok_or_else(|| {
+                        ApplicationError::Args(
+                            "no value provided for `--diagnostics-format`".into(),
+                        )
+                    })?;
+                    diagnostics_format = value.parse()?;
+                }
+                "--overflow" => {
+                    let value = value.or_else(|| args.next()).ok_or_else(|| {
+                        ApplicationError::Args("no value provided for `--overflow`".into())
+                    })?;
+                    overflow = value.parse()?;
+                }
+                "--evaluation" => {
+                    let value = value.or_else(|| args.next()).ok_or_else(|| {
+                        ApplicationError::Args("no value provided for `--evaluation`".into())
+                    })?;
+                    evaluation = value.parse()?;
+                }
+                "--memoise" => {
+                    let value = value.or_else(|| args.next()).ok_or_else(|| {
+                        ApplicationError::Args("no cache size provided for `--memoise`".into())
+                    })?;
+                    let size = value.parse().map_err(|_| {
+                        ApplicationError::Args(format!("invalid cache size `{}`", value))
+                    })?;
+                    memoise = Some(size);
+                }
+                "--print-optimised" => print_optimised = true,
+                "-o" | "--output" => {
+                    let value = value.or_else(|| args.next()).ok_or_else(|| {
+                        ApplicationError::Args("no file provided for `--output`".into())
+                    })?;
+                    output = Some(value);
+                }
+                "--explain" => {
+                    let value = value.or_else(|| args.next()).ok_or_else(|| {
+                        ApplicationError::Args("no error code provided for `--explain`".into())
+                    })?;
+                    explain = Some(value);
+                }
+                _ if option.starts_with("--") => {
+                    return Err(ApplicationError::Args(format!(
+                        "unknown option `{}`",
+                        option
+                    )))
+                }
+                "check" if file_path.is_none() && command == Command::Run => {
+                    command = Command::Check
+                }
+                "build" if file_path.is_none() && command == Command::Run => {
+                    command = Command::Build
+                }
+                _ if file_path.is_some() => {
+                    return Err(ApplicationError::Args(format!(
+                        "unexpected argument `{}`",
+                        arg
+                    )))
+                }
+                _ => file_path = Some(arg),
+            }
+        }
+
+        // No program is needed to explain an error code
+        let file_path = match (file_path, &explain) {
+            (Some(file_path), _) => file_path,
+            (None, Some(_)) => String::new(),
+            (None, None) => return Err(ApplicationError::Args("no file provided".to_string())),
         };
 
-        Ok(Args { file_path })
+        Ok(Args {
+            command,
+            file_path,
+            output,
+            diagnostics_format,
+            overflow,
+            evaluation,
+            memoise,
+            print_optimised,
+            explain,
+        })
     }
 
     pub fn source(&self) -> Result<String, ApplicationError> {
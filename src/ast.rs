@@ -1,27 +1,34 @@
 use {
     crate::{
-        errors,
-        errors::CompilerError,
+        errors::{self, CompilerError, ParserError},
         grammar::{
-            self, Argument, Call, Definition, Element, Expression, Function, FunctionCall,
-            Operation, Pipe, Program, Signature,
+            self, Access, Argument, Arm, Call, Case, Clause, Comprehension, Construction,
+            Constructor, Definition, Element, Expression, Field, Function, FunctionCall, Import,
+            Operation, Pattern, Pipe, Program, Raise, Range, Signature, Tuple, TypeAlias,
+            TypeDeclaration, TypeDefinition, VariantCall,
         },
         parser::Parser,
         tokens::{
-            Conditional, Identifier, LocatedIdentifier, Operator, Symbol, Token, Type, Value,
+            Conditional, Identifier, Keyword, LocatedIdentifier, Operator, OperatorType, Symbol,
+            Token, Type, Value,
         },
+        utils,
     },
     grammar::List,
-    std::collections::BTreeMap,
+    std::collections::{BTreeMap, HashMap},
 };
 
 pub fn build(tokens: Vec<Token>) -> Result<Program, CompilerError> {
     if tokens.is_empty() {
-        return Err(CompilerError::Parser("empty program".to_string(), 0));
+        return Err(CompilerError::Parser(ParserError::EmptyProgram, 0));
     }
     let mut parser = Parser::new(tokens);
     let mut program: Program = Program {
+        imports: Vec::new(),
+        types: Vec::new(),
+        aliases: Vec::new(),
         functions: Vec::new(),
+        offsets: Vec::new(),
     };
 
     while parser.has_more() {
@@ -30,15 +37,114 @@ pub fn build(tokens: Vec<Token>) -> Result<Program, CompilerError> {
             parser.advance();
             continue;
         }
+        if let &Token::Keyword(Keyword::Import, _) = parser.peek() {
+            let import = parse_import(&mut parser)?;
+            program.imports.push(import);
+            continue;
+        }
+        if let &Token::Keyword(Keyword::Data, _) = parser.peek() {
+            let declaration = parse_data(&mut parser)?;
+            program.types.push(declaration);
+            continue;
+        }
+        if let &Token::Keyword(Keyword::Type, _) = parser.peek() {
+            let alias = parse_alias(&mut parser)?;
+            program.aliases.push(alias);
+            continue;
+        }
         let function = parse_function(&mut parser)?;
         program.functions.push(function);
     }
 
+    // Types may be declared in imported files, which are checked when they are linked
+    if program.imports.is_empty() {
+        check_types(&program, &[])?;
+        let aliases = program.aliases.clone();
+        expand_aliases(&mut program, &aliases)?;
+    }
     Ok(program)
 }
 
+fn parse_import(parser: &mut Parser) -> Result<Import, CompilerError> {
+    let line = parser.location();
+    parser.advance(); // Consume `import`
+
+    let path = match parser.advance() {
+        Token::Value(Value::String(path), _) => path.clone(),
+        token => {
+            return Err(errors::expected_token(
+                "path of imported file",
+                token.to_string(),
+                line,
+            ))
+        }
+    };
+
+    let mut import = Import {
+        path,
+        alias: None,
+        names: None,
+        line,
+    };
+    match parser.peek() {
+        Token::Keyword(Keyword::As, _) => {
+            parser.advance();
+            match parser.advance() {
+                Token::Identifier(alias, _) => import.alias = Some(alias.clone()),
+                token => {
+                    return Err(errors::expected_token(
+                        "name of imported module",
+                        token.to_string(),
+                        line,
+                    ))
+                }
+            }
+        }
+        Token::Symbol(Symbol::LP, _) => {
+            parser.advance();
+            let mut names = Vec::new();
+            loop {
+                match parser.advance() {
+                    Token::Identifier(id, line) => names.push(LocatedIdentifier {
+                        id: id.clone(),
+                        line: *line,
+                    }),
+                    Token::Symbol(Symbol::RP, _) => break,
+                    token => {
+                        return Err(errors::unexpected_token_in(
+                            "imported names",
+                            token.to_string(),
+                            line,
+                        ))
+                    }
+                }
+            }
+            import.names = Some(names);
+        }
+        _ => {}
+    }
+
+    if !matches!(
+        parser.advance(),
+        Token::Symbol(Symbol::EOL, _) | Token::Symbol(Symbol::EOF, _)
+    ) {
+        return Err(errors::unexpected_token("end of line after import", line));
+    }
+    Ok(import)
+}
+
+/// Parses a function, whose signature may be left out to have its types inferred.
 fn parse_function(parser: &mut Parser) -> Result<Function, CompilerError> {
-    let signature = parse_signature(parser)?;
+    let signed = parser
+        .rest_of_line()
+        .iter()
+        .find(|t| matches!(t, Token::Symbol(Symbol::Assign | Symbol::Return, _)))
+        .is_none_or(|t| matches!(t, Token::Symbol(Symbol::Return, _)));
+    let signature = if signed {
+        Some(parse_signature(parser)?)
+    } else {
+        None
+    };
     let definition = parse_definition(parser)?;
 
     Ok(Function {
@@ -51,93 +157,487 @@ fn parse_signature(parser: &mut Parser) -> Result<Signature, CompilerError> {
     let mut parameters: Vec<Type> = Vec::new();
     let line = parser.location();
     while !matches!(parser.peek(), Token::Symbol(Symbol::Return, _)) {
-        match parser.advance() {
-            Token::Type(t, _) => parameters.push(t.clone()),
-            Token::Symbol(Symbol::LB, _) => {
-                // Parse list parameter
-                if let Token::Type(t, _) = parser.advance() {
-                    parameters.push(Type::List(Box::new(t.clone())));
+        let parameter = parse_type(parser, "function signature")?;
+        parameters.push(parameter);
+    }
+
+    parser.advance(); // Consume `->`
+
+    let returns = parse_type(parser, "function return type")?;
+
+    if !matches!(parser.advance(), Token::Symbol(Symbol::EOL, _)) {
+        return Err(errors::unexpected_token(
+            "function definition after signature",
+            parser.location(),
+        ));
+    }
 
-                    if !matches!(parser.advance(), Token::Symbol(Symbol::RB, _)) {
+    Ok(Signature {
+        parameters,
+        returns,
+        line,
+    })
+}
+
+/// Parses a type such as `Int`, `[[Str]]`, a declared `Point` or a type variable `a`.
+fn parse_type(parser: &mut Parser, context: &str) -> Result<Type, CompilerError> {
+    let line = parser.location();
+    match parser.advance() {
+        Token::Type(t, _) => Ok(t.clone()),
+        // `Maybe` takes the type that follows it
+        Token::Identifier(id, _) if id == "Maybe" => {
+            Ok(Type::Maybe(Box::new(parse_type(parser, context)?)))
+        }
+        Token::Identifier(id, _) if capitalised(id) => Ok(Type::Named(id.clone())),
+        Token::Identifier(id, _) => Ok(Type::Var(id.clone())),
+        Token::Symbol(Symbol::LB, _) => {
+            let element = parse_type(parser, context)?;
+            if !matches!(parser.advance(), Token::Symbol(Symbol::RB, _)) {
+                return Err(errors::unexpected_token(
+                    &format!("closing bracket in {}", context),
+                    line,
+                ));
+            }
+            Ok(Type::List(Box::new(element)))
+        }
+        Token::Symbol(Symbol::LP, _) => {
+            let mut elements = vec![parse_type(parser, context)?];
+            while matches!(parser.peek(), Token::Symbol(Symbol::Comma, _)) {
+                parser.advance();
+                elements.push(parse_type(parser, context)?);
+            }
+            if !matches!(parser.advance(), Token::Symbol(Symbol::RP, _)) {
+                return Err(errors::unexpected_token(
+                    &format!("closing parenthesis in {}", context),
+                    line,
+                ));
+            }
+            // `(Int)` is just `Int`
+            Ok(match elements.len() {
+                1 => elements.remove(0),
+                _ => Type::Tuple(elements),
+            })
+        }
+        Token::Symbol(Symbol::EOL, _) | Token::Symbol(Symbol::EOF, _) => Err(
+            errors::unexpected_token(&format!("type in {}", context), line),
+        ),
+        t => Err(errors::unexpected_token_in(context, t.to_string(), line)),
+    }
+}
+
+/// Suggests the closest built-in or declared type for a misspelled one.
+fn unknown_type(id: &Identifier, declared: &[Identifier], line: usize) -> CompilerError {
+    let types = Type::primitives()
+        .iter()
+        .map(|t| t.to_string())
+        .chain(declared.iter().cloned())
+        .collect::<Vec<_>>();
+    errors::unknown_type(id, utils::closest(id, types), line)
+}
+
+/// Checks that every type named in a program is declared in it or in `imported`, and that type
+/// variables only appear in signatures.
+pub fn check_types(program: &Program, imported: &[Identifier]) -> Result<(), CompilerError> {
+    let mut declared: Vec<Identifier> = imported.to_vec();
+    declared.extend(program.types.iter().map(|t| t.name.id.clone()));
+    declared.extend(program.aliases.iter().map(|a| a.name.id.clone()));
+
+    let check = |t: &Type, line: usize| match t.named().into_iter().find(|n| !declared.contains(n))
+    {
+        Some(name) => Err(unknown_type(name, &declared, line)),
+        None => Ok(()),
+    };
+    let check_declared = |t: &Type, line: usize| match t.variable() {
+        Some(name) => Err(unknown_type(name, &declared, line)),
+        None => check(t, line),
+    };
+    // `Some` and `None` are the constructors of the built-in `Maybe`
+    let builtin = ["Some".to_string(), "None".to_string()];
+    let mut constructors: Vec<&Identifier> = builtin.iter().collect();
+    for alias in program.aliases.iter() {
+        check_declared(&alias.aliased, alias.name.line)?;
+    }
+    for declaration in program.types.iter() {
+        match &declaration.definition {
+            TypeDefinition::Record(fields) => {
+                for field in fields.iter() {
+                    check_declared(&field.field_type, field.name.line)?;
+                }
+            }
+            TypeDefinition::Sum(variants) => {
+                for constructor in variants.iter() {
+                    let name = &constructor.name;
+                    if constructors.contains(&&name.id) {
                         return Err(CompilerError::Parser(
-                            "expected closing bracket in function signature parameters".into(),
-                            line,
+                            ParserError::DuplicateConstructor(name.id.clone()),
+                            name.line,
                         ));
                     }
-                } else {
-                    return Err(CompilerError::Parser(
-                        "expected type in list parameter in function signature".into(),
-                        line,
-                    ));
+                    constructors.push(&name.id);
+                    for t in constructor.parameters.iter() {
+                        check_declared(t, name.line)?;
+                    }
                 }
             }
-            t => {
-                return Err(CompilerError::Parser(
-                    format!("unexpected token in function signature: `{}`", t),
-                    line,
-                ));
+        }
+    }
+    for signature in program
+        .functions
+        .iter()
+        .filter_map(|f| f.signature.as_ref())
+    {
+        for t in signature.parameters.iter().chain([&signature.returns]) {
+            check(t, signature.line)?;
+        }
+    }
+    Ok(())
+}
+
+/// Replaces aliases in the types of a program, including those of list literals, with the types
+/// they stand for.
+pub fn expand_aliases(program: &mut Program, aliases: &[TypeAlias]) -> Result<(), CompilerError> {
+    let mut resolved = HashMap::new();
+    for alias in aliases.iter() {
+        resolve_alias(alias, aliases, &mut resolved, &mut Vec::new())?;
+    }
+    if resolved.is_empty() {
+        return Ok(());
+    }
+
+    let expand = |t: &mut Type| *t = t.expand(&resolved);
+    for declaration in program.types.iter_mut() {
+        match &mut declaration.definition {
+            TypeDefinition::Record(fields) => {
+                fields.iter_mut().for_each(|f| expand(&mut f.field_type));
+            }
+            TypeDefinition::Sum(constructors) => {
+                for constructor in constructors.iter_mut() {
+                    constructor.parameters.iter_mut().for_each(expand);
+                }
             }
         }
     }
+    for function in program.functions.iter_mut() {
+        if let Some(signature) = &mut function.signature {
+            signature.parameters.iter_mut().for_each(expand);
+            expand(&mut signature.returns);
+        }
+        expression_types(&mut function.definition.body, &expand);
+    }
+    for alias in program.aliases.iter_mut() {
+        expand(&mut alias.aliased);
+    }
+    Ok(())
+}
 
-    parser.advance(); // Consume `->`
+/// Expands an alias, and those it refers to, into `resolved`.
+fn resolve_alias<'a>(
+    alias: &'a TypeAlias,
+    aliases: &'a [TypeAlias],
+    resolved: &mut HashMap<Identifier, Type>,
+    visiting: &mut Vec<&'a Identifier>,
+) -> Result<Type, CompilerError> {
+    if let Some(t) = resolved.get(&alias.name.id) {
+        return Ok(t.clone());
+    }
+    if visiting.contains(&&alias.name.id) {
+        return Err(CompilerError::Parser(
+            ParserError::RecursiveAlias(alias.name.id.clone()),
+            alias.name.line,
+        ));
+    }
+    visiting.push(&alias.name.id);
+    let mut referenced = HashMap::new();
+    for name in alias.aliased.named() {
+        if let Some(other) = aliases.iter().find(|a| &a.name.id == name) {
+            let t = resolve_alias(other, aliases, resolved, visiting)?;
+            referenced.insert(name.clone(), t);
+        }
+    }
+    visiting.pop();
 
-    let returns = match parser.advance() {
-        Token::Type(t, _) => t.clone(),
-        Token::Symbol(Symbol::LB, _) => {
-            // Parse list parameter
-            if let Token::Type(t, _) = parser.advance() {
-                let ret = Type::List(Box::new(t.clone()));
+    let t = alias.aliased.expand(&referenced);
+    resolved.insert(alias.name.id.clone(), t.clone());
+    Ok(t)
+}
 
-                if !matches!(parser.advance(), Token::Symbol(Symbol::RB, _)) {
-                    return Err(CompilerError::Parser(
-                        "expected closing bracket in function return type".into(),
-                        line,
-                    ));
+/// Applies `f` to the type of every list literal in an expression.
+fn expression_types(expression: &mut Expression, f: &dyn Fn(&mut Type)) {
+    match expression {
+        Expression::Value(v) => value_types(v, f),
+        Expression::Call(Call::Operation(op)) => {
+            op.arguments.iter_mut().for_each(|a| argument_types(a, f));
+        }
+        Expression::Call(Call::FunctionCall(fc)) => {
+            fc.arguments.iter_mut().for_each(|a| argument_types(a, f));
+        }
+        Expression::Call(Call::Variant(v)) => {
+            v.arguments.iter_mut().for_each(|a| argument_types(a, f));
+        }
+        Expression::Call(Call::Pipe(p)) => expression_types(&mut p.left, f),
+        Expression::Call(Call::Construction(c)) => {
+            for (_, value) in c.fields.iter_mut() {
+                expression_types(value, f);
+            }
+        }
+        Expression::Call(Call::Access(a)) => argument_types(&mut a.record, f),
+        Expression::Call(Call::Tuple(t)) => {
+            for e in t.elements.iter_mut() {
+                expression_types(e, f);
+            }
+        }
+        Expression::Conditional(c) => {
+            expression_types(&mut c.condition, f);
+            expression_types(&mut c.then, f);
+            expression_types(&mut c.otherwise, f);
+        }
+        Expression::Case(c) => {
+            expression_types(&mut c.subject, f);
+            for arm in c.arms.iter_mut() {
+                expression_types(&mut arm.body, f);
+            }
+        }
+        Expression::Try(t) => {
+            expression_types(&mut t.body, f);
+            expression_types(&mut t.handler, f);
+        }
+        Expression::Raise(r) => expression_types(&mut r.message, f),
+        Expression::Comprehension(c) => {
+            expression_types(&mut c.element, f);
+            for clause in c.clauses.iter_mut() {
+                match clause {
+                    Clause::Generator { list, .. } => expression_types(list, f),
+                    Clause::Guard(condition) => expression_types(condition, f),
                 }
+            }
+        }
+        Expression::Builtin(_) => {}
+    }
+}
 
-                ret
-            } else {
-                return Err(CompilerError::Parser(
-                    "expected type in list parameter in function signature return type".into(),
-                    parser.location(),
-                ));
+fn argument_types(argument: &mut Argument, f: &dyn Fn(&mut Type)) {
+    match argument {
+        Argument::Value(v) => value_types(v, f),
+        Argument::Identifier(_) => {}
+        Argument::ParenExpression(e) => expression_types(e, f),
+    }
+}
+
+fn value_types(value: &mut Value, f: &dyn Fn(&mut Type)) {
+    if let Value::List(list) = value {
+        f(&mut list.list_type);
+        for e in list.elements.iter_mut() {
+            match e {
+                Element::Value(v) => value_types(v, f),
+                Element::Identifier(_) => {}
+                Element::Range(r) => {
+                    argument_types(&mut r.start, f);
+                    if let Some(next) = r.next.as_mut() {
+                        argument_types(next, f);
+                    }
+                    if let Some(end) = r.end.as_mut() {
+                        argument_types(end, f);
+                    }
+                }
             }
         }
-        _ => {
-            return Err(CompilerError::Parser(
-                "expected return type in function signature".into(),
-                parser.location(),
-            ));
+    }
+}
+
+/// Parses `type Name = Type`.
+fn parse_alias(parser: &mut Parser) -> Result<TypeAlias, CompilerError> {
+    let line = parser.location();
+    parser.advance(); // Consume `type`
+
+    let name = match parser.advance() {
+        Token::Identifier(id, line) if capitalised(id) && id != "Maybe" => LocatedIdentifier {
+            id: id.clone(),
+            line: *line,
+        },
+        token => {
+            return Err(errors::expected_token(
+                "capitalised type name",
+                token.to_string(),
+                line,
+            ))
         }
     };
+    if !matches!(parser.advance(), Token::Symbol(Symbol::Assign, _)) {
+        return Err(errors::unexpected_token("`=` after type name", line));
+    }
+    let aliased = parse_type(parser, "type alias")?;
 
-    if !matches!(parser.advance(), Token::Symbol(Symbol::EOL, _)) {
-        return Err(CompilerError::Parser(
-            "expected function definition after signature".into(),
+    if !matches!(
+        parser.advance(),
+        Token::Symbol(Symbol::EOL, _) | Token::Symbol(Symbol::EOF, _)
+    ) {
+        return Err(errors::unexpected_token(
+            "end of line after type alias",
             parser.location(),
         ));
     }
+    Ok(TypeAlias { name, aliased })
+}
 
-    Ok(Signature {
-        parameters,
-        returns,
-    })
+fn parse_data(parser: &mut Parser) -> Result<TypeDeclaration, CompilerError> {
+    let line = parser.location();
+    parser.advance(); // Consume `data`
+
+    let name = match parser.advance() {
+        Token::Identifier(id, line) if capitalised(id) => LocatedIdentifier {
+            id: id.clone(),
+            line: *line,
+        },
+        token => {
+            return Err(errors::expected_token(
+                "capitalised type name",
+                token.to_string(),
+                line,
+            ))
+        }
+    };
+    skip_newlines(parser); // `=` may start the next line
+    if !matches!(parser.advance(), Token::Symbol(Symbol::Assign, _)) {
+        return Err(errors::unexpected_token("`=` after type name", line));
+    }
+
+    let definition = match parser.peek() {
+        Token::Symbol(Symbol::LC, _) => TypeDefinition::Record(parse_fields(parser)?),
+        Token::Identifier(_, _) => TypeDefinition::Sum(parse_constructors(parser)?),
+        token => {
+            return Err(errors::expected_token(
+                "type definition",
+                token.to_string(),
+                line,
+            ))
+        }
+    };
+
+    if !matches!(
+        parser.advance(),
+        Token::Symbol(Symbol::EOL, _) | Token::Symbol(Symbol::EOF, _)
+    ) {
+        return Err(errors::unexpected_token(
+            "end of line after type definition",
+            parser.location(),
+        ));
+    }
+    Ok(TypeDeclaration { name, definition })
+}
+
+/// Parses the `{ field Type, ... }` of a record declaration.
+fn parse_fields(parser: &mut Parser) -> Result<Vec<Field>, CompilerError> {
+    parser.advance(); // Consume `{`
+    let mut fields: Vec<Field> = Vec::new();
+    loop {
+        skip_newlines(parser);
+        let name = match parser.advance() {
+            Token::Identifier(id, line) => LocatedIdentifier {
+                id: id.clone(),
+                line: *line,
+            },
+            Token::Symbol(Symbol::RC, _) if fields.is_empty() => break,
+            token => {
+                return Err(errors::expected_token(
+                    "field name",
+                    token.to_string(),
+                    parser.location(),
+                ))
+            }
+        };
+        if fields.iter().any(|f| f.name.id == name.id) {
+            return Err(CompilerError::Parser(
+                ParserError::DuplicateField(name.id),
+                name.line,
+            ));
+        }
+        let field_type = parse_type(parser, "record field")?;
+        fields.push(Field { name, field_type });
+
+        skip_newlines(parser);
+        match parser.advance() {
+            Token::Symbol(Symbol::Comma, _) => continue,
+            Token::Symbol(Symbol::RC, _) => break,
+            token => {
+                return Err(errors::expected_token(
+                    "`,` or `}` after record field",
+                    token.to_string(),
+                    parser.location(),
+                ))
+            }
+        }
+    }
+    Ok(fields)
+}
+
+/// Parses the `Constructor Type ... | ...` of a sum type declaration. Constructors may
+/// continue on following lines that start with `|`.
+fn parse_constructors(parser: &mut Parser) -> Result<Vec<Constructor>, CompilerError> {
+    let mut constructors: Vec<Constructor> = Vec::new();
+    loop {
+        let name = match parser.advance() {
+            Token::Identifier(id, line) if capitalised(id) => LocatedIdentifier {
+                id: id.clone(),
+                line: *line,
+            },
+            token => {
+                return Err(errors::expected_token(
+                    "capitalised constructor name",
+                    token.to_string(),
+                    parser.location(),
+                ))
+            }
+        };
+        if constructors.iter().any(|c| c.name.id == name.id) {
+            return Err(CompilerError::Parser(
+                ParserError::DuplicateConstructor(name.id),
+                name.line,
+            ));
+        }
+        let mut parameters: Vec<Type> = Vec::new();
+        while !matches!(
+            parser.peek(),
+            Token::Symbol(Symbol::Bar, _)
+                | Token::Symbol(Symbol::EOL, _)
+                | Token::Symbol(Symbol::EOF, _)
+        ) {
+            parameters.push(parse_type(parser, "constructor")?);
+        }
+        constructors.push(Constructor { name, parameters });
+
+        if !matches!(parser.peek_past_newlines(), Token::Symbol(Symbol::Bar, _)) {
+            break;
+        }
+        skip_newlines(parser);
+        parser.advance(); // Consume `|`
+    }
+    Ok(constructors)
+}
+
+/// Constructors and types are capitalised, functions and parameters are not.
+fn capitalised(id: &str) -> bool {
+    id.starts_with(|c: char| c.is_ascii_uppercase())
+}
+
+fn skip_newlines(parser: &mut Parser) {
+    while let &Token::Symbol(Symbol::EOL, _) = parser.peek() {
+        parser.advance();
+    }
 }
 
 fn parse_definition(parser: &mut Parser) -> Result<Definition, CompilerError> {
-    let name = match parser.advance() {
-        Token::Identifier(name, _) => Ok(name.clone()),
-        Token::Symbol(Symbol::EOL, _) => Err(CompilerError::Parser(
-            "expected definition name, found empty line".into(),
+    let (name, line) = match parser.advance() {
+        Token::Identifier(name, line) => Ok((name.clone(), *line)),
+        Token::Symbol(Symbol::EOL, _) => Err(errors::unexpected_token(
+            "definition name",
             parser.location() - 1,
         )),
-        token => Err(CompilerError::Parser(
-            format!("expected definition name, found `{}`", token),
+        token => Err(errors::expected_token(
+            "definition name",
+            token.to_string(),
             parser.location(),
         )),
     }?;
-    let line = parser.location();
     let parameters = parse_params(parser)?;
     let body = parse_expression(parser)?;
     let name = LocatedIdentifier { id: name, line };
@@ -151,115 +651,514 @@ fn parse_definition(parser: &mut Parser) -> Result<Definition, CompilerError> {
 fn parse_params(parser: &mut Parser) -> Result<Vec<LocatedIdentifier>, CompilerError> {
     let mut params: BTreeMap<Identifier, LocatedIdentifier> = BTreeMap::new();
     while !matches!(parser.peek(), Token::Symbol(Symbol::Assign, _)) {
-        if let Token::Identifier(l_id, _) = parser.advance() {
+        if let Token::Identifier(l_id, line) = parser.advance() {
             let l_id = LocatedIdentifier {
                 id: l_id.clone(),
-                line: parser.location(),
+                line: *line,
             };
             if params.contains_key(&l_id.id) {
                 return Err(CompilerError::Parser(
-                    format!("duplicate parameter name `{}`", l_id.id),
+                    ParserError::DuplicateParameter(l_id.id),
                     l_id.line,
                 ));
             }
             params.insert(l_id.id.clone(), l_id);
         } else {
-            return Err(CompilerError::Parser(
-                "expected identifier in definition parameters".into(),
+            return Err(errors::unexpected_token(
+                "identifier in definition parameters",
                 parser.location(),
             ));
         }
     }
-    parser.advance();
-    Ok(params.into_values().collect())
+    parser.advance();
+    Ok(params.into_values().collect())
+}
+
+fn parse_expression(parser: &mut Parser) -> Result<Expression, CompilerError> {
+    let expression = match parser.peek() {
+        Token::Value(_, _) => parse_value(parser),
+        // List of a type variable, e.g. `a [x y]`
+        Token::Identifier(id, _)
+            if !capitalised(id) && matches!(parser.peek_next(), Token::Symbol(Symbol::LB, _)) =>
+        {
+            parse_list(parser)
+        }
+        Token::Identifier(_, _) => parse_call(parser),
+        Token::Operator(_, _) => parse_operation(parser),
+        Token::Conditional(Conditional::If, _) => parse_conditional(parser),
+        Token::Symbol(Symbol::LB, _) if is_comprehension(parser) => parse_comprehension(parser),
+        Token::Type(_, _) | Token::Symbol(Symbol::LB, _) => parse_list(parser),
+        Token::Symbol(Symbol::Dot, _) => parse_access(parser),
+        Token::Keyword(Keyword::Case, _) => parse_case(parser),
+        Token::Keyword(Keyword::Try, _) => parse_try(parser),
+        Token::Keyword(Keyword::Raise, _) => parse_raise(parser),
+        Token::Symbol(Symbol::LP, _) => match parse_paren_expression(parser)? {
+            Argument::ParenExpression(expression) => Ok(expression),
+            _ => unreachable!(),
+        },
+        Token::Symbol(Symbol::Backtick, _) => parse_infix(parser).map(|infix| match infix {
+            Argument::Value(value) => Expression::Value(value),
+            Argument::Identifier(name) => Expression::Call(Call::FunctionCall(FunctionCall {
+                line: name.line,
+                name,
+                arguments: Vec::new(),
+            })),
+            Argument::ParenExpression(expression) => expression,
+        }),
+        token => Err(errors::expected_token(
+            "expression",
+            token.to_string(),
+            parser.location(),
+        )),
+    };
+    if let Token::Symbol(Symbol::Pipe, _) = parser.peek() {
+        parse_pipe(parser, expression?)
+    } else {
+        expression
+    }
+}
+
+fn parse_pipe(parser: &mut Parser, expression: Expression) -> Result<Expression, CompilerError> {
+    if !matches!(parser.advance(), Token::Symbol(Symbol::Pipe, _)) {
+        unreachable!("expected `>>`");
+    }
+    let pipe = match parser.advance() {
+        Token::Identifier(i, l) => Pipe {
+            left: Box::new(expression),
+            right: i.clone(),
+            line: *l,
+        },
+        token => {
+            return Err(errors::expected_token(
+                "identifier after pipe",
+                token.to_string(),
+                parser.location(),
+            ))
+        }
+    };
+    Ok(Expression::Call(Call::Pipe(pipe)))
+}
+
+fn parse_value(parser: &mut Parser) -> Result<Expression, CompilerError> {
+    match parser.advance() {
+        Token::Value(value, _) => Ok(Expression::Value(value.clone())),
+        _ => unreachable!(),
+    }
+}
+
+fn parse_call(parser: &mut Parser) -> Result<Expression, CompilerError> {
+    let (name, line) = match parser.advance() {
+        Token::Identifier(name, line) => (name.clone(), *line),
+        _ => unreachable!(),
+    };
+    let name = LocatedIdentifier { id: name, line };
+    if let Token::Symbol(Symbol::LC, _) = parser.peek() {
+        return parse_construction(parser, name);
+    }
+    let arguments: Vec<Argument> = parse_arguments(parser)?;
+    if capitalised(&name.id) {
+        return Ok(Expression::Call(Call::Variant(VariantCall {
+            name,
+            arguments,
+            line,
+        })));
+    }
+    Ok(Expression::Call(Call::FunctionCall(FunctionCall {
+        name,
+        arguments,
+        line,
+    })))
+}
+
+fn parse_construction(
+    parser: &mut Parser,
+    name: LocatedIdentifier,
+) -> Result<Expression, CompilerError> {
+    let line = name.line;
+    parser.advance(); // Consume `{`
+    let mut fields: Vec<(LocatedIdentifier, Expression)> = Vec::new();
+    loop {
+        skip_newlines(parser);
+        let field = match parser.advance() {
+            Token::Identifier(id, line) => LocatedIdentifier {
+                id: id.clone(),
+                line: *line,
+            },
+            Token::Symbol(Symbol::RC, _) if fields.is_empty() => break,
+            token => {
+                return Err(errors::expected_token(
+                    "field name",
+                    token.to_string(),
+                    parser.location(),
+                ))
+            }
+        };
+        if fields.iter().any(|(f, _)| f.id == field.id) {
+            return Err(CompilerError::Parser(
+                ParserError::DuplicateField(field.id),
+                field.line,
+            ));
+        }
+        let value = parse_expression(parser)?;
+        fields.push((field, value));
+
+        skip_newlines(parser);
+        match parser.advance() {
+            Token::Symbol(Symbol::Comma, _) => continue,
+            Token::Symbol(Symbol::RC, _) => break,
+            token => {
+                return Err(errors::expected_token(
+                    "`,` or `}` after record field",
+                    token.to_string(),
+                    parser.location(),
+                ))
+            }
+        }
+    }
+    Ok(Expression::Call(Call::Construction(Construction {
+        name,
+        fields,
+        line,
+    })))
+}
+
+fn parse_access(parser: &mut Parser) -> Result<Expression, CompilerError> {
+    let line = parser.location();
+    parser.advance(); // Consume `.`
+    let field = match parser.advance() {
+        Token::Identifier(id, _) => id.clone(),
+        token => {
+            return Err(errors::expected_token(
+                "field name",
+                token.to_string(),
+                line,
+            ))
+        }
+    };
+    let mut arguments = parse_arguments(parser)?;
+    if arguments.len() != 1 {
+        return Err(errors::unexpected_token(
+            "a single record after field",
+            line,
+        ));
+    }
+    Ok(Expression::Call(Call::Access(Access {
+        field,
+        record: Box::new(arguments.remove(0)),
+        line,
+    })))
+}
+
+fn parse_operation(parser: &mut Parser) -> Result<Expression, CompilerError> {
+    let line = parser.location();
+    let operator = match parser.advance() {
+        Token::Operator(operator, _) => operator.clone(),
+        _ => unreachable!(),
+    };
+    let arguments: Vec<Argument> = parse_arguments(parser)?;
+    Ok(Expression::Call(Call::Operation(Operation {
+        operator,
+        arguments,
+        line,
+    })))
+}
+
+/// Parses an infix expression between backticks, such as `` `a - b * (a / b)` ``, into the same
+/// operations as its prefix form `- a (* b (/ a b))`.
+fn parse_infix(parser: &mut Parser) -> Result<Argument, CompilerError> {
+    let line = parser.location();
+    parser.advance(); // Consume opening backtick
+    let infix = parse_infix_operation(parser, 0)?;
+    skip_newlines(parser);
+    if !matches!(parser.advance(), Token::Symbol(Symbol::Backtick, _)) {
+        return Err(errors::unexpected_token("closing backtick", line));
+    }
+    Ok(infix)
+}
+
+/// Precedence of an infix operator, from `||` binding the loosest to `*` and `/` the tightest.
+fn precedence(operator: &Operator) -> Option<u8> {
+    match operator {
+        Operator::Or => Some(1),
+        Operator::And => Some(2),
+        Operator::Eq | Operator::Neq => Some(3),
+        Operator::Lt | Operator::Gt | Operator::Lte | Operator::Gte => Some(4),
+        Operator::Add | Operator::Sub => Some(5),
+        Operator::Mul | Operator::Div => Some(6),
+        Operator::Not => None,
+    }
+}
+
+/// Parses operands joined by operators of at least `minimum` precedence, by precedence climbing.
+/// Operators of equal precedence associate to the left.
+fn parse_infix_operation(parser: &mut Parser, minimum: u8) -> Result<Argument, CompilerError> {
+    let mut left = parse_infix_operand(parser)?;
+    let mut chained = false;
+    loop {
+        skip_newlines(parser);
+        let (operator, line, precedence) = match parser.peek() {
+            Token::Operator(operator, line) => match precedence(operator) {
+                Some(precedence) if precedence >= minimum => (operator.clone(), *line, precedence),
+                _ => break,
+            },
+            _ => break,
+        };
+        parser.advance();
+        let right = parse_infix_operation(parser, precedence + 1)?;
+
+        // A chain of the same arithmetic operator is one operation, as in `+ a b c`
+        match &mut left {
+            Argument::ParenExpression(Expression::Call(Call::Operation(operation)))
+                if chained
+                    && operation.operator == operator
+                    && operator.operator_type() == OperatorType::Binary =>
+            {
+                operation.arguments.push(right)
+            }
+            _ => {
+                left = Argument::ParenExpression(Expression::Call(Call::Operation(Operation {
+                    operator,
+                    arguments: vec![left, right],
+                    line,
+                })))
+            }
+        }
+        chained = true;
+    }
+    Ok(left)
+}
+
+/// Parses an operand of an infix operator, which may be negated or a function applied to
+/// arguments, as in `` `- x + f y 1` ``.
+fn parse_infix_operand(parser: &mut Parser) -> Result<Argument, CompilerError> {
+    skip_newlines(parser);
+    let line = parser.location();
+    match parser.peek() {
+        Token::Operator(Operator::Sub | Operator::Not, _) => {
+            let operator = match parser.advance() {
+                Token::Operator(operator, _) => operator.clone(),
+                _ => unreachable!(),
+            };
+            let operand = parse_infix_operand(parser)?;
+            Ok(Argument::ParenExpression(Expression::Call(
+                Call::Operation(Operation {
+                    operator,
+                    arguments: vec![operand],
+                    line,
+                }),
+            )))
+        }
+        Token::Symbol(Symbol::Dot, _) => {
+            parser.advance(); // Consume `.`
+            let field = match parser.advance() {
+                Token::Identifier(id, _) => id.clone(),
+                token => {
+                    return Err(errors::expected_token(
+                        "field name",
+                        token.to_string(),
+                        line,
+                    ))
+                }
+            };
+            let record = parse_infix_atom(parser)?
+                .ok_or_else(|| errors::unexpected_token("a record after field", line))?;
+            Ok(Argument::ParenExpression(Expression::Call(Call::Access(
+                Access {
+                    field,
+                    record: Box::new(record),
+                    line,
+                },
+            ))))
+        }
+        Token::Identifier(id, _)
+            if capitalised(id) || !matches!(parser.peek_next(), Token::Symbol(Symbol::LB, _)) =>
+        {
+            let name = match parser.advance() {
+                Token::Identifier(id, _) => LocatedIdentifier {
+                    id: id.clone(),
+                    line,
+                },
+                _ => unreachable!(),
+            };
+            let mut arguments = Vec::new();
+            while let Some(argument) = parse_infix_atom(parser)? {
+                arguments.push(argument);
+            }
+            let call = match (arguments.is_empty(), capitalised(&name.id)) {
+                (true, false) => return Ok(Argument::Identifier(name)),
+                (_, true) => Call::Variant(VariantCall {
+                    name,
+                    arguments,
+                    line,
+                }),
+                (false, false) => Call::FunctionCall(FunctionCall {
+                    name,
+                    arguments,
+                    line,
+                }),
+            };
+            Ok(Argument::ParenExpression(Expression::Call(call)))
+        }
+        token => {
+            let token = token.to_string();
+            parse_infix_atom(parser)?.ok_or_else(|| errors::expected_token("operand", token, line))
+        }
+    }
 }
 
-fn parse_expression(parser: &mut Parser) -> Result<Expression, CompilerError> {
-    let expression = match parser.peek() {
-        Token::Value(_, _) => parse_value(parser),
-        Token::Identifier(_, _) => parse_call(parser),
-        Token::Operator(_, _) => parse_operation(parser),
-        Token::Conditional(Conditional::If, _) => parse_conditional(parser),
-        Token::Type(_, _) => parse_list(parser),
-        token => Err(CompilerError::Parser(
-            format!("expected expression, found {}", token),
-            parser.location(),
-        )),
+/// Parses an argument of a function applied in an infix expression: a value, name, list or
+/// parenthesised infix expression.
+fn parse_infix_atom(parser: &mut Parser) -> Result<Option<Argument>, CompilerError> {
+    let line = parser.location();
+    let atom = match parser.peek() {
+        Token::Value(_, _) => match parser.advance() {
+            Token::Value(value, _) => Argument::Value(value.clone()),
+            _ => unreachable!(),
+        },
+        Token::Identifier(id, _)
+            if !capitalised(id) && matches!(parser.peek_next(), Token::Symbol(Symbol::LB, _)) =>
+        {
+            match parse_list(parser)? {
+                Expression::Value(value) => Argument::Value(value),
+                _ => unreachable!(),
+            }
+        }
+        Token::Type(_, _) | Token::Symbol(Symbol::LB, _) => match parse_list(parser)? {
+            Expression::Value(value) => Argument::Value(value),
+            _ => unreachable!(),
+        },
+        Token::Identifier(id, _) => {
+            let id = LocatedIdentifier {
+                id: id.clone(),
+                line,
+            };
+            parser.advance();
+            match capitalised(&id.id) {
+                // Constructors without a payload, e.g. `None`
+                true => Argument::ParenExpression(Expression::Call(Call::Variant(VariantCall {
+                    name: id,
+                    arguments: Vec::new(),
+                    line,
+                }))),
+                false => Argument::Identifier(id),
+            }
+        }
+        Token::Symbol(Symbol::LP, _) => {
+            parser.advance(); // Consume `(`
+            let inner = parse_infix_operation(parser, 0)?;
+            skip_newlines(parser);
+            if !matches!(parser.advance(), Token::Symbol(Symbol::RP, _)) {
+                return Err(errors::unexpected_token("closing parenthesis", line));
+            }
+            inner
+        }
+        _ => return Ok(None),
     };
-    if let Token::Symbol(Symbol::Pipe, _) = parser.peek() {
-        parse_pipe(parser, expression?)
-    } else {
-        expression
-    }
+    Ok(Some(atom))
 }
 
-fn parse_pipe(parser: &mut Parser, expression: Expression) -> Result<Expression, CompilerError> {
-    if !matches!(parser.advance(), Token::Symbol(Symbol::Pipe, _)) {
-        unreachable!("expected `>>`");
+/// Whether the brackets starting at the next token hold a comprehension, with a `|` inside them.
+fn is_comprehension(parser: &Parser) -> bool {
+    let mut depth = 0;
+    for token in parser.rest_of_line() {
+        match token {
+            Token::Symbol(Symbol::LB | Symbol::LP, _) => depth += 1,
+            Token::Symbol(Symbol::RB | Symbol::RP, _) => depth -= 1,
+            Token::Symbol(Symbol::Bar, _) if depth == 1 => return true,
+            _ => {}
+        }
+        if depth == 0 {
+            return false;
+        }
     }
-    let pipe = match parser.advance() {
-        Token::Identifier(i, l) => Pipe {
-            left: Box::new(expression),
-            right: i.clone(),
-            line: *l,
-        },
+    false
+}
+
+/// Parses `[ expression | clause, ... ]`, where each clause is a generator `name <- list` or a
+/// condition.
+fn parse_comprehension(parser: &mut Parser) -> Result<Expression, CompilerError> {
+    let line = parser.location();
+    parser.advance(); // Consume [
+    let element = parse_expression(parser)?;
+    match parser.advance() {
+        Token::Symbol(Symbol::Bar, _) => {}
         token => {
-            return Err(CompilerError::Parser(
-                format!("expected identifier after pipe, found `{}`", token),
+            return Err(errors::expected_token(
+                "'|' in list comprehension",
+                token.to_string(),
                 parser.location(),
             ))
         }
-    };
-    Ok(Expression::Call(Call::Pipe(pipe)))
-}
+    }
 
-fn parse_value(parser: &mut Parser) -> Result<Expression, CompilerError> {
-    match parser.advance() {
-        Token::Value(value, _) => Ok(Expression::Value(value.clone())),
-        _ => unreachable!(),
+    let mut clauses = Vec::new();
+    loop {
+        let clause = match (parser.peek(), parser.peek_next()) {
+            (Token::Identifier(id, line), Token::Symbol(Symbol::Draw, _)) => {
+                let name = LocatedIdentifier {
+                    id: id.clone(),
+                    line: *line,
+                };
+                parser.advance();
+                parser.advance(); // Consume <-
+                let list = parse_generator_list(parser)?;
+                Clause::Generator { name, list }
+            }
+            _ => Clause::Guard(parse_expression(parser)?),
+        };
+        clauses.push(clause);
+
+        match parser.advance() {
+            Token::Symbol(Symbol::Comma, _) => {}
+            Token::Symbol(Symbol::RB, _) => break,
+            token => {
+                return Err(errors::expected_token(
+                    "',' or closing bracket",
+                    token.to_string(),
+                    parser.location(),
+                ))
+            }
+        }
     }
-}
 
-fn parse_call(parser: &mut Parser) -> Result<Expression, CompilerError> {
-    let name = match parser.advance() {
-        Token::Identifier(name, _) => name.clone(),
-        _ => unreachable!(),
-    };
-    let line = parser.location();
-    let name = LocatedIdentifier { id: name, line };
-    let arguments: Vec<Argument> = parse_arguments(parser)?;
-    Ok(Expression::Call(Call::FunctionCall(FunctionCall {
-        name,
-        arguments,
+    Ok(Expression::Comprehension(Comprehension {
+        element: Box::new(element),
+        clauses,
         line,
-    })))
+    }))
 }
 
-fn parse_operation(parser: &mut Parser) -> Result<Expression, CompilerError> {
-    let line = parser.location();
-    let operator = match parser.advance() {
-        Token::Operator(operator, _) => operator.clone(),
-        _ => unreachable!(),
-    };
-    let arguments: Vec<Argument> = parse_arguments(parser)?;
-    Ok(Expression::Call(Call::Operation(Operation {
-        operator,
-        arguments,
-        line,
-    })))
+/// Parses the list of a generator, which may leave out its type if it starts with a value, as in
+/// `x <- [1 .. 10]`.
+fn parse_generator_list(parser: &mut Parser) -> Result<Expression, CompilerError> {
+    if let (Token::Symbol(Symbol::LB, _), Token::Value(value, _)) =
+        (parser.peek(), parser.peek_next())
+    {
+        let list_type = value.get_type();
+        return parse_list_literal(parser, list_type);
+    }
+    parse_expression(parser)
 }
 
+/// Parses a list literal such as `Int [1 2]`, `a [x y]` or `[Int] [[1 2] [3]]`.
 fn parse_list(parser: &mut Parser) -> Result<Expression, CompilerError> {
-    let list_type = match parser.advance() {
-        Token::Type(t, _) => t.clone(),
+    let list_type = match parser.peek() {
+        Token::Type(_, _) | Token::Identifier(_, _) | Token::Symbol(Symbol::LB, _) => {
+            parse_type(parser, "list expression")?
+        }
         _ => {
-            return Err(CompilerError::Parser(
-                "expected type in list expression".into(),
+            return Err(errors::unexpected_token(
+                "type in list expression",
                 parser.location(),
             ));
         }
     };
 
+    parse_list_literal(parser, list_type)
+}
+
+/// Parses the elements of a list literal of a type, from its opening bracket.
+fn parse_list_literal(parser: &mut Parser, list_type: Type) -> Result<Expression, CompilerError> {
     if !matches!(parser.advance(), Token::Symbol(Symbol::LB, _)) {
         return Err(errors::unexpected_token(
             "opening bracket",
@@ -296,107 +1195,144 @@ fn parse_list_body(
     list_type: &Type,
     elements: &mut Vec<Element>,
 ) -> Result<(), CompilerError> {
-    let first_element = match parser.advance() {
-        Token::Value(value, _) => Element::Value(value.clone()),
-        Token::Identifier(id, _) => Element::Identifier(id.clone()),
-        token => {
-            return Err(CompilerError::Parser(
-                format!("unexpected token in list: `{}`", token),
-                parser.location(),
-            ));
-        }
+    // A range starts with a bound followed by `,` or `..`, which may be parenthesised or negative
+    if matches!(
+        parser.peek(),
+        Token::Symbol(Symbol::LP | Symbol::Backtick, _) | Token::Operator(Operator::Sub, _)
+    ) || matches!(
+        parser.peek_next(),
+        Token::Symbol(Symbol::Comma | Symbol::Range, _)
+    ) {
+        elements.push(parse_range(parser, list_type)?);
+    } else {
+        parse_list_elements(parser, list_type, elements)?;
+    }
+
+    if !matches!(parser.advance(), Token::Symbol(Symbol::RB, _)) {
+        return Err(errors::unexpected_token(
+            "closing bracket",
+            parser.location(),
+        ));
+    }
+    Ok(())
+}
+
+/// Parses `start .. end` or `start, next .. end`, where the end may be left out.
+fn parse_range(parser: &mut Parser, list_type: &Type) -> Result<Element, CompilerError> {
+    if matches!(
+        list_type,
+        Type::Bool | Type::Void | Type::List(_) | Type::Tuple(_) | Type::Maybe(_)
+    ) {
+        return Err(errors::unrangeable_type(list_type, parser.location()));
+    }
+
+    let start = parse_range_bound(parser)?;
+    let next = if matches!(parser.peek(), Token::Symbol(Symbol::Comma, _)) {
+        parser.advance(); // Consume ,
+        Some(parse_range_bound(parser)?)
+    } else {
+        None
     };
 
-    // Parse range
-    if matches!(parser.peek(), Token::Symbol(Symbol::Range, _)) {
-        if *list_type != Type::Int {
-            return Err(CompilerError::Parser(
-                "range syntax is only allowed for lists of integers".into(),
+    match parser.advance() {
+        Token::Symbol(Symbol::Range, _) => {}
+        token => {
+            return Err(errors::expected_token(
+                "'..'",
+                token.to_string(),
                 parser.location(),
             ));
         }
+    }
+    let end = if matches!(parser.peek(), Token::Symbol(Symbol::RB, _)) {
+        None
+    } else {
+        Some(parse_range_bound(parser)?)
+    };
 
-        let start_value = if let Element::Value(Value::Integer(n)) = first_element {
-            n
-        } else {
-            return Err(CompilerError::Parser(
-                "range start must be an integer".into(),
-                parser.location(),
-            ));
-        };
-
-        parser.advance(); // Consume ..
+    Ok(Element::Range(Box::new(Range { start, next, end })))
+}
 
-        let end_value = match parser.advance() {
-            Token::Value(Value::Integer(n), _) => n,
-            token => {
-                return Err(CompilerError::Parser(
-                    format!("expected integer after '..', found `{}`", token),
+fn parse_range_bound(parser: &mut Parser) -> Result<Argument, CompilerError> {
+    match parser.peek() {
+        Token::Symbol(Symbol::LP, _) => parse_paren_expression(parser),
+        Token::Symbol(Symbol::Backtick, _) => parse_infix(parser),
+        // A bound cannot be an operation, so `-` makes the number after it negative
+        Token::Operator(Operator::Sub, _) => {
+            parser.advance();
+            match parser.advance() {
+                Token::Value(Value::Integer(i), _) => Ok(Argument::Value(Value::Integer(-i))),
+                Token::Value(Value::BigInteger(i), _) => {
+                    Ok(Argument::Value(Value::from(-i.clone())))
+                }
+                Token::Value(Value::Fractional(f), _) => Ok(Argument::Value(Value::Fractional(-f))),
+                Token::Value(Value::Decimal(d), _) => {
+                    Ok(Argument::Value(Value::Decimal(-d.clone())))
+                }
+                token => Err(errors::expected_token(
+                    "number after `-` in range bound",
+                    token.to_string(),
                     parser.location(),
-                ));
-            }
-        };
-
-        if start_value <= *end_value {
-            for n in start_value..=*end_value {
-                elements.push(Element::Value(Value::Integer(n)));
+                )),
             }
-        } else {
-            for n in (*end_value..=start_value).rev() {
-                elements.push(Element::Value(Value::Integer(n)));
-            }
-        }
-
-        if !matches!(parser.advance(), Token::Symbol(Symbol::RB, _)) {
-            return Err(errors::unexpected_token(
-                "closing bracket",
-                parser.location(),
-            ));
         }
-    } else {
-        // Not a range
-        elements.push(first_element);
-        parse_list_elements(parser, elements)?;
-
-        if !matches!(parser.advance(), Token::Symbol(Symbol::RB, _)) {
-            return Err(errors::unexpected_token(
-                "closing bracket",
+        _ => match parser.advance() {
+            Token::Value(value, _) => Ok(Argument::Value(value.clone())),
+            Token::Identifier(id, line) => Ok(Argument::Identifier(LocatedIdentifier {
+                id: id.clone(),
+                line: *line,
+            })),
+            token => Err(errors::expected_token(
+                "range bound",
+                token.to_string(),
                 parser.location(),
-            ));
-        }
+            )),
+        },
     }
-
-    Ok(())
 }
 
 fn parse_list_elements(
     parser: &mut Parser,
+    list_type: &Type,
     elements: &mut Vec<Element>,
 ) -> Result<(), CompilerError> {
-    loop {
-        if matches!(parser.peek(), Token::Symbol(Symbol::RB, _)) {
-            break;
-        }
+    while !matches!(parser.peek(), Token::Symbol(Symbol::RB, _)) {
+        elements.push(parse_element(parser, list_type)?);
+    }
+    Ok(())
+}
 
-        let element = match parser.advance() {
-            Token::Value(value, _) => Element::Value(value.clone()),
-            Token::Identifier(id, _) => Element::Identifier(id.clone()),
-            Token::Symbol(Symbol::EOF, _) => {
-                return Err(errors::unexpected_token(
-                    "closing bracket",
-                    parser.location(),
-                ));
-            }
-            token => {
-                return Err(CompilerError::Parser(
-                    format!("unexpected token in list: `{}`", token),
-                    parser.location(),
-                ));
+/// Parses an element of a list literal, which is itself a list if it starts with `[`.
+fn parse_element(parser: &mut Parser, list_type: &Type) -> Result<Element, CompilerError> {
+    match (parser.advance(), list_type) {
+        (Token::Value(value, _), _) => Ok(Element::Value(value.clone())),
+        (Token::Identifier(id, _), _) => Ok(Element::Identifier(id.clone())),
+        // Nested lists take their type from the enclosing one, e.g. `[Int] [[1 2] [3]]`
+        (Token::Symbol(Symbol::LB, _), Type::List(element_type)) => {
+            let line = parser.location();
+            let mut elements: Vec<Element> = Vec::new();
+            if matches!(parser.peek(), Token::Symbol(Symbol::RB, _)) {
+                parser.advance(); // Consume closing bracket
+            } else {
+                parse_list_body(parser, element_type, &mut elements)?;
             }
-        };
-        elements.push(element);
+            let list = List {
+                list_type: (**element_type).clone(),
+                elements,
+                line,
+            };
+            Ok(Element::Value(Value::List(list)))
+        }
+        (Token::Symbol(Symbol::EOF, _), _) => Err(errors::unexpected_token(
+            "closing bracket",
+            parser.location(),
+        )),
+        (token, _) => Err(errors::unexpected_token_in(
+            "list",
+            token.to_string(),
+            parser.location(),
+        )),
     }
-    Ok(())
 }
 
 fn parse_arguments(parser: &mut Parser) -> Result<Vec<Argument>, CompilerError> {
@@ -408,8 +1344,9 @@ fn parse_arguments(parser: &mut Parser) -> Result<Vec<Argument>, CompilerError>
                 if op == &Operator::Sub || op == &Operator::Add {
                     parse_operation(parser).map(Argument::ParenExpression)
                 } else {
-                    Err(CompilerError::Parser(
-                        format!("unexpected token in function arguments: `{}`", op),
+                    Err(errors::unexpected_token_in(
+                        "function arguments",
+                        op.to_string(),
                         parser.location(),
                     ))
                 }
@@ -418,7 +1355,27 @@ fn parse_arguments(parser: &mut Parser) -> Result<Vec<Argument>, CompilerError>
                 Token::Value(value, _) => Ok(Argument::Value(value.clone())),
                 _ => unreachable!(),
             },
+            Token::Identifier(id, _)
+                if !capitalised(id)
+                    && matches!(parser.peek_next(), Token::Symbol(Symbol::LB, _)) =>
+            {
+                match parse_list(parser)? {
+                    Expression::Value(value) => Ok(Argument::Value(value)),
+                    _ => unreachable!(),
+                }
+            }
             Token::Identifier(_, _) => match parser.advance() {
+                // Constructors without a payload, e.g. `Nothing`
+                Token::Identifier(id, _) if capitalised(id) => Ok(Argument::ParenExpression(
+                    Expression::Call(Call::Variant(VariantCall {
+                        name: LocatedIdentifier {
+                            id: id.clone(),
+                            line,
+                        },
+                        arguments: Vec::new(),
+                        line,
+                    })),
+                )),
                 Token::Identifier(id, _) => {
                     let l_id = LocatedIdentifier {
                         id: id.clone(),
@@ -429,14 +1386,22 @@ fn parse_arguments(parser: &mut Parser) -> Result<Vec<Argument>, CompilerError>
                 _ => unreachable!(),
             },
             Token::Symbol(Symbol::LP, _) => parse_paren_expression(parser),
+            Token::Symbol(Symbol::Backtick, _) => parse_infix(parser),
             Token::Symbol(Symbol::RP, _) => break,
             Token::Symbol(Symbol::Pipe, _) => break,
-            Token::Type(_, _) => {
+            Token::Symbol(Symbol::Comma, _) | Token::Symbol(Symbol::RC, _) => break,
+            Token::Symbol(Symbol::Bar, _) | Token::Keyword(Keyword::Of, _) => break,
+            Token::Keyword(Keyword::Catch, _) => break,
+            Token::Symbol(Symbol::RB, _) => break,
+            Token::Symbol(Symbol::LB, _) if is_comprehension(parser) => {
+                parse_comprehension(parser).map(Argument::ParenExpression)
+            }
+            Token::Type(_, _) | Token::Symbol(Symbol::LB, _) => {
                 if let Ok(Expression::Value(Value::List(list))) = parse_list(parser) {
                     Ok(Argument::Value(Value::List(list)))
                 } else {
-                    Err(CompilerError::Parser(
-                        "expected list expression".into(),
+                    Err(errors::unexpected_token(
+                        "list expression",
                         parser.location(),
                     ))
                 }
@@ -447,8 +1412,9 @@ fn parse_arguments(parser: &mut Parser) -> Result<Vec<Argument>, CompilerError>
             }
             Token::Conditional(Conditional::Then, _) => break,
             Token::Conditional(Conditional::Else, _) => break,
-            token => Err(CompilerError::Parser(
-                format!("unexpected token in function arguments: `{}`", token),
+            token => Err(errors::unexpected_token_in(
+                "function arguments",
+                token.to_string(),
                 parser.location(),
             )),
         }?;
@@ -478,9 +1444,7 @@ fn parse_conditional(parser: &mut Parser) -> Result<Expression, CompilerError> {
     if let &Token::Symbol(Symbol::EOL, _) = parser.peek() {
         parser.advance();
     }
-    let p = parser.advance();
-    if !matches!(p, Token::Conditional(Conditional::Else, _)) {
-        println!("{:?}", p);
+    if !matches!(parser.advance(), Token::Conditional(Conditional::Else, _)) {
         return Err(errors::unexpected_token("else", parser.location()));
     }
     let otherwise = parse_expression(parser)?;
@@ -494,16 +1458,198 @@ fn parse_conditional(parser: &mut Parser) -> Result<Expression, CompilerError> {
     Ok(Expression::Conditional(conditional))
 }
 
+fn parse_case(parser: &mut Parser) -> Result<Expression, CompilerError> {
+    let line = parser.location();
+    parser.advance(); // Consume `case`
+    let subject = parse_expression(parser)?;
+    if !matches!(parser.advance(), Token::Keyword(Keyword::Of, _)) {
+        return Err(errors::unexpected_token("`of` after case subject", line));
+    }
+
+    // Arms are separated by `|`, which may start a new line and is optional before the first
+    let mut arms: Vec<Arm> = Vec::new();
+    if matches!(parser.peek_past_newlines(), Token::Symbol(Symbol::Bar, _)) {
+        skip_newlines(parser);
+        parser.advance();
+    }
+    loop {
+        arms.push(parse_arm(parser)?);
+        if !matches!(parser.peek_past_newlines(), Token::Symbol(Symbol::Bar, _)) {
+            break;
+        }
+        skip_newlines(parser);
+        parser.advance(); // Consume `|`
+    }
+
+    Ok(Expression::Case(Case {
+        subject: Box::new(subject),
+        arms,
+        line,
+    }))
+}
+
+fn parse_arm(parser: &mut Parser) -> Result<Arm, CompilerError> {
+    let line = parser.location();
+    let pattern = match parser.advance() {
+        Token::Symbol(Symbol::Wildcard, _) => Pattern::Wildcard,
+        Token::Symbol(Symbol::LP, _) => Pattern::Tuple(parse_tuple_pattern(parser)?),
+        Token::Identifier(id, line) if capitalised(id) => {
+            let name = LocatedIdentifier {
+                id: id.clone(),
+                line: *line,
+            };
+            let mut bindings: Vec<LocatedIdentifier> = Vec::new();
+            while !matches!(parser.peek(), Token::Symbol(Symbol::Return, _)) {
+                let binding = parse_binding(parser, "binding or `->` in pattern")?;
+                if binding.id != "_" && bindings.iter().any(|b| b.id == binding.id) {
+                    return Err(CompilerError::Parser(
+                        ParserError::DuplicateParameter(binding.id),
+                        binding.line,
+                    ));
+                }
+                bindings.push(binding);
+            }
+            Pattern::Variant { name, bindings }
+        }
+        token => {
+            return Err(errors::expected_token(
+                "constructor, tuple or `_` in pattern",
+                token.to_string(),
+                line,
+            ))
+        }
+    };
+    if !matches!(parser.advance(), Token::Symbol(Symbol::Return, _)) {
+        return Err(errors::unexpected_token("`->` after pattern", line));
+    }
+    let body = parse_expression(parser)?;
+    Ok(Arm {
+        pattern,
+        body,
+        line,
+    })
+}
+
+/// Parses the bindings of a tuple pattern, after its opening parenthesis.
+fn parse_tuple_pattern(parser: &mut Parser) -> Result<Vec<LocatedIdentifier>, CompilerError> {
+    let mut bindings: Vec<LocatedIdentifier> = Vec::new();
+    loop {
+        let binding = parse_binding(parser, "binding in tuple pattern")?;
+        if binding.id != "_" && bindings.iter().any(|b| b.id == binding.id) {
+            return Err(CompilerError::Parser(
+                ParserError::DuplicateParameter(binding.id),
+                binding.line,
+            ));
+        }
+        bindings.push(binding);
+        match parser.advance() {
+            Token::Symbol(Symbol::Comma, _) => {}
+            Token::Symbol(Symbol::RP, _) if bindings.len() > 1 => return Ok(bindings),
+            token => {
+                return Err(errors::expected_token(
+                    "`,` or `)` in tuple pattern",
+                    token.to_string(),
+                    parser.location(),
+                ))
+            }
+        }
+    }
+}
+
+/// Parses `try expression catch (message, line) -> expression`, where `catch` may start a new
+/// line and the pattern may be `_`.
+fn parse_try(parser: &mut Parser) -> Result<Expression, CompilerError> {
+    let line = parser.location();
+    parser.advance(); // Consume `try`
+    let body = parse_expression(parser)?;
+    skip_newlines(parser);
+    if !matches!(parser.advance(), Token::Keyword(Keyword::Catch, _)) {
+        return Err(errors::unexpected_token("`catch` after try", line));
+    }
+
+    let pattern = match parser.advance() {
+        Token::Symbol(Symbol::Wildcard, _) => Pattern::Wildcard,
+        Token::Symbol(Symbol::LP, _) => match parse_tuple_pattern(parser)? {
+            bindings if bindings.len() == 2 => Pattern::Tuple(bindings),
+            bindings => {
+                return Err(errors::expected_token(
+                    "`(message, line)` after catch",
+                    format!("{}-tuple", bindings.len()),
+                    line,
+                ))
+            }
+        },
+        token => {
+            return Err(errors::expected_token(
+                "`(message, line)` or `_` after catch",
+                token.to_string(),
+                line,
+            ))
+        }
+    };
+    if !matches!(parser.advance(), Token::Symbol(Symbol::Return, _)) {
+        return Err(errors::unexpected_token("`->` after catch pattern", line));
+    }
+    let handler = parse_expression(parser)?;
+
+    Ok(Expression::Try(grammar::Try {
+        body: Box::new(body),
+        pattern,
+        handler: Box::new(handler),
+        line,
+    }))
+}
+
+fn parse_raise(parser: &mut Parser) -> Result<Expression, CompilerError> {
+    let line = parser.location();
+    parser.advance(); // Consume `raise`
+    let message = parse_expression(parser)?;
+    Ok(Expression::Raise(Raise {
+        message: Box::new(message),
+        line,
+    }))
+}
+
+/// Parses a name bound by a pattern, or `_` to ignore a value.
+fn parse_binding(parser: &mut Parser, expected: &str) -> Result<LocatedIdentifier, CompilerError> {
+    match parser.advance() {
+        Token::Identifier(id, line) => Ok(LocatedIdentifier {
+            id: id.clone(),
+            line: *line,
+        }),
+        Token::Symbol(Symbol::Wildcard, line) => Ok(LocatedIdentifier {
+            id: "_".to_string(),
+            line: *line,
+        }),
+        token => Err(errors::expected_token(
+            expected,
+            token.to_string(),
+            parser.location(),
+        )),
+    }
+}
+
+/// Parses a parenthesised expression, or a tuple if it holds several separated by commas.
 fn parse_paren_expression(parser: &mut Parser) -> Result<Argument, CompilerError> {
+    let line = parser.location();
     if !matches!(parser.advance(), Token::Symbol(Symbol::LP, _)) {
         unreachable!("expected opening parenthesis");
     }
-    let expression = parse_expression(parser)?;
+    let mut elements = vec![parse_expression(parser)?];
+    while matches!(parser.peek(), Token::Symbol(Symbol::Comma, _)) {
+        parser.advance();
+        elements.push(parse_expression(parser)?);
+    }
     if !matches!(parser.advance(), Token::Symbol(Symbol::RP, _)) {
-        return Err(CompilerError::Parser(
-            "expected closing parenthesis".into(),
+        return Err(errors::unexpected_token(
+            "closing parenthesis",
             parser.location(),
         ));
     }
-    Ok(Argument::ParenExpression(expression))
+    if elements.len() == 1 {
+        return Ok(Argument::ParenExpression(elements.remove(0)));
+    }
+    Ok(Argument::ParenExpression(Expression::Call(Call::Tuple(
+        Tuple { elements, line },
+    ))))
 }
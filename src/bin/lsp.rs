@@ -0,0 +1,13 @@
+use std::{io, process};
+
+use lemma::lsp;
+
+// Stdout carries the protocol, so nothing else may be printed to it
+fn main() {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    if let Err(err) = lsp::run(&mut stdin.lock(), &mut stdout.lock()) {
+        eprintln!("lsp: {}", err);
+        process::exit(1)
+    }
+}
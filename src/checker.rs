@@ -0,0 +1,1039 @@
+use {
+    crate::{
+        checker::substitution::Substitution,
+        errors::{self, CompilerError},
+        grammar::{
+            Access, Argument, Arm, Call, Case, Clause, Comprehension, Conditional, Construction,
+            Constructor, Element, Expression, Function, FunctionCall, List, Operation, Pattern,
+            Pipe, Program, Raise, Signature, Try, Tuple, TypeDefinition, VariantCall,
+        },
+        interpreter,
+        tokens::{Identifier, LocatedIdentifier, Operator, OperatorType, Type, Value},
+        utils,
+    },
+    std::{
+        cell::RefCell,
+        collections::{HashMap, HashSet},
+    },
+};
+
+mod substitution;
+
+/// Statically checks the types of every function in the program, stopping at the first error.
+pub fn check(program: &Program) -> Result<(), CompilerError> {
+    match diagnostics(program).into_iter().next() {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// Checks every function in the program independently and collects all errors found, in source
+/// order.
+pub fn diagnostics(program: &Program) -> Vec<CompilerError> {
+    infer(program).1
+}
+
+/// Signature of every function in the program, inferred for those defined without one.
+pub fn signatures(program: &Program) -> HashMap<Identifier, Signature> {
+    infer(program).0
+}
+
+fn infer(program: &Program) -> (HashMap<Identifier, Signature>, Vec<CompilerError>) {
+    let mut errors = Vec::new();
+    let types: HashMap<Identifier, TypeDefinition> = program
+        .types
+        .iter()
+        .map(|t| (t.name.id.clone(), t.definition.clone()))
+        .collect();
+    let mut signatures: HashMap<Identifier, Signature> = interpreter::prelude_signatures();
+
+    let mut functions: Vec<&Function> = Vec::new();
+    let mut defined: HashSet<&Identifier> = HashSet::new();
+    for f in program.functions.iter() {
+        let name = &f.definition.name;
+        if !defined.insert(&name.id) {
+            errors.push(errors::already_defined(&name.id, name.line));
+            continue;
+        }
+        if let Some(signature) = &f.signature {
+            signatures.insert(name.id.clone(), signature.clone());
+        }
+        functions.push(f);
+    }
+
+    // Functions without a signature are inferred callees first, so that their callers can use
+    // them generically. Mutually recursive functions are inferred together.
+    let unsigned: Vec<&Function> = functions
+        .iter()
+        .filter(|f| f.signature.is_none())
+        .copied()
+        .collect();
+    for group in components(&unsigned) {
+        let substitution = RefCell::new(Substitution::default());
+        for f in group.iter() {
+            let signature = Signature {
+                parameters: f
+                    .definition
+                    .parameters
+                    .iter()
+                    .map(|_| substitution.borrow_mut().fresh())
+                    .collect(),
+                returns: substitution.borrow_mut().fresh(),
+                line: f.definition.name.line,
+            };
+            signatures.insert(f.definition.name.id.clone(), signature);
+        }
+        for f in group.iter() {
+            let signature = &signatures[&f.definition.name.id];
+            if let Err(err) = check_function(&signatures, &types, &substitution, f, signature) {
+                errors.push(err);
+            }
+        }
+        for f in group.iter() {
+            let name = &f.definition.name.id;
+            let signature = substitution.borrow_mut().generalise(&signatures[name]);
+            signatures.insert(name.clone(), signature);
+        }
+    }
+
+    // Declared signatures are verified by checking their functions against them
+    for f in functions.iter() {
+        if let Some(signature) = &f.signature {
+            let substitution = RefCell::new(Substitution::default());
+            if let Err(err) = check_function(&signatures, &types, &substitution, f, signature) {
+                errors.push(err);
+            }
+        }
+    }
+
+    errors.sort_by_key(|err| err.line());
+    (signatures, errors)
+}
+
+/// Groups functions that call each other, ordered so that every group comes after the groups
+/// it calls (Tarjan's strongly connected components).
+fn components<'a>(functions: &[&'a Function]) -> Vec<Vec<&'a Function>> {
+    struct Tarjan<'a, 'b> {
+        functions: &'b [&'a Function],
+        calls: Vec<Vec<usize>>,
+        index: Vec<Option<usize>>,
+        low: Vec<usize>,
+        stack: Vec<usize>,
+        on_stack: Vec<bool>,
+        next: usize,
+        components: Vec<Vec<&'a Function>>,
+    }
+
+    impl Tarjan<'_, '_> {
+        fn visit(&mut self, v: usize) {
+            self.index[v] = Some(self.next);
+            self.low[v] = self.next;
+            self.next += 1;
+            self.stack.push(v);
+            self.on_stack[v] = true;
+
+            for w in self.calls[v].clone() {
+                match self.index[w] {
+                    None => {
+                        self.visit(w);
+                        self.low[v] = self.low[v].min(self.low[w]);
+                    }
+                    Some(index) if self.on_stack[w] => self.low[v] = self.low[v].min(index),
+                    Some(_) => {}
+                }
+            }
+
+            if Some(self.low[v]) == self.index[v] {
+                let mut component = Vec::new();
+                while let Some(w) = self.stack.pop() {
+                    self.on_stack[w] = false;
+                    component.push(self.functions[w]);
+                    if w == v {
+                        break;
+                    }
+                }
+                component.reverse();
+                self.components.push(component);
+            }
+        }
+    }
+
+    let names: Vec<&Identifier> = functions.iter().map(|f| &f.definition.name.id).collect();
+    let calls = functions
+        .iter()
+        .map(|f| {
+            let mut references = Vec::new();
+            expression_references(&f.definition.body, &mut references);
+            names
+                .iter()
+                .enumerate()
+                .filter(|(_, name)| references.contains(name))
+                .map(|(i, _)| i)
+                .collect()
+        })
+        .collect();
+    let mut tarjan = Tarjan {
+        functions,
+        calls,
+        index: vec![None; functions.len()],
+        low: vec![0; functions.len()],
+        stack: Vec::new(),
+        on_stack: vec![false; functions.len()],
+        next: 0,
+        components: Vec::new(),
+    };
+    for v in 0..functions.len() {
+        if tarjan.index[v].is_none() {
+            tarjan.visit(v);
+        }
+    }
+    tarjan.components
+}
+
+/// Names an expression refers to, which may be functions or parameters.
+fn expression_references(expr: &Expression, references: &mut Vec<Identifier>) {
+    let arguments = |arguments: &[Argument], references: &mut Vec<Identifier>| {
+        for arg in arguments.iter() {
+            argument_references(arg, references);
+        }
+    };
+    match expr {
+        Expression::Value(v) => value_references(v, references),
+        Expression::Call(Call::Operation(op)) => arguments(&op.arguments, references),
+        Expression::Call(Call::FunctionCall(fc)) => {
+            references.push(fc.name.id.clone());
+            arguments(&fc.arguments, references);
+        }
+        Expression::Call(Call::Pipe(p)) => {
+            expression_references(&p.left, references);
+            references.push(p.right.clone());
+        }
+        Expression::Call(Call::Construction(c)) => {
+            for (_, value) in c.fields.iter() {
+                expression_references(value, references);
+            }
+        }
+        Expression::Call(Call::Access(a)) => arguments(std::slice::from_ref(&a.record), references),
+        Expression::Call(Call::Variant(v)) => arguments(&v.arguments, references),
+        Expression::Call(Call::Tuple(t)) => {
+            for e in t.elements.iter() {
+                expression_references(e, references);
+            }
+        }
+        Expression::Conditional(c) => {
+            expression_references(&c.condition, references);
+            expression_references(&c.then, references);
+            expression_references(&c.otherwise, references);
+        }
+        Expression::Try(t) => {
+            expression_references(&t.body, references);
+            expression_references(&t.handler, references);
+        }
+        Expression::Raise(r) => expression_references(&r.message, references),
+        Expression::Comprehension(c) => {
+            expression_references(&c.element, references);
+            for clause in c.clauses.iter() {
+                match clause {
+                    Clause::Generator { list, .. } => expression_references(list, references),
+                    Clause::Guard(condition) => expression_references(condition, references),
+                }
+            }
+        }
+        Expression::Builtin(_) => {}
+        Expression::Case(c) => {
+            expression_references(&c.subject, references);
+            for arm in c.arms.iter() {
+                expression_references(&arm.body, references);
+            }
+        }
+    }
+}
+
+fn argument_references(arg: &Argument, references: &mut Vec<Identifier>) {
+    match arg {
+        Argument::Value(v) => value_references(v, references),
+        Argument::Identifier(l_id) => references.push(l_id.id.clone()),
+        Argument::ParenExpression(expr) => expression_references(expr, references),
+    }
+}
+
+fn value_references(value: &Value, references: &mut Vec<Identifier>) {
+    if let Value::List(list) = value {
+        for e in list.elements.iter() {
+            match e {
+                Element::Identifier(id) => references.push(id.clone()),
+                Element::Value(v) => value_references(v, references),
+                Element::Range(r) => {
+                    for bound in r.bounds() {
+                        argument_references(bound, references);
+                    }
+                }
+            }
+        }
+    }
+}
+
+struct Scope<'a> {
+    signatures: &'a HashMap<Identifier, Signature>,
+    types: &'a HashMap<Identifier, TypeDefinition>,
+    substitution: &'a RefCell<Substitution>,
+    locals: HashMap<Identifier, Type>,
+}
+
+fn check_function(
+    signatures: &HashMap<Identifier, Signature>,
+    types: &HashMap<Identifier, TypeDefinition>,
+    substitution: &RefCell<Substitution>,
+    function: &Function,
+    signature: &Signature,
+) -> Result<(), CompilerError> {
+    let definition = &function.definition;
+    if signature.parameters.len() != definition.parameters.len() {
+        return Err(errors::arity_mismatch(
+            signature.parameters.len(),
+            definition.parameters.len(),
+            definition.name.line,
+        ));
+    }
+
+    let locals = definition
+        .parameters
+        .iter()
+        .zip(signature.parameters.iter())
+        .map(|(id, t)| (id.id.clone(), t.clone()))
+        .collect();
+    let scope = Scope {
+        signatures,
+        types,
+        substitution,
+        locals,
+    };
+
+    // `Void` functions may evaluate to anything, e.g. `main`
+    if signature.returns == Type::Void {
+        return scope.void(&definition.body);
+    }
+    let body = scope.expression(&definition.body)?;
+    if !scope.unify(&signature.returns, &body) {
+        let line = line(&definition.body).unwrap_or(definition.name.line);
+        return Err(scope.mismatch(&signature.returns, &body, line));
+    }
+    Ok(())
+}
+
+/// `Some` and `None`, the constructors of `Maybe element`.
+fn maybe_constructors(element: &Type) -> Vec<Constructor> {
+    let constructor = |name: &str, parameters| Constructor {
+        name: LocatedIdentifier {
+            id: name.to_string(),
+            line: 0,
+        },
+        parameters,
+    };
+    vec![
+        constructor("Some", vec![element.clone()]),
+        constructor("None", Vec::new()),
+    ]
+}
+
+fn line(expr: &Expression) -> Option<usize> {
+    match expr {
+        Expression::Value(Value::List(l)) => Some(l.line),
+        Expression::Value(_) => None,
+        Expression::Call(Call::Operation(op)) => Some(op.line),
+        Expression::Call(Call::FunctionCall(fc)) => Some(fc.line),
+        Expression::Call(Call::Pipe(p)) => Some(p.line),
+        Expression::Call(Call::Construction(c)) => Some(c.line),
+        Expression::Call(Call::Access(a)) => Some(a.line),
+        Expression::Call(Call::Variant(v)) => Some(v.line),
+        Expression::Call(Call::Tuple(t)) => Some(t.line),
+        Expression::Conditional(c) => Some(c.line),
+        Expression::Case(c) => Some(c.line),
+        Expression::Try(t) => Some(t.line),
+        Expression::Raise(r) => Some(r.line),
+        Expression::Comprehension(c) => Some(c.line),
+        Expression::Builtin(_) => None,
+    }
+}
+
+impl Scope<'_> {
+    /// Closest parameter or function name to an undefined one.
+    fn suggest(&self, id: &Identifier) -> Option<Identifier> {
+        utils::closest(id, self.locals.keys().chain(self.signatures.keys()))
+    }
+
+    /// The constructor of a sum type or of `Maybe`, with the type of the values it builds.
+    fn constructor(&self, name: &Identifier) -> Option<(Type, Constructor)> {
+        if name == "Some" || name == "None" {
+            let element = self.substitution.borrow_mut().fresh();
+            let constructor = maybe_constructors(&element)
+                .into_iter()
+                .find(|c| &c.name.id == name)?;
+            return Some((Type::Maybe(Box::new(element)), constructor));
+        }
+        self.types.iter().find_map(|(t, d)| {
+            d.constructor(name)
+                .map(|c| (Type::Named(t.clone()), c.clone()))
+        })
+    }
+
+    fn unify(&self, expected: &Type, observed: &Type) -> bool {
+        self.substitution.borrow_mut().unify(expected, observed)
+    }
+
+    fn resolve(&self, t: &Type) -> Type {
+        self.substitution.borrow().resolve(t)
+    }
+
+    fn instantiate(&self, signature: &Signature) -> (Vec<Type>, Type) {
+        self.substitution.borrow_mut().instantiate(signature)
+    }
+
+    fn mismatch(&self, expected: &Type, observed: &Type, line: usize) -> CompilerError {
+        errors::unexpected_type(&self.resolve(expected), &self.resolve(observed), line)
+    }
+
+    /// Type of numeric operands combined by an operator. `Int` is promoted to `Decimal` and both to
+    /// `Frac`, so they are the widest of these types, and otherwise must all be the same type.
+    fn promoted(&self, args: &[Type], line: usize) -> Result<Type, CompilerError> {
+        for t in args.iter() {
+            self.numeric(t, line)?;
+        }
+        for widest in [Type::Frac, Type::Decimal] {
+            if args.iter().any(|t| self.resolve(t) == widest) {
+                // Operands of unknown type are taken to be the widest rather than defaulting to `Int`
+                for t in args.iter() {
+                    if self.substitution.borrow().is_unknown(t) {
+                        self.unify(&widest, t);
+                    }
+                }
+                return Ok(widest);
+            }
+        }
+        let first = &args[0];
+        for t in args.iter().skip(1) {
+            if !self.unify(first, t) {
+                return Err(self.mismatch(first, t, line));
+            }
+        }
+        Ok(self.resolve(first))
+    }
+
+    fn numeric(&self, t: &Type, line: usize) -> Result<(), CompilerError> {
+        if self.substitution.borrow_mut().numeric(t) {
+            Ok(())
+        } else {
+            Err(errors::unexpected_type_class(
+                "Numeric",
+                &self.resolve(t),
+                line,
+            ))
+        }
+    }
+
+    fn expression(&self, expr: &Expression) -> Result<Type, CompilerError> {
+        match expr {
+            Expression::Value(v) => self.value(v),
+            Expression::Call(c) => match c {
+                Call::Operation(op) => self.operation(op),
+                Call::FunctionCall(fc) => self.call(fc),
+                Call::Pipe(p) => self.pipe(p),
+                Call::Construction(c) => self.construction(c),
+                Call::Access(a) => self.access(a),
+                Call::Variant(v) => self.variant(v),
+                Call::Tuple(t) => self.tuple(t),
+            },
+            Expression::Conditional(c) => self.conditional(c),
+            Expression::Case(c) => self.case(c),
+            Expression::Try(t) => self.attempt(t),
+            Expression::Raise(r) => self.raise(r),
+            Expression::Comprehension(c) => self.comprehension(c),
+            // Builtins only appear in the prelude, and return what their signature declares
+            Expression::Builtin(name) => match self.signatures.get(name) {
+                Some(signature) => Ok(self.instantiate(signature).1),
+                None => Err(errors::undefined_variable(name, None, 0)),
+            },
+        }
+    }
+
+    fn value(&self, value: &Value) -> Result<Type, CompilerError> {
+        match value {
+            Value::List(l) => self.list(l),
+            v => Ok(v.get_type()),
+        }
+    }
+
+    fn list(&self, list: &List) -> Result<Type, CompilerError> {
+        for e in list.elements.iter() {
+            let types = match e {
+                Element::Value(v) => vec![self.value(v)?],
+                Element::Identifier(i) => vec![self.lookup(i, list.line)?],
+                Element::Range(r) => r
+                    .bounds()
+                    .map(|bound| self.argument(bound))
+                    .collect::<Result<Vec<Type>, CompilerError>>()?,
+            };
+            for t in types.iter() {
+                if !self.unify(&list.list_type, t) {
+                    return Err(errors::list_type_mismatch(
+                        &self.resolve(&list.list_type),
+                        &self.resolve(t),
+                        list.line,
+                    ));
+                }
+            }
+            if let Element::Range(_) = e {
+                let t = self.resolve(&list.list_type);
+                if !matches!(
+                    t,
+                    Type::Int | Type::Frac | Type::Decimal | Type::Str | Type::Var(_)
+                ) {
+                    return Err(errors::unrangeable_type(&t, list.line));
+                }
+            }
+        }
+        Ok(Type::List(Box::new(self.resolve(&list.list_type))))
+    }
+
+    fn argument(&self, arg: &Argument) -> Result<Type, CompilerError> {
+        match arg {
+            Argument::Value(v) => self.value(v),
+            Argument::Identifier(l_id) => self.lookup(&l_id.id, l_id.line),
+            Argument::ParenExpression(expr) => self.expression(expr),
+        }
+    }
+
+    fn lookup(&self, id: &Identifier, line: usize) -> Result<Type, CompilerError> {
+        if let Some(t) = self.locals.get(id) {
+            return Ok(t.clone());
+        }
+        match self.signatures.get(id) {
+            Some(s) if s.parameters.is_empty() => Ok(self.instantiate(s).1),
+            Some(s) => Err(errors::wrong_function_arity(
+                id,
+                s.parameters.len(),
+                0,
+                line,
+            )),
+            None => Err(errors::undefined_variable(id, self.suggest(id), line)),
+        }
+    }
+
+    fn call(&self, fc: &FunctionCall) -> Result<Type, CompilerError> {
+        if fc.arguments.is_empty() {
+            return self.lookup(&fc.name.id, fc.line);
+        }
+
+        if self.locals.contains_key(&fc.name.id) {
+            return Err(errors::wrong_function_arity(
+                &fc.name.id,
+                0,
+                fc.arguments.len(),
+                fc.line,
+            ));
+        }
+        let signature = self.signatures.get(&fc.name.id).ok_or_else(|| {
+            errors::undefined_variable(&fc.name.id, self.suggest(&fc.name.id), fc.line)
+        })?;
+
+        if signature.parameters.len() != fc.arguments.len() {
+            return Err(errors::wrong_function_arity(
+                &fc.name.id,
+                signature.parameters.len(),
+                fc.arguments.len(),
+                fc.line,
+            ));
+        }
+
+        let (parameters, returns) = self.instantiate(signature);
+        for (expected, arg) in parameters.iter().zip(fc.arguments.iter()) {
+            let observed = self.argument(arg)?;
+            if !self.unify(expected, &observed) {
+                return Err(self.mismatch(expected, &observed, fc.line));
+            }
+        }
+        Ok(self.resolve(&returns))
+    }
+
+    fn pipe(&self, pipe: &Pipe) -> Result<Type, CompilerError> {
+        let left = self.expression(&pipe.left)?;
+        let signature = self.signatures.get(&pipe.right).ok_or_else(|| {
+            errors::undefined_variable(&pipe.right, self.suggest(&pipe.right), pipe.line)
+        })?;
+        if signature.parameters.len() != 1 {
+            return Err(errors::wrong_function_arity(
+                &pipe.right,
+                signature.parameters.len(),
+                1,
+                pipe.line,
+            ));
+        }
+
+        // Piping a list or a `Maybe` maps the function over its elements
+        let (parameters, returns) = self.instantiate(signature);
+        let left = self.resolve(&left);
+        let observed = match &left {
+            Type::List(t) | Type::Maybe(t) => t.as_ref().clone(),
+            t => t.clone(),
+        };
+        if !self.unify(&parameters[0], &observed) {
+            return Err(self.mismatch(&parameters[0], &observed, pipe.line));
+        }
+        let returns = Box::new(self.resolve(&returns));
+        Ok(match left {
+            Type::List(_) => Type::List(returns),
+            Type::Maybe(_) => Type::Maybe(returns),
+            _ => *returns,
+        })
+    }
+
+    fn construction(&self, construction: &Construction) -> Result<Type, CompilerError> {
+        let name = &construction.name.id;
+        let fields = match self.types.get(name) {
+            Some(TypeDefinition::Record(fields)) => fields,
+            Some(TypeDefinition::Sum(_)) => {
+                return Err(errors::unexpected_type_class(
+                    "record",
+                    &Type::Named(name.clone()),
+                    construction.line,
+                ))
+            }
+            None => {
+                let suggestion = utils::closest(name, self.types.keys());
+                return Err(errors::unknown_type(name, suggestion, construction.line));
+            }
+        };
+
+        for (field, value) in construction.fields.iter() {
+            let declared = fields
+                .iter()
+                .find(|f| f.name.id == field.id)
+                .ok_or_else(|| {
+                    let names = fields.iter().map(|f| &f.name.id);
+                    errors::unknown_field(
+                        name,
+                        &field.id,
+                        utils::closest(&field.id, names),
+                        field.line,
+                    )
+                })?;
+            let observed = self.expression(value)?;
+            if !self.unify(&declared.field_type, &observed) {
+                return Err(self.mismatch(&declared.field_type, &observed, field.line));
+            }
+        }
+        if let Some(missing) = fields.iter().find(|f| {
+            construction
+                .fields
+                .iter()
+                .all(|(given, _)| given.id != f.name.id)
+        }) {
+            return Err(errors::missing_field(
+                name,
+                &missing.name.id,
+                construction.line,
+            ));
+        }
+        Ok(Type::Named(name.clone()))
+    }
+
+    fn access(&self, access: &Access) -> Result<Type, CompilerError> {
+        let mut record = self.argument(&access.record)?;
+        // An unknown type is taken to be the only record with the field, if there is one
+        if self.substitution.borrow().is_unknown(&record) {
+            let mut candidates = self.types.iter().filter(|(_, d)| match d {
+                TypeDefinition::Record(fields) => fields.iter().any(|f| f.name.id == access.field),
+                TypeDefinition::Sum(_) => false,
+            });
+            if let (Some((name, _)), None) = (candidates.next(), candidates.next()) {
+                self.unify(&record, &Type::Named(name.clone()));
+            }
+        }
+        record = self.resolve(&record);
+
+        let (name, fields) = match record {
+            Type::Named(name) => match self.types.get(&name) {
+                Some(TypeDefinition::Record(fields)) => (name, fields),
+                Some(TypeDefinition::Sum(_)) => {
+                    return Err(errors::unexpected_type_class(
+                        "record",
+                        &Type::Named(name),
+                        access.line,
+                    ))
+                }
+                None => return Err(errors::unknown_type(&name, None, access.line)),
+            },
+            t => return Err(errors::unexpected_type_class("record", &t, access.line)),
+        };
+        fields
+            .iter()
+            .find(|f| f.name.id == access.field)
+            .map(|f| f.field_type.clone())
+            .ok_or_else(|| {
+                let names = fields.iter().map(|f| &f.name.id);
+                let suggestion = utils::closest(&access.field, names);
+                errors::unknown_field(&name, &access.field, suggestion, access.line)
+            })
+    }
+
+    fn variant(&self, call: &VariantCall) -> Result<Type, CompilerError> {
+        let name = &call.name.id;
+        let (built, constructor) = self.constructor(name).ok_or_else(|| {
+            let names = self.types.values().flat_map(|d| d.constructors());
+            let suggestion = utils::closest(name, names.map(|c| &c.name.id));
+            errors::unknown_constructor(name, suggestion, call.line)
+        })?;
+        if constructor.parameters.len() != call.arguments.len() {
+            return Err(errors::wrong_function_arity(
+                name,
+                constructor.parameters.len(),
+                call.arguments.len(),
+                call.line,
+            ));
+        }
+        for (expected, arg) in constructor.parameters.iter().zip(call.arguments.iter()) {
+            let observed = self.argument(arg)?;
+            if !self.unify(expected, &observed) {
+                return Err(self.mismatch(expected, &observed, call.line));
+            }
+        }
+        Ok(self.resolve(&built))
+    }
+
+    /// Checks that every arm matches a constructor of the subject's type, that the arms agree on
+    /// their type, and that every constructor is covered.
+    fn case(&self, case: &Case) -> Result<Type, CompilerError> {
+        let mut subject = self.expression(&case.subject)?;
+        if case
+            .arms
+            .iter()
+            .any(|arm| matches!(arm.pattern, Pattern::Tuple(_)))
+        {
+            return self.tuple_case(case, &subject);
+        }
+        // An unknown subject has the type of the first constructor matched
+        if self.substitution.borrow().is_unknown(&subject) {
+            let first = case.arms.iter().find_map(|arm| match &arm.pattern {
+                Pattern::Variant { name, .. } => self.constructor(&name.id),
+                Pattern::Tuple(_) | Pattern::Wildcard => None,
+            });
+            if let Some((built, _)) = first {
+                self.unify(&subject, &built);
+            }
+        }
+        subject = self.resolve(&subject);
+
+        let (type_name, constructors) = match &subject {
+            Type::Maybe(element) => ("Maybe".to_string(), maybe_constructors(element)),
+            Type::Named(name) => match self.types.get(name) {
+                Some(TypeDefinition::Sum(constructors)) => (name.clone(), constructors.clone()),
+                _ => {
+                    return Err(errors::unexpected_type_class(
+                        "sum type", &subject, case.line,
+                    ))
+                }
+            },
+            t => return Err(errors::unexpected_type_class("sum type", t, case.line)),
+        };
+
+        let mut returns: Option<Type> = None;
+        let mut covered: Vec<&Identifier> = Vec::new();
+        let mut wildcard = false;
+        for arm in case.arms.iter() {
+            let mut locals = self.locals.clone();
+            match &arm.pattern {
+                Pattern::Wildcard => wildcard = true,
+                Pattern::Variant { name, bindings } => {
+                    let constructor = match constructors.iter().find(|c| c.name.id == name.id) {
+                        Some(c) => c,
+                        None => {
+                            return Err(match self.constructor(&name.id) {
+                                Some((other, _)) => {
+                                    errors::unexpected_type(&subject, &other, name.line)
+                                }
+                                None => {
+                                    let names = constructors.iter().map(|c| &c.name.id);
+                                    let suggestion = utils::closest(&name.id, names);
+                                    errors::unknown_constructor(&name.id, suggestion, name.line)
+                                }
+                            })
+                        }
+                    };
+                    if constructor.parameters.len() != bindings.len() {
+                        return Err(errors::wrong_function_arity(
+                            &name.id,
+                            constructor.parameters.len(),
+                            bindings.len(),
+                            arm.line,
+                        ));
+                    }
+                    for (binding, t) in bindings.iter().zip(constructor.parameters.iter()) {
+                        if binding.id != "_" {
+                            locals.insert(binding.id.clone(), t.clone());
+                        }
+                    }
+                    covered.push(&name.id);
+                }
+                Pattern::Tuple(_) => unreachable!("tuple patterns are checked by `tuple_case`"),
+            }
+            self.arm(arm, locals, &mut returns)?;
+        }
+
+        let missing: Vec<Identifier> = constructors
+            .iter()
+            .filter(|c| !covered.contains(&&c.name.id))
+            .map(|c| c.name.id.clone())
+            .collect();
+        if !wildcard && !missing.is_empty() {
+            return Err(errors::non_exhaustive(&type_name, missing, case.line));
+        }
+        let returns = returns.expect("case has at least one arm");
+        Ok(self.resolve(&returns))
+    }
+
+    /// Checks a `case` destructuring a tuple, whose tuple patterns always match.
+    fn tuple_case(&self, case: &Case, subject: &Type) -> Result<Type, CompilerError> {
+        let mut returns: Option<Type> = None;
+        for arm in case.arms.iter() {
+            let mut locals = self.locals.clone();
+            match &arm.pattern {
+                Pattern::Wildcard => {}
+                Pattern::Variant { .. } => {
+                    return Err(errors::unexpected_type_class(
+                        "sum type",
+                        &self.resolve(subject),
+                        arm.line,
+                    ))
+                }
+                Pattern::Tuple(bindings) => {
+                    if self.substitution.borrow().is_unknown(subject) {
+                        let fresh = bindings
+                            .iter()
+                            .map(|_| self.substitution.borrow_mut().fresh())
+                            .collect();
+                        self.unify(subject, &Type::Tuple(fresh));
+                    }
+                    let elements = match self.resolve(subject) {
+                        Type::Tuple(elements) if elements.len() == bindings.len() => elements,
+                        t => {
+                            let class = format!("{}-tuple", bindings.len());
+                            return Err(errors::unexpected_type_class(&class, &t, arm.line));
+                        }
+                    };
+                    for (binding, t) in bindings.iter().zip(elements) {
+                        if binding.id != "_" {
+                            locals.insert(binding.id.clone(), t);
+                        }
+                    }
+                }
+            }
+            self.arm(arm, locals, &mut returns)?;
+        }
+        let returns = returns.expect("case has at least one arm");
+        Ok(self.resolve(&returns))
+    }
+
+    /// Checks the body of a `case` arm with its bindings in scope, against the type of the arms
+    /// before it.
+    fn arm(
+        &self,
+        arm: &Arm,
+        locals: HashMap<Identifier, Type>,
+        returns: &mut Option<Type>,
+    ) -> Result<(), CompilerError> {
+        let scope = Scope {
+            signatures: self.signatures,
+            types: self.types,
+            substitution: self.substitution,
+            locals,
+        };
+        let body = scope.expression(&arm.body)?;
+        match returns {
+            Some(t) if !self.unify(t, &body) => {
+                let line = line(&arm.body).unwrap_or(arm.line);
+                Err(self.mismatch(t, &body, line))
+            }
+            Some(_) => Ok(()),
+            None => {
+                *returns = Some(body);
+                Ok(())
+            }
+        }
+    }
+
+    fn tuple(&self, tuple: &Tuple) -> Result<Type, CompilerError> {
+        let elements = tuple
+            .elements
+            .iter()
+            .map(|e| self.expression(e).map(|t| self.resolve(&t)))
+            .collect::<Result<_, _>>()?;
+        Ok(Type::Tuple(elements))
+    }
+
+    /// Checks that the handler of a `try` has the type of its body, with the error's message and
+    /// line in scope.
+    fn attempt(&self, attempt: &Try) -> Result<Type, CompilerError> {
+        let body = self.expression(&attempt.body)?;
+        let mut locals = self.locals.clone();
+        if let Pattern::Tuple(bindings) = &attempt.pattern {
+            for (binding, t) in bindings.iter().zip([Type::Str, Type::Int]) {
+                if binding.id != "_" {
+                    locals.insert(binding.id.clone(), t);
+                }
+            }
+        }
+        let scope = Scope {
+            signatures: self.signatures,
+            types: self.types,
+            substitution: self.substitution,
+            locals,
+        };
+        let handler = scope.expression(&attempt.handler)?;
+        if !self.unify(&body, &handler) {
+            let line = line(&attempt.handler).unwrap_or(attempt.line);
+            return Err(self.mismatch(&body, &handler, line));
+        }
+        Ok(self.resolve(&body))
+    }
+
+    /// A comprehension is a list of its element, in which each generator's name has the type of
+    /// the elements of its list.
+    fn comprehension(&self, comprehension: &Comprehension) -> Result<Type, CompilerError> {
+        let scope = |locals: &HashMap<Identifier, Type>| Scope {
+            signatures: self.signatures,
+            types: self.types,
+            substitution: self.substitution,
+            locals: locals.clone(),
+        };
+        let mut locals = self.locals.clone();
+        for clause in comprehension.clauses.iter() {
+            match clause {
+                Clause::Generator { name, list } => {
+                    let t = scope(&locals).expression(list)?;
+                    let element = self.substitution.borrow_mut().fresh();
+                    let expected = Type::List(Box::new(element.clone()));
+                    if !self.unify(&expected, &t) {
+                        let line = line(list).unwrap_or(comprehension.line);
+                        return Err(self.mismatch(&expected, &t, line));
+                    }
+                    locals.insert(name.id.clone(), element);
+                }
+                Clause::Guard(condition) => {
+                    let t = scope(&locals).expression(condition)?;
+                    if !self.unify(&Type::Bool, &t) {
+                        let line = line(condition).unwrap_or(comprehension.line);
+                        return Err(self.mismatch(&Type::Bool, &t, line));
+                    }
+                }
+            }
+        }
+        let element = scope(&locals).expression(&comprehension.element)?;
+        Ok(Type::List(Box::new(self.resolve(&element))))
+    }
+
+    /// A `raise` never evaluates to a value, so it fits wherever one of any type is expected.
+    fn raise(&self, raise: &Raise) -> Result<Type, CompilerError> {
+        let message = self.expression(&raise.message)?;
+        if !self.unify(&Type::Str, &message) {
+            return Err(self.mismatch(&Type::Str, &message, raise.line));
+        }
+        Ok(self.substitution.borrow_mut().fresh())
+    }
+
+    /// The body of a `Void` function, whose conditionals may evaluate to a different type in each
+    /// branch.
+    fn void(&self, expr: &Expression) -> Result<(), CompilerError> {
+        match expr {
+            Expression::Conditional(cdl) => {
+                let condition = self.expression(&cdl.condition)?;
+                if !self.unify(&Type::Bool, &condition) {
+                    return Err(self.mismatch(&Type::Bool, &condition, cdl.line));
+                }
+                self.void(&cdl.then)?;
+                self.void(&cdl.otherwise)
+            }
+            _ => self.expression(expr).map(|_| ()),
+        }
+    }
+
+    fn conditional(&self, cdl: &Conditional) -> Result<Type, CompilerError> {
+        let condition = self.expression(&cdl.condition)?;
+        if !self.unify(&Type::Bool, &condition) {
+            return Err(self.mismatch(&Type::Bool, &condition, cdl.line));
+        }
+        let then = self.expression(&cdl.then)?;
+        let otherwise = self.expression(&cdl.otherwise)?;
+        if !self.unify(&then, &otherwise) {
+            let line = line(&cdl.otherwise).unwrap_or(cdl.line);
+            return Err(self.mismatch(&then, &otherwise, line));
+        }
+        Ok(self.resolve(&then))
+    }
+
+    fn operation(&self, op: &Operation) -> Result<Type, CompilerError> {
+        let args = op
+            .arguments
+            .iter()
+            .map(|arg| self.argument(arg))
+            .collect::<Result<Vec<Type>, CompilerError>>()?;
+
+        match op.operator.operator_type() {
+            OperatorType::Unary => {
+                if args.len() != 1 {
+                    return Err(errors::wrong_operator_arity(&op.operator, op.line));
+                }
+                if !self.unify(&Type::Bool, &args[0]) {
+                    return Err(self.mismatch(&Type::Bool, &args[0], op.line));
+                }
+                Ok(Type::Bool)
+            }
+            OperatorType::Binary => {
+                let unary = args.len() == 1 && matches!(op.operator, Operator::Add | Operator::Sub);
+                if args.len() < 2 && !unary {
+                    return Err(errors::wrong_operator_arity(&op.operator, op.line));
+                }
+                self.promoted(&args, op.line)
+            }
+            OperatorType::Relational => {
+                if args.len() != 2 {
+                    return Err(errors::wrong_operator_arity(&op.operator, op.line));
+                }
+                self.promoted(&args, op.line)?;
+                Ok(Type::Bool)
+            }
+            OperatorType::Logical => {
+                if args.len() != 2 {
+                    return Err(errors::wrong_operator_arity(&op.operator, op.line));
+                }
+                if !matches!(op.operator, Operator::Eq | Operator::Neq) {
+                    for t in args.iter() {
+                        if !self.unify(&Type::Bool, t) {
+                            return Err(self.mismatch(&Type::Bool, t, op.line));
+                        }
+                    }
+                    return Ok(Type::Bool);
+                }
+                let (left, right) = (self.resolve(&args[0]), self.resolve(&args[1]));
+                // A number compares equal to the wider number it is promoted to
+                let number = |t: &Type| matches!(t, Type::Int | Type::Frac | Type::Decimal);
+                if number(&left) && number(&right) && left != right {
+                    return Ok(Type::Bool);
+                }
+                if !self.unify(&left, &right) {
+                    return Err(errors::incomparable(&left, &right, op.line));
+                }
+                match self.resolve(&left) {
+                    Type::Bool
+                    | Type::Int
+                    | Type::Frac
+                    | Type::Decimal
+                    | Type::Named(_)
+                    | Type::Var(_)
+                    | Type::Tuple(_)
+                    | Type::Maybe(_) => Ok(Type::Bool),
+                    t => Err(errors::incomparable(&t, &t, op.line)),
+                }
+            }
+        }
+    }
+}
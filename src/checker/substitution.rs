@@ -0,0 +1,193 @@
+use {
+    crate::{
+        grammar::Signature,
+        tokens::{Identifier, Type},
+    },
+    std::collections::{HashMap, HashSet},
+};
+
+/// Prefix of inference variables, which cannot be written in source code. Type variables
+/// written in signatures are rigid: they only unify with themselves.
+const INFERENCE: char = '\'';
+
+/// Types inferred for the inference variables of a group of functions.
+#[derive(Default)]
+pub struct Substitution {
+    bindings: HashMap<Identifier, Type>,
+    /// Variables used in arithmetic, which may only be bound to `Int`, `Frac` or `Decimal`.
+    numeric: HashSet<Identifier>,
+    next: usize,
+}
+
+fn is_inference(name: &str) -> bool {
+    name.starts_with(INFERENCE)
+}
+
+impl Substitution {
+    pub fn fresh(&mut self) -> Type {
+        self.next += 1;
+        Type::Var(format!("{}{}", INFERENCE, self.next))
+    }
+
+    /// The type with every bound inference variable replaced, recursively.
+    pub fn resolve(&self, t: &Type) -> Type {
+        match t {
+            Type::Var(name) => match self.bindings.get(name) {
+                Some(bound) => self.resolve(bound),
+                None => t.clone(),
+            },
+            Type::List(element) => Type::List(Box::new(self.resolve(element))),
+            Type::Tuple(types) => Type::Tuple(types.iter().map(|t| self.resolve(t)).collect()),
+            Type::Maybe(t) => Type::Maybe(Box::new(self.resolve(t))),
+            t => t.clone(),
+        }
+    }
+
+    /// Makes two types equal by binding inference variables, or returns false.
+    pub fn unify(&mut self, expected: &Type, observed: &Type) -> bool {
+        let (expected, observed) = (self.resolve(expected), self.resolve(observed));
+        match (&expected, &observed) {
+            _ if expected == observed => true,
+            (Type::Var(name), t) | (t, Type::Var(name)) if is_inference(name) => self.bind(name, t),
+            (Type::List(expected), Type::List(observed))
+            | (Type::Maybe(expected), Type::Maybe(observed)) => self.unify(expected, observed),
+            (Type::Tuple(expected), Type::Tuple(observed)) => {
+                expected.len() == observed.len()
+                    && expected
+                        .iter()
+                        .zip(observed.iter())
+                        .all(|(e, o)| self.unify(e, o))
+            }
+            _ => false,
+        }
+    }
+
+    fn bind(&mut self, name: &Identifier, t: &Type) -> bool {
+        if occurs(name, t) {
+            return false;
+        }
+        if self.numeric.contains(name) {
+            match t {
+                Type::Int | Type::Frac | Type::Decimal => {}
+                Type::Var(other) if is_inference(other) => {
+                    self.numeric.insert(other.clone());
+                }
+                _ => return false,
+            }
+        }
+        self.bindings.insert(name.clone(), t.clone());
+        true
+    }
+
+    /// Constrains a type to `Int`, `Frac` or `Decimal`, returning false if it is none of them.
+    pub fn numeric(&mut self, t: &Type) -> bool {
+        match self.resolve(t) {
+            Type::Int | Type::Frac | Type::Decimal => true,
+            Type::Var(name) if is_inference(&name) => {
+                self.numeric.insert(name);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether a type is still an unknown inference variable.
+    pub fn is_unknown(&self, t: &Type) -> bool {
+        matches!(self.resolve(t), Type::Var(name) if is_inference(&name))
+    }
+
+    /// Parameter and return types of a signature, with its type variables replaced by fresh
+    /// inference variables so that each use of a generic function is independent.
+    pub fn instantiate(&mut self, signature: &Signature) -> (Vec<Type>, Type) {
+        let mut fresh: HashMap<Identifier, Type> = HashMap::new();
+        let mut variables = Vec::new();
+        for t in signature.parameters.iter().chain([&signature.returns]) {
+            collect_variables(t, &mut variables);
+        }
+        for name in variables.into_iter().filter(|v| !is_inference(v)) {
+            let t = self.fresh();
+            fresh.entry(name).or_insert(t);
+        }
+        let parameters = signature
+            .parameters
+            .iter()
+            .map(|t| t.substitute(&fresh))
+            .collect();
+        (parameters, signature.returns.substitute(&fresh))
+    }
+
+    /// Turns an inferred signature into one that can be written in source code. Arithmetic on
+    /// otherwise unconstrained values defaults to `Int`, and the remaining inference variables
+    /// become type variables `a`, `b`, ...
+    pub fn generalise(&mut self, signature: &Signature) -> Signature {
+        let resolve = |s: &Self| -> Vec<Type> {
+            signature
+                .parameters
+                .iter()
+                .chain([&signature.returns])
+                .map(|t| s.resolve(t))
+                .collect()
+        };
+        let mut variables = Vec::new();
+        for t in resolve(self).iter() {
+            collect_variables(t, &mut variables);
+        }
+        for name in variables.iter() {
+            if self.numeric.contains(name) {
+                self.bindings.insert(name.clone(), Type::Int);
+            }
+        }
+
+        let mut types = resolve(self);
+        let mut variables = Vec::new();
+        for t in types.iter() {
+            collect_variables(t, &mut variables);
+        }
+        let mut names: HashMap<Identifier, Type> = HashMap::new();
+        let mut letters = (0..).map(letter).filter(|l| !variables.contains(l));
+        for name in variables.iter().filter(|v| is_inference(v)) {
+            if !names.contains_key(name) {
+                names.insert(name.clone(), Type::Var(letters.next().unwrap()));
+            }
+        }
+        types = types.iter().map(|t| t.substitute(&names)).collect();
+
+        let returns = types.pop().unwrap();
+        Signature {
+            parameters: types,
+            returns,
+            line: signature.line,
+        }
+    }
+}
+
+fn occurs(name: &Identifier, t: &Type) -> bool {
+    match t {
+        Type::Var(other) => other == name,
+        Type::List(element) | Type::Maybe(element) => occurs(name, element),
+        Type::Tuple(types) => types.iter().any(|t| occurs(name, t)),
+        _ => false,
+    }
+}
+
+fn collect_variables(t: &Type, variables: &mut Vec<Identifier>) {
+    match t {
+        Type::Var(name) if !variables.contains(name) => variables.push(name.clone()),
+        Type::List(element) | Type::Maybe(element) => collect_variables(element, variables),
+        Type::Tuple(types) => {
+            for t in types.iter() {
+                collect_variables(t, variables);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// `a` to `z`, then `a1`, `b1`, ...
+fn letter(i: usize) -> Identifier {
+    let c = (b'a' + (i % 26) as u8) as char;
+    match i / 26 {
+        0 => c.to_string(),
+        n => format!("{}{}", c, n),
+    }
+}
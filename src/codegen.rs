@@ -0,0 +1,318 @@
+use {
+    crate::{
+        errors::{self, CompilerError, InterpreterError},
+        grammar::{Argument, Call, Element, Expression, Function, List, Operation, Program},
+        interpreter,
+        tokens::{Identifier, LocatedIdentifier, Operator, Type, Value},
+    },
+    std::{collections::HashMap, fmt::Write},
+};
+
+/// C source of the values and operations the generated functions are written with.
+const RUNTIME: &str = include_str!("codegen/runtime.c");
+
+/// Translates a type checked program to a single C file, which prints the value of `main` when it
+/// runs. Only integers, fractions, booleans, strings, lists, conditionals and functions can be
+/// translated.
+pub fn generate(program: &Program) -> Result<String, CompilerError> {
+    let functions: HashMap<&Identifier, &Function> = program
+        .functions
+        .iter()
+        .map(|f| (&f.definition.name.id, f))
+        .collect();
+    if !functions.contains_key(&"main".to_string()) {
+        return Err(CompilerError::Interpreter(
+            InterpreterError::MainNotFound,
+            0,
+        ));
+    }
+
+    let mut c = RUNTIME.to_string();
+    c.push_str(
+        "\n/* Functions of the program, declared first so that they can call each other. They are not \
+         static, so that those the optimiser inlined are not reported as unused. */\n\n",
+    );
+    for function in program.functions.iter() {
+        writeln!(c, "lm_value {};", declaration(function)).unwrap();
+    }
+    for function in program.functions.iter() {
+        let scope = Scope {
+            functions: &functions,
+            parameters: &function.definition.parameters,
+        };
+        let body = scope.expression(&function.definition.body, function.definition.name.line)?;
+        write!(
+            c,
+            "\nlm_value {} {{\n    return {};\n}}\n",
+            declaration(function),
+            body
+        )
+        .unwrap();
+    }
+    c.push_str(
+        "\nint main(void) {\n    lm_print(f_main());\n    putchar('\\n');\n    return 0;\n}\n",
+    );
+    Ok(c)
+}
+
+/// The C name and parameters of a function.
+fn declaration(function: &Function) -> String {
+    let parameters: Vec<String> = function
+        .definition
+        .parameters
+        .iter()
+        .map(|p| format!("lm_value {}", mangle("p", &p.id)))
+        .collect();
+    let parameters = match parameters.is_empty() {
+        true => "void".to_string(),
+        false => parameters.join(", "),
+    };
+    format!(
+        "{}({})",
+        mangle("f", &function.definition.name.id),
+        parameters
+    )
+}
+
+/// A C identifier for a name, which may contain characters such as `-`.
+fn mangle(prefix: &str, id: &Identifier) -> String {
+    let mut mangled = format!("{}_", prefix);
+    for byte in id.bytes() {
+        match byte {
+            b'_' => mangled.push_str("__"),
+            b if b.is_ascii_alphanumeric() => mangled.push(b as char),
+            b => write!(mangled, "_{:02x}", b).unwrap(),
+        }
+    }
+    mangled
+}
+
+/// The names visible from the body of a function.
+struct Scope<'a> {
+    functions: &'a HashMap<&'a Identifier, &'a Function>,
+    parameters: &'a [LocatedIdentifier],
+}
+
+impl Scope<'_> {
+    fn expression(&self, expr: &Expression, line: usize) -> Result<String, CompilerError> {
+        match expr {
+            Expression::Value(v) => self.value(v, line),
+            Expression::Call(Call::Operation(op)) => self.operation(op),
+            Expression::Call(Call::FunctionCall(fc)) => {
+                self.call(&fc.name.id, &fc.arguments, fc.line)
+            }
+            Expression::Call(Call::Pipe(p)) => {
+                let left = self.expression(&p.left, p.line)?;
+                match self.functions.get(&p.right) {
+                    Some(f) if f.definition.parameters.len() == 1 => {
+                        Ok(format!("lm_pipe({}, {})", left, mangle("f", &p.right)))
+                    }
+                    Some(f) => Err(errors::wrong_function_arity(
+                        &p.right,
+                        f.definition.parameters.len(),
+                        1,
+                        p.line,
+                    )),
+                    None => Err(self.undefined(&p.right, p.line)),
+                }
+            }
+            Expression::Call(Call::Construction(c)) => Err(errors::unsupported("records", c.line)),
+            Expression::Call(Call::Access(a)) => Err(errors::unsupported("records", a.line)),
+            Expression::Call(Call::Variant(v)) => Err(errors::unsupported("sum types", v.line)),
+            Expression::Call(Call::Tuple(t)) => Err(errors::unsupported("tuples", t.line)),
+            Expression::Conditional(c) => Ok(format!(
+                "(lm_test({}, {}) ? {} : {})",
+                self.expression(&c.condition, c.line)?,
+                c.line,
+                self.expression(&c.then, c.line)?,
+                self.expression(&c.otherwise, c.line)?
+            )),
+            Expression::Case(c) => Err(errors::unsupported("case expressions", c.line)),
+            Expression::Try(t) => Err(errors::unsupported("try expressions", t.line)),
+            Expression::Raise(r) => Err(errors::unsupported("raise", r.line)),
+            Expression::Comprehension(c) => Err(errors::unsupported("list comprehensions", c.line)),
+            Expression::Builtin(name) => {
+                Err(errors::unsupported(&format!("builtin `{}`", name), line))
+            }
+        }
+    }
+
+    fn argument(&self, arg: &Argument, line: usize) -> Result<String, CompilerError> {
+        match arg {
+            Argument::Value(v) => self.value(v, line),
+            Argument::Identifier(id) => self.call(&id.id, &[], id.line),
+            Argument::ParenExpression(expr) => self.expression(expr, line),
+        }
+    }
+
+    fn value(&self, value: &Value, line: usize) -> Result<String, CompilerError> {
+        Ok(match value {
+            Value::Integer(i64::MIN) => "lm_int(INT64_MIN)".to_string(),
+            Value::Integer(i) => format!("lm_int(INT64_C({}))", i),
+            Value::Fractional(f) if f.is_nan() => "lm_frac(NAN)".to_string(),
+            Value::Fractional(f) if f.is_infinite() && *f > 0.0 => "lm_frac(HUGE_VAL)".to_string(),
+            Value::Fractional(f) if f.is_infinite() => "lm_frac(-HUGE_VAL)".to_string(),
+            // Debug output has the fewest digits that read back as the same number
+            Value::Fractional(f) => format!("lm_frac({:?})", f),
+            Value::Boolean(b) => format!("lm_bool({})", *b as u8),
+            Value::String(s) => format!("lm_str({})", string(s)),
+            Value::List(list) => return self.list(list),
+            Value::BigInteger(_) => return Err(errors::unsupported("integers over 64 bits", line)),
+            Value::Decimal(_) => return Err(errors::unsupported("decimals", line)),
+            Value::Lazy(_) => return Err(errors::unsupported("lazy lists", line)),
+            Value::Record(_) => return Err(errors::unsupported("records", line)),
+            Value::Variant(_) => return Err(errors::unsupported("sum types", line)),
+            Value::Tuple(_) => return Err(errors::unsupported("tuples", line)),
+            Value::Maybe(_) => return Err(errors::unsupported("optional values", line)),
+        })
+    }
+
+    fn list(&self, list: &List) -> Result<String, CompilerError> {
+        let line = list.line;
+        // Ranges are expanded when they are evaluated, so only finite ones can be compiled
+        if let [Element::Range(range)] = list.elements.as_slice() {
+            let end = match (&list.list_type, &range.end) {
+                (Type::Int, Some(end)) => self.argument(end, line)?,
+                (Type::Int, None) => return Err(errors::unsupported("open-ended ranges", line)),
+                (t, _) => return Err(errors::unsupported(&format!("ranges of `{}`", t), line)),
+            };
+            let start = self.argument(&range.start, line)?;
+            // The next value is passed by pointer, as it may be left out
+            let next = match &range.next {
+                Some(next) => format!("(lm_value[]){{{}}}", self.argument(next, line)?),
+                None => "NULL".to_string(),
+            };
+            return Ok(format!("lm_range({}, {}, {}, {})", start, next, end, line));
+        }
+
+        let elements = list
+            .elements
+            .iter()
+            .map(|e| match e {
+                Element::Value(v) => self.value(v, line),
+                Element::Identifier(id) => self.call(id, &[], line),
+                Element::Range(_) => unreachable!("a range is the only element of its list"),
+            })
+            .collect::<Result<Vec<String>, CompilerError>>()?;
+        Ok(match elements.is_empty() {
+            true => "lm_list_of(0, NULL)".to_string(),
+            false => format!(
+                "lm_list_of({}, (lm_value[]){{{}}})",
+                elements.len(),
+                elements.join(", ")
+            ),
+        })
+    }
+
+    fn operation(&self, op: &Operation) -> Result<String, CompilerError> {
+        let line = op.line;
+        let arguments = op
+            .arguments
+            .iter()
+            .map(|arg| self.argument(arg, line))
+            .collect::<Result<Vec<String>, CompilerError>>()?;
+        let arity = |valid: bool| match valid {
+            true => Ok(()),
+            false => Err(errors::wrong_operator_arity(&op.operator, line)),
+        };
+        let symbol = String::from(op.operator.clone());
+        match op.operator {
+            Operator::Add | Operator::Sub | Operator::Mul | Operator::Div => {
+                let unary = matches!(op.operator, Operator::Add | Operator::Sub);
+                arity(arguments.len() >= 2 || (unary && arguments.len() == 1))?;
+                Ok(format!(
+                    "lm_arithmetic('{}', {}, (lm_value[]){{{}}}, {})",
+                    symbol,
+                    arguments.len(),
+                    arguments.join(", "),
+                    line
+                ))
+            }
+            Operator::Lt | Operator::Lte | Operator::Gt | Operator::Gte => {
+                arity(arguments.len() == 2)?;
+                Ok(format!(
+                    "lm_compare(\"{}\", {}, {}, {})",
+                    symbol, arguments[0], arguments[1], line
+                ))
+            }
+            Operator::Eq | Operator::Neq => {
+                arity(arguments.len() == 2)?;
+                let equal = format!("lm_equal({}, {}, {})", arguments[0], arguments[1], line);
+                Ok(match op.operator {
+                    Operator::Eq => equal,
+                    _ => format!("lm_bool(!lm_test({}, {}))", equal, line),
+                })
+            }
+            // The second operand is only evaluated when the first does not decide the result
+            Operator::And | Operator::Or => {
+                arity(arguments.len() == 2)?;
+                let second = format!("lm_bool(lm_test({}, {}))", arguments[1], line);
+                let (then, otherwise) = match op.operator {
+                    Operator::And => (second, "lm_bool(0)".to_string()),
+                    _ => ("lm_bool(1)".to_string(), second),
+                };
+                Ok(format!(
+                    "(lm_test({}, {}) ? {} : {})",
+                    arguments[0], line, then, otherwise
+                ))
+            }
+            Operator::Not => {
+                arity(arguments.len() == 1)?;
+                Ok(format!("lm_bool(!lm_test({}, {}))", arguments[0], line))
+            }
+        }
+    }
+
+    /// A call of a function, or the value of a parameter if the name is one.
+    fn call(
+        &self,
+        name: &Identifier,
+        arguments: &[Argument],
+        line: usize,
+    ) -> Result<String, CompilerError> {
+        if arguments.is_empty() && self.parameters.iter().any(|p| p.id == *name) {
+            return Ok(mangle("p", name));
+        }
+        let function = self
+            .functions
+            .get(name)
+            .ok_or_else(|| self.undefined(name, line))?;
+        let parameters = function.definition.parameters.len();
+        if parameters != arguments.len() {
+            return Err(errors::wrong_function_arity(
+                name,
+                parameters,
+                arguments.len(),
+                line,
+            ));
+        }
+        let arguments = arguments
+            .iter()
+            .map(|arg| self.argument(arg, line))
+            .collect::<Result<Vec<String>, CompilerError>>()?;
+        Ok(format!("{}({})", mangle("f", name), arguments.join(", ")))
+    }
+
+    /// The error for a name that is neither a parameter nor a function of the program.
+    fn undefined(&self, name: &Identifier, line: usize) -> CompilerError {
+        if interpreter::prelude_signatures().contains_key(name) {
+            return errors::unsupported(&format!("prelude function `{}`", name), line);
+        }
+        errors::undefined_variable(name, None, line)
+    }
+}
+
+/// A C string literal. Bytes outside printable ASCII are escaped, and so is `?`, which could start
+/// a trigraph.
+fn string(s: &str) -> String {
+    let mut literal = String::from("\"");
+    for byte in s.bytes() {
+        match byte {
+            b'"' | b'\\' | b'?' => write!(literal, "\\{}", byte as char).unwrap(),
+            b' '..=b'~' => literal.push(byte as char),
+            b => write!(literal, "\\{:03o}", b).unwrap(),
+        }
+    }
+    literal.push('"');
+    literal
+}
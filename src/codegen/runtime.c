@@ -0,0 +1,360 @@
+/* Values and operations of Lemma programs compiled to C. Functions are `static inline` so that
+   those a program does not use are not reported as unused. */
+
+#include <inttypes.h>
+#include <math.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+typedef enum { LM_INT, LM_FRAC, LM_BOOL, LM_STR, LM_LIST } lm_tag;
+
+struct lm_list;
+
+typedef struct {
+    lm_tag tag;
+    union {
+        int64_t i;
+        double f;
+        int b;
+        const char *s;
+        struct lm_list *l;
+    } as;
+} lm_value;
+
+typedef struct lm_list {
+    size_t length;
+    lm_value *elements;
+} lm_list;
+
+typedef lm_value (*lm_function)(lm_value);
+
+/* Stops the program like the interpreter does, with its exit code for runtime errors. */
+static inline void lm_fail(const char *message, int line) {
+    fprintf(stderr, "error: %s (line %d)\n", message, line);
+    exit(4);
+}
+
+static inline void *lm_alloc(size_t size) {
+    void *memory = malloc(size ? size : 1);
+    if (!memory) {
+        fprintf(stderr, "error: out of memory\n");
+        exit(4);
+    }
+    return memory;
+}
+
+static inline lm_value lm_int(int64_t i) {
+    lm_value v;
+    v.tag = LM_INT;
+    v.as.i = i;
+    return v;
+}
+
+static inline lm_value lm_frac(double f) {
+    lm_value v;
+    v.tag = LM_FRAC;
+    v.as.f = f;
+    return v;
+}
+
+static inline lm_value lm_bool(int b) {
+    lm_value v;
+    v.tag = LM_BOOL;
+    v.as.b = b != 0;
+    return v;
+}
+
+static inline lm_value lm_str(const char *s) {
+    lm_value v;
+    v.tag = LM_STR;
+    v.as.s = s;
+    return v;
+}
+
+static inline lm_value lm_list_of(size_t length, const lm_value *elements) {
+    lm_value v;
+    lm_list *list = lm_alloc(sizeof(lm_list));
+    list->length = length;
+    list->elements = lm_alloc(length * sizeof(lm_value));
+    if (length) {
+        memcpy(list->elements, elements, length * sizeof(lm_value));
+    }
+    v.tag = LM_LIST;
+    v.as.l = list;
+    return v;
+}
+
+static inline int lm_numeric(lm_value v) {
+    return v.tag == LM_INT || v.tag == LM_FRAC;
+}
+
+static inline double lm_fractional(lm_value v) {
+    return v.tag == LM_INT ? (double)v.as.i : v.as.f;
+}
+
+/* The condition of a conditional, or an operand of a logical operator. */
+static inline int lm_test(lm_value v, int line) {
+    if (v.tag != LM_BOOL) {
+        lm_fail("unexpected type, expected `Bool`", line);
+    }
+    return v.as.b;
+}
+
+static inline int lm_add_overflows(int64_t a, int64_t b) {
+    return (b > 0 && a > INT64_MAX - b) || (b < 0 && a < INT64_MIN - b);
+}
+
+static inline int lm_sub_overflows(int64_t a, int64_t b) {
+    return (b < 0 && a > INT64_MAX + b) || (b > 0 && a < INT64_MIN + b);
+}
+
+static inline int lm_mul_overflows(int64_t a, int64_t b) {
+    if (a == 0 || b == 0) {
+        return 0;
+    }
+    if (a == -1) {
+        return b == INT64_MIN;
+    }
+    if (b == -1) {
+        return a == INT64_MIN;
+    }
+    if (a > 0) {
+        return b > 0 ? a > INT64_MAX / b : b < INT64_MIN / a;
+    }
+    return b > 0 ? a < INT64_MIN / b : a < INT64_MAX / b;
+}
+
+/* `+`, `-`, `*` or `/` over all the operands, which are promoted to `Frac` if any of them is. */
+static inline lm_value lm_arithmetic(char operator, size_t count, const lm_value *operands, int line) {
+    size_t i;
+    int frac = 0;
+    for (i = 0; i < count; i++) {
+        if (!lm_numeric(operands[i])) {
+            lm_fail("unexpected type, expected a numeric value", line);
+        }
+        frac = frac || operands[i].tag == LM_FRAC;
+    }
+    if (count == 1) {
+        if (operator == '+') {
+            return operands[0];
+        }
+        if (frac) {
+            return lm_frac(-operands[0].as.f);
+        }
+        if (operands[0].as.i == INT64_MIN) {
+            lm_fail("integer overflow", line);
+        }
+        return lm_int(-operands[0].as.i);
+    }
+    if (operator == '/') {
+        for (i = 1; i < count; i++) {
+            if (lm_fractional(operands[i]) == 0.0) {
+                lm_fail("division by zero", line);
+            }
+        }
+    }
+    if (frac) {
+        double result = operator == '*' ? 1.0 : operator == '+' ? 0.0 : lm_fractional(operands[0]);
+        for (i = operator == '+' || operator == '*' ? 0 : 1; i < count; i++) {
+            double operand = lm_fractional(operands[i]);
+            switch (operator) {
+            case '+': result += operand; break;
+            case '-': result -= operand; break;
+            case '*': result *= operand; break;
+            default: result /= operand; break;
+            }
+        }
+        return lm_frac(result);
+    } else {
+        int64_t result = operands[0].as.i;
+        for (i = 1; i < count; i++) {
+            int64_t operand = operands[i].as.i;
+            int overflows;
+            switch (operator) {
+            case '+': overflows = lm_add_overflows(result, operand); break;
+            case '-': overflows = lm_sub_overflows(result, operand); break;
+            case '*': overflows = lm_mul_overflows(result, operand); break;
+            default: overflows = result == INT64_MIN && operand == -1; break;
+            }
+            if (overflows) {
+                lm_fail("integer overflow", line);
+            }
+            switch (operator) {
+            case '+': result += operand; break;
+            case '-': result -= operand; break;
+            case '*': result *= operand; break;
+            default: result /= operand; break;
+            }
+        }
+        return lm_int(result);
+    }
+}
+
+/* `<`, `<=`, `>` or `>=` between two numbers. */
+static inline lm_value lm_compare(const char *operator, lm_value a, lm_value b, int line) {
+    int less, greater, equal;
+    if (!lm_numeric(a) || !lm_numeric(b)) {
+        lm_fail("unexpected type, expected a numeric value", line);
+    }
+    if (a.tag == LM_INT && b.tag == LM_INT) {
+        less = a.as.i < b.as.i;
+        greater = a.as.i > b.as.i;
+        equal = a.as.i == b.as.i;
+    } else {
+        less = lm_fractional(a) < lm_fractional(b);
+        greater = lm_fractional(a) > lm_fractional(b);
+        equal = lm_fractional(a) == lm_fractional(b);
+    }
+    if (strcmp(operator, "<") == 0) {
+        return lm_bool(less);
+    }
+    if (strcmp(operator, ">") == 0) {
+        return lm_bool(greater);
+    }
+    if (strcmp(operator, "<=") == 0) {
+        return lm_bool(less || equal);
+    }
+    return lm_bool(greater || equal);
+}
+
+/* `==` between two booleans or two numbers. */
+static inline lm_value lm_equal(lm_value a, lm_value b, int line) {
+    if (a.tag == LM_BOOL && b.tag == LM_BOOL) {
+        return lm_bool(a.as.b == b.as.b);
+    }
+    if (a.tag == LM_INT && b.tag == LM_INT) {
+        return lm_bool(a.as.i == b.as.i);
+    }
+    if (lm_numeric(a) && lm_numeric(b)) {
+        return lm_bool(lm_fractional(a) == lm_fractional(b));
+    }
+    lm_fail("cannot compare these values", line);
+    return lm_bool(0);
+}
+
+/* `Int [start, next .. end]`, or `Int [start .. end]` without a `next`. */
+static inline lm_value lm_range(lm_value start, const lm_value *next, lm_value end, int line) {
+    int64_t step, value, count = 0;
+    lm_value result;
+    if (start.tag != LM_INT || end.tag != LM_INT || (next && next->tag != LM_INT)) {
+        lm_fail("unexpected type, expected `Int`", line);
+    }
+    if (next) {
+        if (lm_sub_overflows(next->as.i, start.as.i)) {
+            lm_fail("integer overflow", line);
+        }
+        step = next->as.i - start.as.i;
+    } else {
+        step = start.as.i <= end.as.i ? 1 : -1;
+    }
+    if (step == 0) {
+        lm_fail("invalid range: step of zero", line);
+    }
+    result = lm_list_of(0, NULL);
+    value = start.as.i;
+    while (step > 0 ? value <= end.as.i : value >= end.as.i) {
+        if (count % 64 == 0) {
+            lm_value *grown = lm_alloc((size_t)(count + 64) * sizeof(lm_value));
+            if (count) {
+                memcpy(grown, result.as.l->elements, (size_t)count * sizeof(lm_value));
+            }
+            result.as.l->elements = grown;
+        }
+        result.as.l->elements[count++] = lm_int(value);
+        if (lm_add_overflows(value, step)) {
+            break;
+        }
+        value += step;
+    }
+    result.as.l->length = (size_t)count;
+    return result;
+}
+
+/* `value >> function`, mapping the function over the value if it is a list. */
+static inline lm_value lm_pipe(lm_value value, lm_function function) {
+    size_t i;
+    lm_value result;
+    if (value.tag != LM_LIST) {
+        return function(value);
+    }
+    result = lm_list_of(value.as.l->length, value.as.l->elements);
+    for (i = 0; i < value.as.l->length; i++) {
+        result.as.l->elements[i] = function(value.as.l->elements[i]);
+    }
+    return result;
+}
+
+/* Prints a `Frac` with the fewest digits that read back as the same number, without an exponent,
+   and with `.0` if there would be no decimal point. */
+static inline void lm_print_frac(double f) {
+    char buffer[64], digits[32];
+    int precision, exponent, length = 0, i;
+    char *mantissa;
+    if (isnan(f)) {
+        printf("NaN.0");
+        return;
+    }
+    if (isinf(f)) {
+        printf(f > 0 ? "inf.0" : "-inf.0");
+        return;
+    }
+    for (precision = 1; precision <= 17; precision++) {
+        snprintf(buffer, sizeof buffer, "%.*e", precision - 1, f);
+        if (strtod(buffer, NULL) == f) {
+            break;
+        }
+    }
+    mantissa = buffer;
+    if (*mantissa == '-') {
+        putchar('-');
+        mantissa++;
+    }
+    for (; *mantissa != 'e'; mantissa++) {
+        if (*mantissa != '.') {
+            digits[length++] = *mantissa;
+        }
+    }
+    exponent = atoi(mantissa + 1);
+    while (length > 1 && digits[length - 1] == '0') {
+        length--;
+    }
+    if (exponent < 0) {
+        printf("0.");
+        for (i = 1; i < -exponent; i++) {
+            putchar('0');
+        }
+        fwrite(digits, 1, (size_t)length, stdout);
+        return;
+    }
+    for (i = 0; i <= exponent || i < length; i++) {
+        if (i == exponent + 1) {
+            putchar('.');
+        }
+        putchar(i < length ? digits[i] : '0');
+    }
+    if (length <= exponent + 1) {
+        printf(".0");
+    }
+}
+
+static inline void lm_print(lm_value v) {
+    size_t i;
+    switch (v.tag) {
+    case LM_INT: printf("%" PRId64, v.as.i); break;
+    case LM_FRAC: lm_print_frac(v.as.f); break;
+    case LM_BOOL: printf(v.as.b ? "true" : "false"); break;
+    case LM_STR: printf("%s", v.as.s); break;
+    case LM_LIST:
+        putchar('[');
+        for (i = 0; i < v.as.l->length; i++) {
+            if (i > 0) {
+                printf(", ");
+            }
+            lm_print(v.as.l->elements[i]);
+        }
+        putchar(']');
+        break;
+    }
+}
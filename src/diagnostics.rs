@@ -0,0 +1,153 @@
+use {
+    crate::{args::ApplicationError, errors::CompilerError},
+    colored::Colorize,
+    serde::Serialize,
+    serde_json::json,
+    std::str::FromStr,
+};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SURROUNDING_LINES: usize = 3;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DiagnosticsFormat {
+    Human,
+    Json,
+    Sarif,
+}
+
+impl FromStr for DiagnosticsFormat {
+    type Err = ApplicationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "human" => Ok(DiagnosticsFormat::Human),
+            "json" => Ok(DiagnosticsFormat::Json),
+            "sarif" => Ok(DiagnosticsFormat::Sarif),
+            _ => Err(ApplicationError::Args(format!(
+                "invalid diagnostics format `{}` (expected human, json or sarif)",
+                s
+            ))),
+        }
+    }
+}
+
+/// A `CompilerError` resolved against the file it was raised in.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Diagnostic {
+    pub phase: &'static str,
+    pub code: &'static str,
+    pub file: String,
+    pub line: usize,
+    pub span: Option<Span>,
+    pub message: String,
+}
+
+/// 1-indexed columns of the offending code, the end being exclusive.
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Diagnostic {
+    pub fn new(err: &CompilerError, file: &str, source: &str) -> Self {
+        let line = err.line();
+        // Errors only record their line, so the span covers the code on that line
+        let span = line
+            .checked_sub(1)
+            .and_then(|i| source.split('\n').nth(i))
+            .map(|l| l.trim_end())
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| Span {
+                start: l.chars().take_while(|c| c.is_whitespace()).count() + 1,
+                end: l.chars().count() + 1,
+            });
+
+        Self {
+            phase: err.phase(),
+            code: err.code(),
+            file: file.to_string(),
+            line,
+            span,
+            message: err.message().to_string(),
+        }
+    }
+}
+
+/// Renders diagnostics in a machine-readable format. Human-readable output is handled by
+/// `human`.
+pub fn render(format: DiagnosticsFormat, diagnostics: &[Diagnostic]) -> String {
+    let value = match format {
+        DiagnosticsFormat::Json => json!({ "diagnostics": diagnostics }),
+        DiagnosticsFormat::Sarif => sarif(diagnostics),
+        DiagnosticsFormat::Human => unreachable!("human diagnostics are not rendered"),
+    };
+    serde_json::to_string_pretty(&value).unwrap()
+}
+
+/// Renders an error for a terminal, with the offending line underlined in its surrounding code.
+pub fn human(err: &CompilerError, file: &str, source: &str) -> String {
+    let mut out = format!("{:>5} {}\n", "ERROR".red(), err);
+    let error_line = err.line();
+    let lines: Vec<&str> = source.split('\n').collect();
+    if error_line < 1 || error_line > lines.len() {
+        return out;
+    }
+    out.push_str(&format!("{} {}:{}\n", "  -->".blue(), file, error_line));
+
+    let start = std::cmp::max(1, error_line.saturating_sub(SURROUNDING_LINES));
+    let end = std::cmp::min(lines.len(), error_line + SURROUNDING_LINES);
+
+    for i in start..=end {
+        let line = lines[i - 1];
+        let padding = " ".repeat(5 - i.to_string().len());
+        let num = format!("{}{}| ", padding, i).blue();
+        out.push_str(&format!("{}{}\n", num, line));
+        if i == error_line {
+            let padding = " ".repeat(num.len());
+            out.push_str(&format!("{}{}\n", padding, "^".repeat(line.len()).red()));
+            out.push_str(&format!("{}{}\n", padding, err.message().red()));
+        }
+    }
+    out
+}
+
+fn sarif(diagnostics: &[Diagnostic]) -> serde_json::Value {
+    let mut codes: Vec<&str> = diagnostics.iter().map(|d| d.code).collect();
+    codes.sort();
+    codes.dedup();
+    let rules: Vec<serde_json::Value> = codes.iter().map(|c| json!({ "id": c })).collect();
+
+    let results: Vec<serde_json::Value> = diagnostics
+        .iter()
+        .map(|d| {
+            let mut region = json!({ "startLine": d.line.max(1) });
+            if let Some(span) = &d.span {
+                region["startColumn"] = json!(span.start);
+                region["endColumn"] = json!(span.end);
+            }
+            json!({
+                "ruleId": d.code,
+                "level": "error",
+                "message": { "text": d.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": d.file },
+                        "region": region
+                    }
+                }],
+                "properties": { "phase": d.phase }
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": { "driver": { "name": "lemma", "rules": rules } },
+            "results": results
+        }]
+    })
+}
@@ -1,11 +1,15 @@
 use {
-    crate::tokens::{Identifier, Operator, Type},
+    crate::tokens::{Identifier, Operator, Type, Value},
     std::{
         error::Error,
         fmt::{self, Display, Formatter},
     },
 };
 
+pub use catalogue::{explain, Explanation};
+
+mod catalogue;
+
 #[derive(PartialEq, Debug)]
 pub enum ApplicationError {
     Args(String),
@@ -23,13 +27,273 @@ impl Display for ApplicationError {
 
 #[derive(PartialEq, Debug)]
 pub enum CompilerError {
-    Lexer(String, usize),
-    Parser(String, usize),
-    Interpreter(String, usize),
+    Lexer(LexerError, usize),
+    Parser(ParserError, usize),
+    Module(ModuleError, usize),
+    Interpreter(InterpreterError, usize),
+    Codegen(CodegenError, usize),
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum LexerError {
+    InvalidToken(String),
+    InvalidCharacter(char),
+    InvalidNumeric(String),
+    UnterminatedString,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum ParserError {
+    EmptyProgram,
+    UnexpectedToken {
+        token: String,
+        context: String,
+    },
+    ExpectedToken {
+        expected: String,
+        found: Option<String>,
+    },
+    DuplicateParameter(Identifier),
+    UnrangeableType(Type),
+    UnknownType {
+        name: Identifier,
+        suggestion: Option<Identifier>,
+    },
+    DuplicateField(Identifier),
+    DuplicateConstructor(Identifier),
+    RecursiveAlias(Identifier),
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum ModuleError {
+    ImportNotFound(String),
+    ImportCycle(Vec<String>),
+    DuplicateDefinition {
+        name: Identifier,
+        first: String,
+        second: String,
+    },
+    UnknownImport {
+        name: Identifier,
+        module: String,
+        suggestion: Option<Identifier>,
+    },
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum InterpreterError {
+    UnexpectedType {
+        expected: Type,
+        found: Type,
+    },
+    UnexpectedTypeClass {
+        expected: String,
+        found: Type,
+    },
+    UndefinedVariable {
+        name: Identifier,
+        suggestion: Option<Identifier>,
+    },
+    UndefinedArgument {
+        name: Identifier,
+        suggestion: Option<Identifier>,
+    },
+    WrongOperatorArity(Operator),
+    ArityMismatch {
+        expected: usize,
+        found: usize,
+    },
+    WrongFunctionArity {
+        function: Identifier,
+        expected: usize,
+        found: usize,
+    },
+    AlreadyDefined(Identifier),
+    MainNotFound,
+    DivisionByZero,
+    ListTypeMismatch {
+        expected: Type,
+        found: Type,
+    },
+    Incomparable {
+        left: Type,
+        right: Type,
+    },
+    MissingValue(String),
+    UnknownField {
+        record: Identifier,
+        field: Identifier,
+        suggestion: Option<Identifier>,
+    },
+    MissingField {
+        record: Identifier,
+        field: Identifier,
+    },
+    NonExhaustive {
+        type_name: Identifier,
+        missing: Vec<Identifier>,
+    },
+    UnknownConstructor {
+        name: Identifier,
+        suggestion: Option<Identifier>,
+    },
+    Raised(String),
+    IntegerOverflow,
+    InvalidRange(String),
+    /// A `Frac` rounded to an `Int`, such as infinity, which no integer is close to.
+    NoIntegerValue(String),
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum CodegenError {
+    /// A construct the C code generator has no translation for.
+    Unsupported(String),
 }
 
 impl Error for CompilerError {}
 
+impl CompilerError {
+    /// Compilation phase the error was raised in.
+    pub fn phase(&self) -> &'static str {
+        match self {
+            CompilerError::Lexer(_, _) => "lexer",
+            CompilerError::Parser(_, _) => "parser",
+            CompilerError::Module(_, _) => "module",
+            CompilerError::Interpreter(_, _) => "interpreter",
+            CompilerError::Codegen(_, _) => "codegen",
+        }
+    }
+
+    /// Stable error code, explained by `errors::explain`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            CompilerError::Lexer(e, _) => e.code(),
+            CompilerError::Parser(e, _) => e.code(),
+            CompilerError::Module(e, _) => e.code(),
+            CompilerError::Interpreter(e, _) => e.code(),
+            CompilerError::Codegen(e, _) => e.code(),
+        }
+    }
+
+    /// Message without the phase and line decorations added by `Display`.
+    pub fn message(&self) -> String {
+        match self {
+            CompilerError::Lexer(e, _) => e.to_string(),
+            CompilerError::Parser(e, _) => e.to_string(),
+            CompilerError::Module(e, _) => e.to_string(),
+            CompilerError::Interpreter(e, _) => e.to_string(),
+            CompilerError::Codegen(e, _) => e.to_string(),
+        }
+    }
+
+    /// 1-indexed source line, or 0 if the error is not tied to a line.
+    pub fn line(&self) -> usize {
+        match self {
+            CompilerError::Lexer(_, line) => *line,
+            CompilerError::Parser(_, line) => *line,
+            CompilerError::Module(_, line) => *line,
+            CompilerError::Interpreter(_, line) => *line,
+            CompilerError::Codegen(_, line) => *line,
+        }
+    }
+
+    /// The same error reported at another line.
+    pub fn with_line(self, line: usize) -> Self {
+        match self {
+            CompilerError::Lexer(e, _) => CompilerError::Lexer(e, line),
+            CompilerError::Parser(e, _) => CompilerError::Parser(e, line),
+            CompilerError::Module(e, _) => CompilerError::Module(e, line),
+            CompilerError::Interpreter(e, _) => CompilerError::Interpreter(e, line),
+            CompilerError::Codegen(e, _) => CompilerError::Codegen(e, line),
+        }
+    }
+
+    /// Process exit code, distinct for each phase.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CompilerError::Lexer(_, _) => 2,
+            CompilerError::Parser(_, _) => 3,
+            CompilerError::Interpreter(_, _) => 4,
+            CompilerError::Module(_, _) => 5,
+            CompilerError::Codegen(_, _) => 6,
+        }
+    }
+}
+
+impl LexerError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            LexerError::InvalidToken(_) => "L0001",
+            LexerError::InvalidCharacter(_) => "L0002",
+            LexerError::InvalidNumeric(_) => "L0003",
+            LexerError::UnterminatedString => "L0004",
+        }
+    }
+}
+
+impl ParserError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParserError::EmptyProgram => "P0001",
+            ParserError::UnexpectedToken { .. } => "P0002",
+            ParserError::ExpectedToken { .. } => "P0003",
+            ParserError::DuplicateParameter(_) => "P0004",
+            ParserError::UnrangeableType(_) => "P0005",
+            ParserError::UnknownType { .. } => "P0006",
+            ParserError::DuplicateField(_) => "P0007",
+            ParserError::DuplicateConstructor(_) => "P0008",
+            ParserError::RecursiveAlias(_) => "P0009",
+        }
+    }
+}
+
+impl ModuleError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ModuleError::ImportNotFound(_) => "M0001",
+            ModuleError::ImportCycle(_) => "M0002",
+            ModuleError::DuplicateDefinition { .. } => "M0003",
+            ModuleError::UnknownImport { .. } => "M0004",
+        }
+    }
+}
+
+impl InterpreterError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            InterpreterError::UnexpectedType { .. } => "E0001",
+            InterpreterError::UnexpectedTypeClass { .. } => "E0002",
+            InterpreterError::UndefinedVariable { .. } => "E0003",
+            InterpreterError::UndefinedArgument { .. } => "E0004",
+            InterpreterError::WrongOperatorArity(_) => "E0005",
+            InterpreterError::ArityMismatch { .. } => "E0006",
+            InterpreterError::WrongFunctionArity { .. } => "E0007",
+            InterpreterError::AlreadyDefined(_) => "E0008",
+            InterpreterError::MainNotFound => "E0009",
+            InterpreterError::DivisionByZero => "E0010",
+            InterpreterError::ListTypeMismatch { .. } => "E0011",
+            InterpreterError::Incomparable { .. } => "E0012",
+            InterpreterError::MissingValue(_) => "E0013",
+            InterpreterError::UnknownField { .. } => "E0014",
+            InterpreterError::MissingField { .. } => "E0015",
+            InterpreterError::NonExhaustive { .. } => "E0016",
+            InterpreterError::UnknownConstructor { .. } => "E0017",
+            InterpreterError::Raised(_) => "E0018",
+            InterpreterError::IntegerOverflow => "E0019",
+            InterpreterError::InvalidRange(_) => "E0020",
+            InterpreterError::NoIntegerValue(_) => "E0021",
+        }
+    }
+}
+
+impl CodegenError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            CodegenError::Unsupported(_) => "C0001",
+        }
+    }
+}
+
 impl Display for CompilerError {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
@@ -39,6 +303,9 @@ impl Display for CompilerError {
             CompilerError::Parser(e, line) => {
                 write!(f, "Parser error: {} (line {})", e, line)
             }
+            CompilerError::Module(e, line) => {
+                write!(f, "Module error: {} (line {})", e, line)
+            }
             CompilerError::Interpreter(e, line) => {
                 if line > &0 {
                     write!(f, "Interpreter error: {} (line {})", e, line)
@@ -46,37 +313,408 @@ impl Display for CompilerError {
                     write!(f, "Interpreter error: {}", e)
                 }
             }
+            CompilerError::Codegen(e, line) => {
+                write!(f, "Codegen error: {} (line {})", e, line)
+            }
+        }
+    }
+}
+
+impl Display for LexerError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            LexerError::InvalidToken(t) => write!(f, "invalid token `{}`", t),
+            LexerError::InvalidCharacter(c) => write!(f, "invalid character `{}`", c),
+            LexerError::InvalidNumeric(n) => write!(f, "could not parse numeric `{}`", n),
+            LexerError::UnterminatedString => write!(f, "unterminated string"),
+        }
+    }
+}
+
+impl Display for ParserError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ParserError::EmptyProgram => write!(f, "empty program"),
+            ParserError::UnexpectedToken { token, context } => {
+                write!(f, "unexpected token in {}: {}", context, quoted(token))
+            }
+            ParserError::ExpectedToken { expected, found } => match found {
+                Some(found) => write!(f, "expected {}, found {}", expected, quoted(found)),
+                None => write!(f, "expected {}", expected),
+            },
+            ParserError::DuplicateParameter(id) => write!(f, "duplicate parameter name `{}`", id),
+            ParserError::UnrangeableType(t) => write!(f, "cannot make a range of `{}`", t),
+            ParserError::UnknownType { name, suggestion } => {
+                write!(f, "unknown type `{}`{}", name, did_you_mean(suggestion))
+            }
+            ParserError::DuplicateField(id) => write!(f, "duplicate field name `{}`", id),
+            ParserError::DuplicateConstructor(id) => {
+                write!(f, "constructor `{}` is declared more than once", id)
+            }
+            ParserError::RecursiveAlias(id) => write!(f, "type alias `{}` refers to itself", id),
+        }
+    }
+}
+
+impl Display for ModuleError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ModuleError::ImportNotFound(path) => write!(f, "cannot find imported file `{}`", path),
+            ModuleError::ImportCycle(chain) => write!(f, "import cycle: {}", chain.join(" -> ")),
+            ModuleError::DuplicateDefinition {
+                name,
+                first,
+                second,
+            } => write!(
+                f,
+                "`{}` is defined in both `{}` and `{}`",
+                name, first, second
+            ),
+            ModuleError::UnknownImport {
+                name,
+                module,
+                suggestion,
+            } => write!(
+                f,
+                "`{}` is not defined in `{}`{}",
+                name,
+                module,
+                did_you_mean(suggestion)
+            ),
+        }
+    }
+}
+
+impl Display for InterpreterError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            InterpreterError::UnexpectedType { expected, found } => {
+                write!(f, "expected {}, found {}", expected, found)
+            }
+            InterpreterError::UnexpectedTypeClass { expected, found } => {
+                write!(f, "expected {}, found {}", expected, found)
+            }
+            InterpreterError::UndefinedVariable { name, suggestion } => {
+                write!(f, "undefined variable `{}`{}", name, did_you_mean(suggestion))
+            }
+            InterpreterError::UndefinedArgument { name, suggestion } => write!(
+                f,
+                "no argument passed for `{}`{}",
+                name,
+                did_you_mean(suggestion)
+            ),
+            InterpreterError::WrongOperatorArity(operator) => write!(
+                f,
+                "operator `{}` expects {}",
+                operator,
+                operator.operator_type().arity()
+            ),
+            InterpreterError::ArityMismatch { expected, found } => write!(
+                f,
+                "function signature and definition arity mismatch: expected {} parameters, found {}",
+                expected, found
+            ),
+            InterpreterError::WrongFunctionArity {
+                function,
+                expected,
+                found,
+            } => write!(
+                f,
+                "function `{}` expects {} arguments, found {}",
+                function, expected, found
+            ),
+            InterpreterError::AlreadyDefined(id) => write!(f, "variable `{}` already defined", id),
+            InterpreterError::MainNotFound => write!(f, "main function not found"),
+            InterpreterError::DivisionByZero => write!(f, "division by zero"),
+            InterpreterError::ListTypeMismatch { expected, found } => write!(
+                f,
+                "list type mismatch: expected {}, found {}",
+                expected, found
+            ),
+            InterpreterError::Incomparable { left, right } => {
+                write!(f, "cannot compare {} with {}", left, right)
+            }
+            InterpreterError::MissingValue(context) => {
+                write!(f, "{} did not evaluate to a value", context)
+            }
+            InterpreterError::UnknownField {
+                record,
+                field,
+                suggestion,
+            } => write!(
+                f,
+                "record `{}` has no field `{}`{}",
+                record,
+                field,
+                did_you_mean(suggestion)
+            ),
+            InterpreterError::MissingField { record, field } => {
+                write!(f, "missing field `{}` of record `{}`", field, record)
+            }
+            InterpreterError::NonExhaustive { type_name, missing } => {
+                let missing: Vec<String> = missing.iter().map(|m| format!("`{}`", m)).collect();
+                write!(
+                    f,
+                    "non-exhaustive case on `{}`: {} not covered",
+                    type_name,
+                    missing.join(", ")
+                )
+            }
+            InterpreterError::UnknownConstructor { name, suggestion } => write!(
+                f,
+                "unknown constructor `{}`{}",
+                name,
+                did_you_mean(suggestion)
+            ),
+            InterpreterError::Raised(message) => write!(f, "{}", message),
+            InterpreterError::IntegerOverflow => write!(f, "integer overflow"),
+            InterpreterError::InvalidRange(reason) => write!(f, "invalid range: {}", reason),
+            InterpreterError::NoIntegerValue(value) => {
+                write!(f, "`{}` has no integer value", value)
+            }
         }
     }
 }
 
+impl Display for CodegenError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            CodegenError::Unsupported(what) => write!(f, "cannot compile {} to C", what),
+        }
+    }
+}
+
+/// A token in backticks, unless it is one, which could not be told apart from them, or there is
+/// none.
+fn quoted(token: &str) -> String {
+    match token {
+        "`" => "a backtick".to_string(),
+        "" => "the end of the program".to_string(),
+        token => format!("`{}`", token),
+    }
+}
+
+fn did_you_mean(suggestion: &Option<Identifier>) -> String {
+    match suggestion {
+        Some(s) => format!(", did you mean `{}`?", s),
+        None => String::new(),
+    }
+}
+
 pub fn unexpected_type(expected: &Type, observed: &Type, line: usize) -> CompilerError {
-    CompilerError::Interpreter(format!("expected {}, found {}", expected, observed), line)
+    CompilerError::Interpreter(
+        InterpreterError::UnexpectedType {
+            expected: expected.clone(),
+            found: observed.clone(),
+        },
+        line,
+    )
 }
 
 pub fn unexpected_type_class(expected: &str, observed: &Type, line: usize) -> CompilerError {
-    CompilerError::Interpreter(format!("expected {}, found {}", expected, observed), line)
+    CompilerError::Interpreter(
+        InterpreterError::UnexpectedTypeClass {
+            expected: expected.to_string(),
+            found: observed.clone(),
+        },
+        line,
+    )
 }
 
-pub fn undefined_variable(identifier: &Identifier, line: usize) -> CompilerError {
-    CompilerError::Interpreter(format!("undefined variable `{}`", identifier), line)
+pub fn undefined_variable(
+    identifier: &Identifier,
+    suggestion: Option<Identifier>,
+    line: usize,
+) -> CompilerError {
+    CompilerError::Interpreter(
+        InterpreterError::UndefinedVariable {
+            name: identifier.clone(),
+            suggestion,
+        },
+        line,
+    )
 }
 
-pub fn undefined_argument(identifier: &Identifier, line: usize) -> CompilerError {
-    CompilerError::Interpreter(format!("no argument passed for `{}`", identifier), line)
+pub fn undefined_argument(
+    identifier: &Identifier,
+    suggestion: Option<Identifier>,
+    line: usize,
+) -> CompilerError {
+    CompilerError::Interpreter(
+        InterpreterError::UndefinedArgument {
+            name: identifier.clone(),
+            suggestion,
+        },
+        line,
+    )
+}
+
+pub fn unknown_type(
+    identifier: &Identifier,
+    suggestion: Option<Identifier>,
+    line: usize,
+) -> CompilerError {
+    CompilerError::Parser(
+        ParserError::UnknownType {
+            name: identifier.clone(),
+            suggestion,
+        },
+        line,
+    )
 }
 
 pub fn unexpected_token(token: &str, line: usize) -> CompilerError {
-    CompilerError::Interpreter(format!("expected `{}`", token), line)
+    CompilerError::Parser(
+        ParserError::ExpectedToken {
+            expected: token.to_string(),
+            found: None,
+        },
+        line,
+    )
 }
 
 pub fn wrong_operator_arity(operator: &Operator, line: usize) -> CompilerError {
+    CompilerError::Interpreter(InterpreterError::WrongOperatorArity(operator.clone()), line)
+}
+
+pub fn arity_mismatch(expected: usize, found: usize, line: usize) -> CompilerError {
+    CompilerError::Interpreter(InterpreterError::ArityMismatch { expected, found }, line)
+}
+
+pub fn wrong_function_arity(
+    identifier: &Identifier,
+    expected: usize,
+    found: usize,
+    line: usize,
+) -> CompilerError {
     CompilerError::Interpreter(
-        format!(
-            "operator `{}` expects {}",
-            operator,
-            operator.operator_type().arity()
-        ),
+        InterpreterError::WrongFunctionArity {
+            function: identifier.clone(),
+            expected,
+            found,
+        },
         line,
     )
 }
+
+pub fn already_defined(identifier: &Identifier, line: usize) -> CompilerError {
+    CompilerError::Interpreter(InterpreterError::AlreadyDefined(identifier.clone()), line)
+}
+
+pub fn list_type_mismatch(expected: &Type, observed: &Type, line: usize) -> CompilerError {
+    CompilerError::Interpreter(
+        InterpreterError::ListTypeMismatch {
+            expected: expected.clone(),
+            found: observed.clone(),
+        },
+        line,
+    )
+}
+
+pub fn unrangeable_type(t: &Type, line: usize) -> CompilerError {
+    CompilerError::Parser(ParserError::UnrangeableType(t.clone()), line)
+}
+
+pub fn invalid_range(reason: &str, line: usize) -> CompilerError {
+    CompilerError::Interpreter(InterpreterError::InvalidRange(reason.to_string()), line)
+}
+
+pub fn no_integer_value(value: &Value, line: usize) -> CompilerError {
+    CompilerError::Interpreter(InterpreterError::NoIntegerValue(value.to_string()), line)
+}
+
+pub fn incomparable(left: &Type, right: &Type, line: usize) -> CompilerError {
+    CompilerError::Interpreter(
+        InterpreterError::Incomparable {
+            left: left.clone(),
+            right: right.clone(),
+        },
+        line,
+    )
+}
+
+pub fn missing_value(context: &str, line: usize) -> CompilerError {
+    CompilerError::Interpreter(InterpreterError::MissingValue(context.to_string()), line)
+}
+
+pub fn expected_token(expected: &str, found: String, line: usize) -> CompilerError {
+    CompilerError::Parser(
+        ParserError::ExpectedToken {
+            expected: expected.to_string(),
+            found: Some(found),
+        },
+        line,
+    )
+}
+
+pub fn unexpected_token_in(context: &str, token: String, line: usize) -> CompilerError {
+    CompilerError::Parser(
+        ParserError::UnexpectedToken {
+            token,
+            context: context.to_string(),
+        },
+        line,
+    )
+}
+
+pub fn unknown_field(
+    record: &Identifier,
+    field: &Identifier,
+    suggestion: Option<Identifier>,
+    line: usize,
+) -> CompilerError {
+    CompilerError::Interpreter(
+        InterpreterError::UnknownField {
+            record: record.clone(),
+            field: field.clone(),
+            suggestion,
+        },
+        line,
+    )
+}
+
+pub fn missing_field(record: &Identifier, field: &Identifier, line: usize) -> CompilerError {
+    CompilerError::Interpreter(
+        InterpreterError::MissingField {
+            record: record.clone(),
+            field: field.clone(),
+        },
+        line,
+    )
+}
+
+pub fn non_exhaustive(
+    type_name: &Identifier,
+    missing: Vec<Identifier>,
+    line: usize,
+) -> CompilerError {
+    CompilerError::Interpreter(
+        InterpreterError::NonExhaustive {
+            type_name: type_name.clone(),
+            missing,
+        },
+        line,
+    )
+}
+
+pub fn unknown_constructor(
+    identifier: &Identifier,
+    suggestion: Option<Identifier>,
+    line: usize,
+) -> CompilerError {
+    CompilerError::Interpreter(
+        InterpreterError::UnknownConstructor {
+            name: identifier.clone(),
+            suggestion,
+        },
+        line,
+    )
+}
+
+pub fn raised(message: &str, line: usize) -> CompilerError {
+    CompilerError::Interpreter(InterpreterError::Raised(message.to_string()), line)
+}
+
+pub fn unsupported(what: &str, line: usize) -> CompilerError {
+    CompilerError::Codegen(CodegenError::Unsupported(what.to_string()), line)
+}
@@ -0,0 +1,308 @@
+use std::fmt::{self, Display, Formatter};
+
+/// Long-form description of an error code, printed by `lemma --explain CODE`.
+#[derive(Debug, PartialEq)]
+pub struct Explanation {
+    pub code: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    pub example: &'static str,
+}
+
+pub fn explain(code: &str) -> Option<&'static Explanation> {
+    CATALOGUE.iter().find(|e| e.code.eq_ignore_ascii_case(code))
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "{}: {}\n", self.code, self.title)?;
+        writeln!(f, "{}", self.description)?;
+        if !self.example.is_empty() {
+            writeln!(f, "\nExample:\n")?;
+            for line in self.example.lines() {
+                writeln!(f, "    {}", line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub const CATALOGUE: &[Explanation] = &[
+    Explanation {
+        code: "L0001",
+        title: "invalid token",
+        description: "A symbol was used that is only valid as part of a longer token. `&` must be \
+                      doubled to form `&&`, and `.` must be doubled to form the range `..`.",
+        example: "Bool Bool -> Bool\nboth a b = & a b",
+    },
+    Explanation {
+        code: "L0002",
+        title: "invalid character",
+        description: "The source contains a character that does not start any token.",
+        example: "Int -> Int\nincrement n = + n @",
+    },
+    Explanation {
+        code: "L0003",
+        title: "invalid numeric literal",
+        description:
+            "A numeric literal could not be parsed, because it ends with a decimal point. \
+                      Write `1.0` for a `Frac`, or `1` for an `Int`; integers of any size are \
+                      valid.",
+        example: "-> Frac\nmain = 1.",
+    },
+    Explanation {
+        code: "L0004",
+        title: "unterminated string",
+        description: "A string literal was opened with `\"` but never closed.",
+        example: "-> Str\nmain = \"Hello, world!",
+    },
+    Explanation {
+        code: "P0001",
+        title: "empty program",
+        description: "The source file contains no tokens. A program needs at least a `main` \
+                      function.",
+        example: "-> Str\nmain = \"Hello, world!\"",
+    },
+    Explanation {
+        code: "P0002",
+        title: "unexpected token",
+        description: "A token appeared where it is not allowed, such as a value in a function \
+                      signature or a non-argument inside a call.",
+        example: "Int 1 -> Int\nfoo a b = + a b",
+    },
+    Explanation {
+        code: "P0003",
+        title: "expected token",
+        description: "The parser required a specific token to continue, such as a closing \
+                      bracket or parenthesis, `then`, `else`, or a return type.",
+        example: "Int -> Int\nfoo a = + 1 (+ a 1",
+    },
+    Explanation {
+        code: "P0004",
+        title: "duplicate parameter",
+        description: "A function definition names the same parameter more than once.",
+        example: "Int Int -> Int\nadd a a = + a a",
+    },
+    Explanation {
+        code: "P0005",
+        title: "unrangeable type",
+        description: "Range syntax `[a .. b]` is only available for lists of `Int`, `Frac`, \
+                      `Decimal` or single-character `Str` values.",
+        example: "-> [Bool]\nmain = Bool [ false .. true ]",
+    },
+    Explanation {
+        code: "P0006",
+        title: "unknown type",
+        description: "A function signature names a type that does not exist. The built-in types \
+                      are `Int`, `Frac`, `Decimal`, `Str`, `Bool` and `Void`, and lists of them \
+                      such as `[Int]`. Lowercase type variables such as `a` are only allowed in \
+                      signatures.",
+        example: "Itn -> Int\nincrement n = + n 1",
+    },
+    Explanation {
+        code: "P0007",
+        title: "duplicate field",
+        description: "A record declaration or construction names the same field more than once.",
+        example: "data Point = { x Int, x Int }",
+    },
+    Explanation {
+        code: "P0008",
+        title: "duplicate constructor",
+        description: "Two constructors share a name, in the same sum type or in different ones. \
+                      Constructors are global, so each name can only be declared once, and \
+                      `Some` and `None` belong to the built-in `Maybe`.",
+        example: "data Shape = Circle Frac | Circle Int",
+    },
+    Explanation {
+        code: "P0009",
+        title: "recursive type alias",
+        description: "A type alias is defined in terms of itself, directly or through other \
+                      aliases, so it never names an actual type. Use a `data` declaration for \
+                      recursive types.",
+        example: "type Tree = [Tree]",
+    },
+    Explanation {
+        code: "M0001",
+        title: "imported file not found",
+        description: "An `import` names a file that could not be read. Paths are relative to the \
+                      directory of the importing file.",
+        example: "import \"missing.lm\"",
+    },
+    Explanation {
+        code: "M0002",
+        title: "import cycle",
+        description: "A file imports itself, directly or through other imports. Move the shared \
+                      functions into a file that both can import.",
+        example: "# a.lm\nimport \"b.lm\"\n\n# b.lm\nimport \"a.lm\"",
+    },
+    Explanation {
+        code: "M0003",
+        title: "duplicate definition",
+        description: "Two files bring a function of the same name into one scope. Import one of \
+                      them with `as` to put its functions in a namespace, or import only the \
+                      functions needed with a list of names.",
+        example: "import \"math.lm\"\n\nInt Int -> Int\nrem a b = a",
+    },
+    Explanation {
+        code: "M0004",
+        title: "unknown import",
+        description: "A name in the import list of an `import` is not defined in the imported \
+                      file.",
+        example: "import \"math.lm\" (remainder)",
+    },
+    Explanation {
+        code: "E0001",
+        title: "unexpected type",
+        description: "A value of one type was used where another type is required, for example \
+                      a string passed to a function taking an integer.",
+        example: "-> Int\nmain = square \"two\"\n\nInt -> Int\nsquare n = * n n",
+    },
+    Explanation {
+        code: "E0002",
+        title: "unexpected type class",
+        description: "An operator was applied to a value outside the class of types it supports. \
+                      Arithmetic and ordering operators need numeric values, `Int`, `Decimal` \
+                      or `Frac`.",
+        example: "-> Int\nmain = + \"a\" \"b\"",
+    },
+    Explanation {
+        code: "E0003",
+        title: "undefined variable",
+        description: "A name was referenced that is not a parameter of the enclosing function \
+                      and not a function defined in the program or the prelude.",
+        example: "-> Int\nmain = undefined-function 1",
+    },
+    Explanation {
+        code: "E0004",
+        title: "undefined argument",
+        description: "A parameter was used but no argument was passed for it.",
+        example: "-> Int\nmain = add-one\n\nInt -> Int\nadd-one n = + n 1",
+    },
+    Explanation {
+        code: "E0005",
+        title: "wrong operator arity",
+        description: "An operator was given the wrong number of arguments. Arithmetic operators \
+                      take at least two arguments (or one for `+` and `-`), comparisons take \
+                      exactly two and `!` takes exactly one.",
+        example: "-> Bool\nmain = < 1 2 3",
+    },
+    Explanation {
+        code: "E0006",
+        title: "signature and definition arity mismatch",
+        description: "A function definition names a different number of parameters than its \
+                      signature declares.",
+        example: "Int Int -> Int\nadd a = + a 1",
+    },
+    Explanation {
+        code: "E0007",
+        title: "wrong function arity",
+        description: "A function was called with a different number of arguments than its \
+                      signature declares.",
+        example: "-> Int\nmain = add 1\n\nInt Int -> Int\nadd a b = + a b",
+    },
+    Explanation {
+        code: "E0008",
+        title: "already defined",
+        description: "Two functions in the program share the same name.",
+        example: "-> Int\nmain = 1\n\n-> Int\nmain = 2",
+    },
+    Explanation {
+        code: "E0009",
+        title: "main function not found",
+        description: "Every program is evaluated from its `main` function, which was not \
+                      defined.",
+        example: "Int -> Int\nadd-one n = + n 1",
+    },
+    Explanation {
+        code: "E0010",
+        title: "division by zero",
+        description: "A division operation was evaluated with a divisor of zero.",
+        example: "-> Int\nmain = / 1 0",
+    },
+    Explanation {
+        code: "E0011",
+        title: "list type mismatch",
+        description: "A list element does not match the element type the list was declared \
+                      with.",
+        example: "-> [Int]\nmain = Int [ 1 2 \"three\" ]",
+    },
+    Explanation {
+        code: "E0012",
+        title: "incomparable types",
+        description: "`==` and `!=` were applied to values that cannot be compared, such as \
+                      values of different types.",
+        example: "-> Bool\nmain = == 1 1.0",
+    },
+    Explanation {
+        code: "E0013",
+        title: "missing value",
+        description: "An expression whose value is required evaluated to nothing.",
+        example: "",
+    },
+    Explanation {
+        code: "E0014",
+        title: "unknown field",
+        description: "A record was constructed with, or accessed by, a field that its type does \
+                      not declare.",
+        example: "data Point = { x Int, y Int }\n\nPoint -> Int\nheight p = .z p",
+    },
+    Explanation {
+        code: "E0015",
+        title: "missing field",
+        description: "A record was constructed without a value for one of the fields its type \
+                      declares. Every field must be given.",
+        example: "data Point = { x Int, y Int }\n\n-> Point\nmain = Point { x 1 }",
+    },
+    Explanation {
+        code: "E0016",
+        title: "non-exhaustive case",
+        description: "A `case` expression has no arm for some constructors of its subject's \
+                      type. Add an arm for each of them, or a `_` arm to match the rest.",
+        example: "data Shape = Circle Frac | Rect Frac Frac\n\nShape -> Frac\n\
+                  area s = case s of Circle r -> * 3.14 r r",
+    },
+    Explanation {
+        code: "E0017",
+        title: "unknown constructor",
+        description: "A constructor was called or matched that no sum type declares, or that \
+                      does not belong to the type of the `case` subject.",
+        example: "data Shape = Circle Frac | Rect Frac Frac\n\n-> Shape\nmain = Square 1.0",
+    },
+    Explanation {
+        code: "E0018",
+        title: "raised error",
+        description: "A `raise` expression was evaluated outside of any `try`. Wrap the call in \
+                      `try ... catch (message, line) -> ...` to recover from it.",
+        example: "-> Int\nmain = raise \"not implemented\"",
+    },
+    Explanation {
+        code: "E0019",
+        title: "integer overflow",
+        description: "Integer arithmetic gave a result too large for 64 bits while running with \
+                      `--overflow error`. By default, integers grow to any size instead.",
+        example: "-> Int\nmain = * 9223372036854775807 2",
+    },
+    Explanation {
+        code: "E0020",
+        title: "invalid range",
+        description: "A range could not be expanded, because its step is zero or its bounds are \
+                      strings longer than one character.",
+        example: "-> [Int]\nmain = Int [ 1, 1 .. 5 ]",
+    },
+    Explanation {
+        code: "E0021",
+        title: "no integer value",
+        description: "A `Frac` that is infinite or not a number was converted to an `Int` with \
+                      `round`, `floor`, `ceiling` or `truncate`.",
+        example: "-> Int\nmain = round (largest 2.0)\n\n\
+                  Frac -> Frac\nlargest x = if == x (* x x) then x else largest (* x x)",
+    },
+    Explanation {
+        code: "C0001",
+        title: "unsupported construct",
+        description: "`lemma build` only compiles integers, fractions, booleans, strings, lists, \
+                      conditionals and functions to C, and the program uses something else, such \
+                      as a record or a prelude function.",
+        example: "-> Maybe Int\nmain = parse-int \"42\"",
+    },
+];
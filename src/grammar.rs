@@ -1,67 +1,187 @@
-use serde::{Deserialize, Serialize};
+use {
+    serde::{Deserialize, Serialize},
+    std::fmt::{self, Display, Formatter},
+};
 
 use crate::tokens::{Identifier, LocatedIdentifier, Operator, Type, Value};
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Program {
+    pub imports: Vec<Import>,
+    pub types: Vec<TypeDeclaration>,
+    pub aliases: Vec<TypeAlias>,
     pub functions: Vec<Function>,
+    /// Lines after which each file of a linked program starts, so that lines can be told
+    /// relative to their file. Empty for a program of one file.
+    #[serde(skip)]
+    pub offsets: Vec<usize>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// `type Name = Type`, another name for a type.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TypeAlias {
+    pub name: LocatedIdentifier,
+    pub aliased: Type,
+}
+
+/// `data Name = ...`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TypeDeclaration {
+    pub name: LocatedIdentifier,
+    pub definition: TypeDefinition,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum TypeDefinition {
+    /// `{ field Type, ... }`
+    Record(Vec<Field>),
+    /// `Constructor Type ... | ...`
+    Sum(Vec<Constructor>),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Field {
+    pub name: LocatedIdentifier,
+    pub field_type: Type,
+}
+
+impl TypeDefinition {
+    /// Constructors of a sum type, none for a record.
+    pub fn constructors(&self) -> &[Constructor] {
+        match self {
+            TypeDefinition::Record(_) => &[],
+            TypeDefinition::Sum(constructors) => constructors,
+        }
+    }
+
+    pub fn constructor(&self, name: &Identifier) -> Option<&Constructor> {
+        self.constructors().iter().find(|c| c.name.id == *name)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Constructor {
+    pub name: LocatedIdentifier,
+    pub parameters: Vec<Type>,
+}
+
+/// `import "path.lm"`, optionally namespaced with `as name` or restricted to `(name ...)`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Import {
+    pub path: String,
+    pub alias: Option<Identifier>,
+    pub names: Option<Vec<LocatedIdentifier>>,
+    pub line: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Function {
-    pub signature: Signature,
+    /// Left out for functions whose types are inferred.
+    pub signature: Option<Signature>,
     pub definition: Definition,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Signature {
     pub parameters: Vec<Type>,
     pub returns: Type,
+    pub line: usize,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+impl Display for Signature {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        for parameter in self.parameters.iter() {
+            write!(f, "{} ", parameter)?;
+        }
+        write!(f, "-> {}", self.returns)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Definition {
     pub name: LocatedIdentifier,
     pub parameters: Vec<LocatedIdentifier>,
     pub body: Expression,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum Expression {
     Value(Value),
     Call(Call),
     Conditional(Conditional),
+    Case(Case),
+    Try(Try),
+    Raise(Raise),
+    Comprehension(Comprehension),
+    /// Body of a prelude function implemented by the interpreter, which reads its arguments from
+    /// the function's parameters.
+    Builtin(Identifier),
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum Call {
     Operation(Operation),
     FunctionCall(FunctionCall),
     Pipe(Pipe),
+    Construction(Construction),
+    Access(Access),
+    Variant(VariantCall),
+    Tuple(Tuple),
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Operation {
     pub operator: Operator,
     pub arguments: Vec<Argument>,
     pub line: usize,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct FunctionCall {
     pub name: LocatedIdentifier,
     pub arguments: Vec<Argument>,
     pub line: usize,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// `Name { field expression, ... }`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Construction {
+    pub name: LocatedIdentifier,
+    pub fields: Vec<(LocatedIdentifier, Expression)>,
+    pub line: usize,
+}
+
+/// `.field record`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Access {
+    pub field: Identifier,
+    pub record: Box<Argument>,
+    pub line: usize,
+}
+
+/// `Constructor argument ...`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct VariantCall {
+    pub name: LocatedIdentifier,
+    pub arguments: Vec<Argument>,
+    pub line: usize,
+}
+
+/// `(expression, expression, ...)`, with at least two elements.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Tuple {
+    pub elements: Vec<Expression>,
+    pub line: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Pipe {
     pub left: Box<Expression>,
     pub right: Identifier,
     pub line: usize,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Conditional {
     pub condition: Box<Expression>,
     pub then: Box<Expression>,
@@ -69,7 +189,71 @@ pub struct Conditional {
     pub line: usize,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// `case expression of | Pattern -> expression ...`
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Case {
+    pub subject: Box<Expression>,
+    pub arms: Vec<Arm>,
+    pub line: usize,
+}
+
+/// `try expression catch (message, line) -> expression`, where the pattern may also be `_`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Try {
+    pub body: Box<Expression>,
+    pub pattern: Pattern,
+    pub handler: Box<Expression>,
+    pub line: usize,
+}
+
+/// `raise expression`, failing with a message.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Raise {
+    pub message: Box<Expression>,
+    pub line: usize,
+}
+
+/// `[ expression | name <- list, condition, ... ]`, the values of the expression for each element
+/// of the lists that satisfies the conditions.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Comprehension {
+    pub element: Box<Expression>,
+    pub clauses: Vec<Clause>,
+    pub line: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum Clause {
+    /// `name <- list`, binding each element of the list in turn.
+    Generator {
+        name: LocatedIdentifier,
+        list: Expression,
+    },
+    /// A condition, skipping the elements for which it is false.
+    Guard(Expression),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Arm {
+    pub pattern: Pattern,
+    pub body: Expression,
+    pub line: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum Pattern {
+    /// `Constructor binding ...`, where `_` ignores a payload.
+    Variant {
+        name: LocatedIdentifier,
+        bindings: Vec<LocatedIdentifier>,
+    },
+    /// `(binding, ...)`, destructuring a tuple.
+    Tuple(Vec<LocatedIdentifier>),
+    /// `_`
+    Wildcard,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum Argument {
     Value(Value),
     Identifier(LocatedIdentifier),
@@ -87,4 +271,39 @@ pub struct List {
 pub enum Element {
     Value(Value),
     Identifier(Identifier),
+    Range(Box<Range>),
+}
+
+/// `start .. end` or `start, next .. end` in a list literal, whose values are computed as they are
+/// needed. Without an end, the range goes on forever.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Range {
+    pub start: Argument,
+    /// The second value, whose distance from the first is the step.
+    pub next: Option<Argument>,
+    pub end: Option<Argument>,
+}
+
+impl Range {
+    /// The bounds in the order they are written.
+    pub fn bounds(&self) -> impl Iterator<Item = &Argument> {
+        std::iter::once(&self.start)
+            .chain(self.next.as_ref())
+            .chain(self.end.as_ref())
+    }
+}
+
+/// Value of a record type.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Record {
+    pub name: Identifier,
+    pub fields: Vec<(Identifier, Value)>,
+}
+
+/// Value of a sum type, built by one of its constructors.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Variant {
+    pub type_name: Identifier,
+    pub name: Identifier,
+    pub values: Vec<Value>,
 }
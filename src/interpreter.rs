@@ -1,28 +1,123 @@
 use {
     crate::{
-        errors::CompilerError,
-        grammar::{Function, Program},
-        interpreter::environment::Environment,
+        args::ApplicationError,
+        checker,
+        errors::{self, CompilerError, InterpreterError},
+        grammar::{Function, Operation, Program, Signature},
+        interpreter::{environment::Environment, memo::Memo},
         tokens::{Identifier, Value},
     },
+    log::info,
     std::{
         cell::RefCell,
         collections::{hash_map::Entry, HashMap},
         rc::Rc,
+        str::FromStr,
     },
 };
 
 mod arguments;
 mod calls;
+mod comprehensions;
 mod data;
 mod environment;
+mod exceptions;
 mod expressions;
 mod functions;
+mod memo;
 mod operations;
 mod prelude;
+mod ranges;
+mod records;
+mod tuples;
+mod variants;
+
+/// What integer arithmetic does when its result does not fit in 64 bits.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Overflow {
+    /// Continue with arbitrary-precision integers
+    #[default]
+    Promote,
+    /// Stop with an overflow error
+    Error,
+}
+
+impl FromStr for Overflow {
+    type Err = ApplicationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "promote" => Ok(Overflow::Promote),
+            "error" => Ok(Overflow::Error),
+            _ => Err(ApplicationError::Args(format!(
+                "invalid overflow behaviour `{}` (expected promote or error)",
+                s
+            ))),
+        }
+    }
+}
+
+/// When the arguments of a function call are evaluated.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Evaluation {
+    /// Before the call, in order
+    #[default]
+    Strict,
+    /// The first time the function uses them, and only once
+    Lazy,
+}
+
+impl FromStr for Evaluation {
+    type Err = ApplicationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "strict" => Ok(Evaluation::Strict),
+            "lazy" => Ok(Evaluation::Lazy),
+            _ => Err(ApplicationError::Args(format!(
+                "invalid evaluation strategy `{}` (expected strict or lazy)",
+                s
+            ))),
+        }
+    }
+}
+
+/// How a program is evaluated.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Options {
+    pub overflow: Overflow,
+    pub evaluation: Evaluation,
+    /// How many results of function calls to cache, if any
+    pub memoise: Option<usize>,
+}
+
+/// The value of an operation whose arguments are literals, unless evaluating it fails. Integer
+/// overflow is an error, so that a result is the same however overflow is handled at runtime.
+pub fn fold(operation: &Operation) -> Option<Value> {
+    let env = Environment::new(HashMap::new()).with_overflow(Overflow::Error);
+    match operations::eval(Rc::new(RefCell::new(env)), operation) {
+        Ok(Some(Value::Lazy(_))) | Ok(None) | Err(_) => None,
+        Ok(Some(value)) => Some(value),
+    }
+}
 
 pub fn evaluate(program: Program) -> Result<Option<Value>, CompilerError> {
-    let enclosing = Environment::new(prelude::functions());
+    evaluate_with(program, Options::default())
+}
+
+/// Evaluates a program with the given options.
+pub fn evaluate_with(program: Program, options: Options) -> Result<Option<Value>, CompilerError> {
+    // Lazily mapped lists take the types of functions without a signature from the checker
+    let mut inferred = match program.functions.iter().any(|f| f.signature.is_none()) {
+        true => checker::signatures(&program),
+        false => HashMap::new(),
+    };
+    // The prelude is evaluated with the same options as the program. The offsets of its files give
+    // `try` handlers the line of an error within its file.
+    let enclosing = Environment::new(prelude::functions())
+        .with_overflow(options.overflow)
+        .with_evaluation(options.evaluation)
+        .with_offsets(program.offsets);
     let scope: HashMap<Identifier, Function> =
         program
             .functions
@@ -34,18 +129,54 @@ pub fn evaluate(program: Program) -> Result<Option<Value>, CompilerError> {
                         e.insert(f);
                         Ok(acc)
                     }
-                    Entry::Occupied(_) => Err(CompilerError::Interpreter(
-                        format!("variable `{}` already defined", key),
-                        f.definition.name.line,
-                    )),
+                    Entry::Occupied(_) => {
+                        Err(errors::already_defined(&key, f.definition.name.line))
+                    }
                 }
             })?;
 
-    let env = Environment::with_enclosing(scope, Rc::new(RefCell::new(enclosing)));
+    let types = program
+        .types
+        .into_iter()
+        .map(|t| (t.name.id, t.definition))
+        .collect();
+    inferred.retain(|id, _| scope.get(id).is_some_and(|f| f.signature.is_none()));
+    let pure = options.memoise.map(|_| memo::pure(&scope));
+    let mut env = Environment::with_enclosing(scope, Rc::new(RefCell::new(enclosing)))
+        .with_types(types)
+        .with_inferred(inferred);
+    let memo = options
+        .memoise
+        .zip(pure)
+        .map(|(capacity, pure)| Rc::new(RefCell::new(Memo::new(capacity, pure))));
+    if let Some(memo) = &memo {
+        env = env.with_memo(Rc::clone(memo));
+    }
 
     let main = env
         .get(&"main".to_string())
-        .ok_or_else(|| CompilerError::Interpreter("main function not found".to_string(), 0))?;
+        .ok_or(CompilerError::Interpreter(
+            InterpreterError::MainNotFound,
+            0,
+        ))?;
+
+    // Lazy lists are computed before the result leaves the interpreter
+    let result = functions::eval(Rc::new(RefCell::new(env)), &main)
+        .and_then(|value| value.map(data::force).transpose());
+    if let Some(memo) = memo {
+        let memo = memo.borrow();
+        info!(
+            "Memoisation: {} hits, {} misses, {} evictions",
+            memo.hits, memo.misses, memo.evictions
+        );
+    }
+    result
+}
 
-    functions::eval(Rc::new(RefCell::new(env)), &main)
+/// Signatures of the functions available to every program.
+pub fn prelude_signatures() -> HashMap<Identifier, Signature> {
+    prelude::functions()
+        .into_iter()
+        .filter_map(|(id, f)| f.signature.map(|s| (id, s)))
+        .collect()
 }
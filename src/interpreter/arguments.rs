@@ -3,22 +3,22 @@ use {
         errors,
         errors::CompilerError,
         grammar::Argument,
-        interpreter::{environment::Environment, expressions, functions},
-        tokens::Value,
+        interpreter::{data, environment::Environment, expressions, functions},
+        tokens::{Identifier, Value},
     },
     std::{cell::RefCell, rc::Rc},
 };
 
 pub fn eval(env: Rc<RefCell<Environment>>, arg: &Argument) -> Result<Value, CompilerError> {
     match arg {
+        // Ranges and variables in list literals are evaluated like those of a list expression
+        Argument::Value(Value::List(l)) => Ok(data::eval_list(env, l)?.expect("a list")),
         Argument::Value(v) => Ok(v.clone()),
         Argument::Identifier(l_id) => {
-            let value = env
-                .borrow()
-                .get(&l_id.id)
-                .map(|f| functions::eval(Rc::clone(&env), &f));
+            let value = lookup(Rc::clone(&env), &l_id.id);
             if value.is_none() {
-                return Err(errors::undefined_argument(&l_id.id, l_id.line));
+                let suggestion = env.borrow().suggest(&l_id.id);
+                return Err(errors::undefined_argument(&l_id.id, suggestion, l_id.line));
             }
             let value = value.unwrap()?;
             Ok(value.unwrap())
@@ -28,11 +28,21 @@ pub fn eval(env: Rc<RefCell<Environment>>, arg: &Argument) -> Result<Value, Comp
             if let Some(v) = result {
                 Ok(v)
             } else {
-                Err(CompilerError::Interpreter(
-                    "parenthesized expression did not evaluate to a value".to_string(),
-                    0,
-                ))
+                Err(errors::missing_value("parenthesized expression", 0))
             }
         }
     }
 }
+
+/// The value of a name, bound either to a function of no parameters or to a thunk.
+pub fn lookup(
+    env: Rc<RefCell<Environment>>,
+    id: &Identifier,
+) -> Option<Result<Option<Value>, CompilerError>> {
+    let thunk = env.borrow().thunk(id);
+    if let Some(thunk) = thunk {
+        return Some(thunk.force().map(Some));
+    }
+    let function = env.borrow().get(id);
+    function.map(|f| functions::eval(env, &f))
+}
@@ -6,8 +6,12 @@ use {
             Argument, Call, Definition, Element, Expression, Function, FunctionCall, List, Pipe,
             Signature,
         },
-        interpreter::{arguments, environment::Environment, functions, operations},
-        tokens::{Identifier, LocatedIdentifier, Value},
+        interpreter::{
+            arguments, data,
+            environment::{Environment, Thunk},
+            functions, memo, operations, prelude, records, tuples, variants, Evaluation,
+        },
+        tokens::{Identifier, Lazy, LocatedIdentifier, Type, Value},
     },
     std::{cell::RefCell, collections::HashMap, rc::Rc},
 };
@@ -16,26 +20,100 @@ pub fn eval(
     env: Rc<RefCell<Environment>>,
     fc: &FunctionCall,
 ) -> Result<Option<Value>, CompilerError> {
-    let function = env
-        .borrow()
-        .get(&fc.name.id)
-        .ok_or_else(|| errors::undefined_variable(&fc.name.id, fc.line))?;
+    // A parameter bound to a thunk is a value, computed the first time it is used
+    if fc.arguments.is_empty() {
+        let thunk = env.borrow().thunk(&fc.name.id);
+        if let Some(thunk) = thunk {
+            return thunk.force().map(Some);
+        }
+    }
+
+    let function = env.borrow().get(&fc.name.id).ok_or_else(|| {
+        let suggestion = env.borrow().suggest(&fc.name.id);
+        errors::undefined_variable(&fc.name.id, suggestion, fc.line)
+    })?;
 
     let params = &function.definition.parameters;
 
-    let bindings: HashMap<Identifier, Function> = params
+    // Type variables are bound to the types of the arguments they are first matched against
+    let mut types: HashMap<Identifier, Type> = HashMap::new();
+    let expected = function.signature.as_ref().map(|s| s.parameters.as_slice());
+
+    // Lazy arguments are delayed unless they are already values, which are checked like strict ones
+    let lazy = env.borrow().evaluation() == Evaluation::Lazy;
+    let (delayed, evaluated): (Vec<_>, Vec<_>) = params
         .iter()
         .zip(fc.arguments.iter())
-        .map(|(id, arg)| {
+        .enumerate()
+        .partition(|(_, (_, arg))| match arg {
+            Argument::Value(Value::List(_)) => lazy,
+            Argument::Value(_) => false,
+            _ => lazy,
+        });
+    let thunks: HashMap<Identifier, Rc<Thunk>> = delayed
+        .into_iter()
+        .map(|(_, (id, arg))| {
+            let thunk = Thunk::new(arg.clone(), Rc::clone(&env));
+            (id.id.clone(), Rc::new(thunk))
+        })
+        .collect();
+
+    let values: Vec<(Identifier, Value)> = evaluated
+        .into_iter()
+        .map(|(i, (id, arg))| {
             let value = arguments::eval(Rc::clone(&env), arg)?;
-            let function = to_fn(value);
-            Ok((id.clone().id, function))
+            let expected = match expected.and_then(|parameters| parameters.get(i)) {
+                Some(t) if t.variable().is_some() => t,
+                _ => return Ok((id.clone().id, value)),
+            };
+            if !expected.unify(&value.get_type(), &mut types) {
+                return Err(errors::unexpected_type(
+                    &expected.substitute(&types),
+                    &value.get_type(),
+                    fc.line,
+                ));
+            }
+            Ok((id.clone().id, value))
         })
         .collect::<Result<_, _>>()?;
 
-    let env = Environment::with_enclosing(bindings, Rc::clone(&env));
+    // A call of a pure function with the same arguments has the same result. Builtins are cheap,
+    // and delayed arguments would have to be evaluated to make a key.
+    let memo = env.borrow().memo();
+    let key = match (&memo, &function.definition.body) {
+        (Some(_), Expression::Builtin(_)) => None,
+        (Some(memo), _)
+            if memo.borrow().caches(&fc.name.id) && thunks.is_empty() && !values.is_empty() =>
+        {
+            memo::key(&fc.name.id, values.iter().map(|(_, v)| v))
+        }
+        _ => None,
+    };
+    if let (Some(memo), Some(key)) = (&memo, &key) {
+        if let Some(result) = memo.borrow_mut().get(key) {
+            return Ok(result);
+        }
+    }
+
+    let bindings: HashMap<Identifier, Function> = values
+        .into_iter()
+        .map(|(id, value)| (id, to_fn(value)))
+        .collect();
+    // Prelude functions only see the prelude, whatever the program calling them defines
+    let enclosing = Environment::prelude(&env, &fc.name.id).unwrap_or_else(|| Rc::clone(&env));
+    let env = Environment::with_enclosing(bindings, enclosing)
+        .with_thunks(thunks)
+        .with_type_variables(types);
 
-    let result = functions::eval(Rc::new(RefCell::new(env)), &function)?;
+    let env = Rc::new(RefCell::new(env));
+    // Builtins have no source of their own, so their errors are reported at the call
+    let result = match &function.definition.body {
+        Expression::Builtin(name) => prelude::eval(env, name, fc.line)?,
+        _ => functions::eval(env, &function)?,
+    };
+    if let (Some(memo), Some(key)) = (memo, key) {
+        memo.borrow_mut().insert(key, result.clone());
+    }
     Ok(result)
 }
 
@@ -44,61 +122,79 @@ pub fn eval_pipe(
     pipe: &Pipe,
 ) -> Result<Option<Value>, CompilerError> {
     let arg = match *pipe.left {
+        Expression::Value(Value::List(ref l)) => {
+            Ok(data::eval_list(Rc::clone(&env), l)?.expect("a list"))
+        }
         Expression::Value(ref v) => Ok(v.clone()),
         Expression::Call(ref call) => {
             let result = match call {
                 Call::Operation(op) => operations::eval(Rc::clone(&env), op),
                 Call::FunctionCall(fc) => eval(Rc::clone(&env), fc),
                 Call::Pipe(p) => eval_pipe(Rc::clone(&env), p),
+                Call::Construction(c) => records::construct(Rc::clone(&env), c),
+                Call::Access(a) => records::access(Rc::clone(&env), a),
+                Call::Variant(v) => variants::construct(Rc::clone(&env), v),
+                Call::Tuple(t) => tuples::construct(Rc::clone(&env), t),
             };
 
-            result?.ok_or_else(|| {
-                CompilerError::Interpreter(
-                    "Expected expression on left side of pipe, found none".to_string(),
-                    pipe.line,
-                )
-            })
+            result?.ok_or_else(|| errors::missing_value("left side of pipe", pipe.line))
         }
         _ => {
-            return Err(CompilerError::Interpreter(
-                "Expected expression on left side of pipe".to_string(),
-                pipe.line,
-            ));
+            return Err(errors::missing_value("left side of pipe", pipe.line));
         }
     }?;
 
+    // Piping a `Maybe` maps the function over the value if there is one
+    if let Value::Maybe(maybe) = arg {
+        return match maybe {
+            Some(value) => {
+                let result = apply(Rc::clone(&env), &pipe.right, *value, pipe.line)?
+                    .ok_or_else(|| errors::missing_value("result of pipe", pipe.line))?;
+                Ok(Some(Value::Maybe(Some(Box::new(result)))))
+            }
+            None => Ok(Some(Value::Maybe(None))),
+        };
+    }
+
+    // Mapping gives a list of the function's return type, or of the type of its results if it has
+    // no signature
+    if let Value::Lazy(lazy) = arg {
+        // Elements are mapped as they are needed, so that infinite lists can be piped, and none
+        // are mapped to find their type
+        let element_type = mapped_type(&env, &pipe.right, &lazy.element_type)
+            .unwrap_or_else(|| lazy.element_type.clone());
+        let (function, line) = (pipe.right.clone(), pipe.line);
+        return Ok(Some(Value::Lazy(Lazy::new(element_type, move || {
+            let (env, function) = (Rc::clone(&env), function.clone());
+            Box::new(lazy.iter().map(move |v| {
+                apply(Rc::clone(&env), &function, v?, line)?
+                    .ok_or_else(|| errors::missing_value("result of pipe", line))
+            }))
+        }))));
+    }
+
     if let Value::List(ref list) = arg {
-        let mut resolved: Vec<Value> = Vec::new();
-        for elem in list.elements.iter() {
-            let v = match elem {
+        let declared = mapped_type(&env, &pipe.right, &list.list_type);
+        let element_type = list.list_type.clone();
+        let resolved: Vec<Value> = list
+            .elements
+            .iter()
+            .map(|elem| match elem {
                 Element::Value(v) => v.clone(),
-                Element::Identifier(id) => env
-                    .borrow()
-                    .get(id)
-                    .map(|f| functions::eval(Rc::clone(&env), &f))
-                    .ok_or_else(|| errors::undefined_variable(id, pipe.line))??
-                    .ok_or_else(|| errors::undefined_argument(id, pipe.line))?,
-            };
-            resolved.push(v);
-        }
+                _ => unreachable!("list elements are evaluated with the list"),
+            })
+            .collect();
 
         let list = resolved
-            .iter()
-            .map(|v| {
-                let function_call = FunctionCall {
-                    name: LocatedIdentifier {
-                        id: pipe.right.clone(),
-                        line: pipe.line,
-                    },
-                    arguments: vec![Argument::Value(v.clone())],
-                    line: pipe.line,
-                };
-                eval(Rc::clone(&env), &function_call)
-            })
+            .into_iter()
+            .map(|v| apply(Rc::clone(&env), &pipe.right, v, pipe.line))
             .collect::<Result<Vec<Option<Value>>, CompilerError>>()?;
 
+        let list_type = declared
+            .or_else(|| list.iter().flatten().next().map(Value::get_type))
+            .unwrap_or(element_type);
         return Ok(Some(Value::List(List {
-            list_type: arg.get_type(),
+            list_type,
             elements: list
                 .into_iter()
                 .map(|v| Element::Value(v.unwrap()))
@@ -107,24 +203,56 @@ pub fn eval_pipe(
         })));
     }
 
+    apply(env, &pipe.right, arg, pipe.line)
+}
+
+/// Calls a function of one parameter with a value.
+fn apply(
+    env: Rc<RefCell<Environment>>,
+    function: &Identifier,
+    value: Value,
+    line: usize,
+) -> Result<Option<Value>, CompilerError> {
     let function_call = FunctionCall {
         name: LocatedIdentifier {
-            id: pipe.right.clone(),
-            line: pipe.line,
+            id: function.clone(),
+            line,
         },
-        arguments: vec![Argument::Value(arg)],
-        line: pipe.line,
+        arguments: vec![Argument::Value(value)],
+        line,
     };
-    eval(Rc::clone(&env), &function_call)
+    eval(env, &function_call)
+}
+
+/// The return type of a function mapped over a list with elements of a type, as declared, or as
+/// inferred if it is known.
+fn mapped_type(
+    env: &Rc<RefCell<Environment>>,
+    function: &Identifier,
+    element_type: &Type,
+) -> Option<Type> {
+    let declared = env.borrow().get(function).and_then(|f| f.signature);
+    let (signature, inferred) = match declared {
+        Some(s) => (s, false),
+        None => (env.borrow().inferred(function)?, true),
+    };
+    if signature.parameters.len() != 1 {
+        return None;
+    }
+    let mut types = HashMap::new();
+    signature.parameters[0].unify(element_type, &mut types);
+    let returns = signature.returns.substitute(&types);
+    (!inferred || returns.variable().is_none()).then_some(returns)
 }
 
 // 🤔
-fn to_fn(value: Value) -> Function {
+pub fn to_fn(value: Value) -> Function {
     Function {
-        signature: Signature {
+        signature: Some(Signature {
             parameters: vec![],
             returns: value.get_type(),
-        },
+            line: 0,
+        }),
         definition: Definition {
             name: LocatedIdentifier {
                 id: value.to_string(),
@@ -0,0 +1,74 @@
+use {
+    crate::{
+        errors::{self, CompilerError},
+        grammar::{Clause, Comprehension, Element},
+        interpreter::{calls, environment::Environment, expressions},
+        tokens::{Elements, Lazy, Type, Value},
+    },
+    std::{cell::RefCell, collections::HashMap, iter, rc::Rc},
+};
+
+/// Evaluates a comprehension into a lazy list, so that its generators may be infinite.
+pub fn eval(
+    env: Rc<RefCell<Environment>>,
+    comprehension: &Comprehension,
+) -> Result<Option<Value>, CompilerError> {
+    let comprehension = Rc::new(comprehension.clone());
+    // The type of the elements is not known until one is computed
+    let element_type = Type::Var("a".into());
+    let lazy = Lazy::new(element_type, move || {
+        clauses(Rc::clone(&env), Rc::clone(&comprehension), 0)
+    });
+    Ok(Some(Value::Lazy(lazy)))
+}
+
+/// Elements given by the clauses from `index` on, with the names of earlier generators in `env`.
+fn clauses(
+    env: Rc<RefCell<Environment>>,
+    comprehension: Rc<Comprehension>,
+    index: usize,
+) -> Elements {
+    let line = comprehension.line;
+    let failed = |err| -> Elements { Box::new(iter::once(Err(err))) };
+    match comprehension.clauses.get(index).cloned() {
+        None => {
+            let element = expressions::eval(env, &comprehension.element).and_then(|v| {
+                v.ok_or_else(|| errors::missing_value("element of comprehension", line))
+            });
+            Box::new(iter::once(element))
+        }
+        Some(Clause::Guard(condition)) => match expressions::eval(Rc::clone(&env), &condition) {
+            Ok(Some(Value::Boolean(true))) => clauses(env, comprehension, index + 1),
+            Ok(Some(Value::Boolean(false))) => Box::new(iter::empty()),
+            Ok(Some(v)) => failed(errors::unexpected_type(&Type::Bool, &v.get_type(), line)),
+            Ok(None) => failed(errors::missing_value("condition of comprehension", line)),
+            Err(err) => failed(err),
+        },
+        Some(Clause::Generator { name, list }) => {
+            let values: Elements = match expressions::eval(Rc::clone(&env), &list) {
+                Ok(Some(Value::List(list))) => {
+                    Box::new(list.elements.into_iter().map(|e| match e {
+                        Element::Value(v) => Ok(v),
+                        _ => unreachable!("list elements are evaluated with the list"),
+                    }))
+                }
+                Ok(Some(Value::Lazy(lazy))) => lazy.iter(),
+                Ok(Some(v)) => failed(errors::unexpected_type_class("list", &v.get_type(), line)),
+                Ok(None) => failed(errors::missing_value("list of generator", line)),
+                Err(err) => failed(err),
+            };
+            Box::new(values.flat_map(move |value| match value {
+                Ok(value) => {
+                    let scope = HashMap::from([(name.id.clone(), calls::to_fn(value))]);
+                    let env = Environment::with_enclosing(scope, Rc::clone(&env));
+                    clauses(
+                        Rc::new(RefCell::new(env)),
+                        Rc::clone(&comprehension),
+                        index + 1,
+                    )
+                }
+                Err(err) => failed(err),
+            }))
+        }
+    }
+}
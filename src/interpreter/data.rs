@@ -1,59 +1,95 @@
 use {
     crate::{
-        errors::{undefined_argument, undefined_variable, CompilerError},
-        grammar::{Element, List},
-        interpreter::{environment::Environment, functions},
+        errors::{list_type_mismatch, undefined_argument, undefined_variable, CompilerError},
+        grammar::{Element, List, Record, Variant},
+        interpreter::{arguments, environment::Environment, ranges},
         tokens::Value,
     },
-    std::{cell::RefCell, rc::Rc},
+    std::{cell::RefCell, collections::HashMap, rc::Rc},
 };
 
 pub fn eval_list(env: Rc<RefCell<Environment>>, l: &List) -> Result<Option<Value>, CompilerError> {
-    let list_type = l.list_type.clone();
+    // A range is the only element of its list, and is evaluated lazily
+    if let [Element::Range(r)] = l.elements.as_slice() {
+        let list_type = env.borrow().resolve(&l.list_type);
+        let lazy = ranges::eval(env, r, &list_type, l.line)?;
+        return Ok(Some(Value::Lazy(lazy)));
+    }
+    Ok(Some(Value::List(resolve(env, l)?)))
+}
+
+/// Computes the elements of lazy lists within a value, which never finishes if one is infinite.
+pub fn force(value: Value) -> Result<Value, CompilerError> {
+    let force_all = |values: Vec<Value>| -> Result<Vec<Value>, CompilerError> {
+        values.into_iter().map(force).collect()
+    };
+    Ok(match value {
+        Value::Lazy(lazy) => Value::List(List {
+            list_type: lazy.element_type.clone(),
+            elements: lazy
+                .iter()
+                .map(|v| force(v?).map(Element::Value))
+                .collect::<Result<_, _>>()?,
+            line: 0,
+        }),
+        Value::List(list) => Value::List(List {
+            elements: list
+                .elements
+                .into_iter()
+                .map(|e| match e {
+                    Element::Value(v) => force(v).map(Element::Value),
+                    e => Ok(e),
+                })
+                .collect::<Result<_, _>>()?,
+            ..list
+        }),
+        Value::Record(record) => Value::Record(Record {
+            fields: record
+                .fields
+                .into_iter()
+                .map(|(name, v)| Ok((name, force(v)?)))
+                .collect::<Result<_, CompilerError>>()?,
+            ..record
+        }),
+        Value::Variant(variant) => Value::Variant(Variant {
+            values: force_all(variant.values)?,
+            ..variant
+        }),
+        Value::Tuple(values) => Value::Tuple(force_all(values)?),
+        Value::Maybe(Some(v)) => Value::Maybe(Some(Box::new(force(*v)?))),
+        v => v,
+    })
+}
+
+/// Evaluates the elements of a list literal, including those of nested lists.
+fn resolve(env: Rc<RefCell<Environment>>, l: &List) -> Result<List, CompilerError> {
+    // Type variables not bound by the enclosing function are bound by the first element
+    let list_type = env.borrow().resolve(&l.list_type);
+    let mut bindings = HashMap::new();
     let mut resolved: Vec<Element> = Vec::new();
     for e in l.elements.iter() {
-        match e {
-            Element::Value(v) => {
-                if v.get_type() != list_type {
-                    return Err(CompilerError::Interpreter(
-                        format!(
-                            "list type mismatch: expected {}, found {}",
-                            list_type,
-                            v.get_type()
-                        ),
-                        l.line,
-                    ));
-                }
-                resolved.push(Element::Value(v.clone()));
+        let value = match e {
+            // Nested lists may refer to variables
+            Element::Value(Value::List(nested)) => {
+                eval_list(Rc::clone(&env), nested)?.expect("a list")
             }
-            Element::Identifier(i) => {
-                let value = env
-                    .borrow()
-                    .get(i)
-                    .map(|f| functions::eval(Rc::clone(&env), &f))
-                    .ok_or_else(|| undefined_variable(i, l.line))??
-                    .ok_or_else(|| undefined_argument(i, l.line))?;
-
-                if value.get_type() != list_type {
-                    return Err(CompilerError::Interpreter(
-                        format!(
-                            "list type mismatch: expected {}, found {}",
-                            list_type,
-                            value.get_type()
-                        ),
-                        l.line,
-                    ));
-                }
+            Element::Value(v) => v.clone(),
+            Element::Identifier(i) => arguments::lookup(Rc::clone(&env), i)
+                .ok_or_else(|| undefined_variable(i, env.borrow().suggest(i), l.line))??
+                .ok_or_else(|| undefined_argument(i, None, l.line))?,
+            Element::Range(_) => unreachable!("a range is the only element of its list"),
+        };
 
-                resolved.push(Element::Value(value));
-            }
+        if !list_type.unify(&value.get_type(), &mut bindings) {
+            let expected = list_type.substitute(&bindings);
+            return Err(list_type_mismatch(&expected, &value.get_type(), l.line));
         }
+        resolved.push(Element::Value(value));
     }
 
-    let list = List {
-        list_type,
+    Ok(List {
+        list_type: list_type.substitute(&bindings),
         elements: resolved,
         line: l.line,
-    };
-    Ok(Some(Value::List(list)))
+    })
 }
@@ -1,10 +1,51 @@
 use {
-    crate::{grammar::Function, tokens::Identifier},
+    crate::{
+        errors::CompilerError,
+        grammar::{Argument, Constructor, Function, Signature, TypeDefinition},
+        interpreter::{arguments, memo::Memo, Evaluation, Overflow},
+        tokens::{Identifier, Type, Value},
+        utils,
+    },
     std::{cell::RefCell, collections::HashMap, rc::Rc},
 };
 
+/// An argument evaluated the first time its parameter is used, in the scope of the call.
+pub struct Thunk {
+    argument: Argument,
+    env: Rc<RefCell<Environment>>,
+    value: RefCell<Option<Value>>,
+}
+
+impl Thunk {
+    pub fn new(argument: Argument, env: Rc<RefCell<Environment>>) -> Self {
+        Self {
+            argument,
+            env,
+            value: RefCell::new(None),
+        }
+    }
+
+    /// The value of the argument, which is only computed once.
+    pub fn force(&self) -> Result<Value, CompilerError> {
+        if let Some(value) = self.value.borrow().as_ref() {
+            return Ok(value.clone());
+        }
+        let value = arguments::eval(Rc::clone(&self.env), &self.argument)?;
+        *self.value.borrow_mut() = Some(value.clone());
+        Ok(value)
+    }
+}
+
 pub struct Environment {
     scope: HashMap<Identifier, Function>,
+    thunks: HashMap<Identifier, Rc<Thunk>>,
+    types: HashMap<Identifier, TypeDefinition>,
+    inferred: HashMap<Identifier, Signature>,
+    type_variables: HashMap<Identifier, Type>,
+    overflow: Option<Overflow>,
+    evaluation: Option<Evaluation>,
+    memo: Option<Rc<RefCell<Memo>>>,
+    offsets: Vec<usize>,
     enclosing: Option<Rc<RefCell<Environment>>>,
 }
 
@@ -12,6 +53,14 @@ impl Environment {
     pub fn new(scope: HashMap<Identifier, Function>) -> Self {
         Self {
             scope,
+            thunks: HashMap::new(),
+            types: HashMap::new(),
+            inferred: HashMap::new(),
+            type_variables: HashMap::new(),
+            overflow: None,
+            evaluation: None,
+            memo: None,
+            offsets: Vec::new(),
             enclosing: None,
         }
     }
@@ -22,17 +71,223 @@ impl Environment {
     ) -> Self {
         Self {
             scope,
+            thunks: HashMap::new(),
+            types: HashMap::new(),
+            inferred: HashMap::new(),
+            type_variables: HashMap::new(),
+            overflow: None,
+            evaluation: None,
+            memo: None,
+            offsets: Vec::new(),
             enclosing: Some(enclosing),
         }
     }
 
+    /// Declares the types visible from this scope.
+    pub fn with_types(mut self, types: HashMap<Identifier, TypeDefinition>) -> Self {
+        self.types = types;
+        self
+    }
+
+    /// Gives the signatures inferred for the functions of this scope that have none.
+    pub fn with_inferred(mut self, inferred: HashMap<Identifier, Signature>) -> Self {
+        self.inferred = inferred;
+        self
+    }
+
+    /// The signature inferred for a function without one.
+    pub fn inferred(&self, id: &Identifier) -> Option<Signature> {
+        match self.inferred.get(id) {
+            Some(s) => Some(s.clone()),
+            None => self
+                .enclosing
+                .as_ref()
+                .and_then(|env| env.borrow().inferred(id)),
+        }
+    }
+
+    /// Binds the type variables of the function evaluated in this scope.
+    pub fn with_type_variables(mut self, type_variables: HashMap<Identifier, Type>) -> Self {
+        self.type_variables = type_variables;
+        self
+    }
+
+    /// Sets how integer overflow is handled from this scope on.
+    pub fn with_overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = Some(overflow);
+        self
+    }
+
+    /// Binds parameters to arguments that are evaluated when they are needed.
+    pub fn with_thunks(mut self, thunks: HashMap<Identifier, Rc<Thunk>>) -> Self {
+        self.thunks = thunks;
+        self
+    }
+
+    /// Sets when function arguments are evaluated from this scope on.
+    pub fn with_evaluation(mut self, evaluation: Evaluation) -> Self {
+        self.evaluation = Some(evaluation);
+        self
+    }
+
+    /// When function arguments are evaluated, as set by this scope or the closest enclosing one.
+    pub fn evaluation(&self) -> Evaluation {
+        match (self.evaluation, &self.enclosing) {
+            (Some(evaluation), _) => evaluation,
+            (None, Some(env)) => env.borrow().evaluation(),
+            (None, None) => Evaluation::default(),
+        }
+    }
+
+    /// Caches the results of calls from this scope on.
+    pub fn with_memo(mut self, memo: Rc<RefCell<Memo>>) -> Self {
+        self.memo = Some(memo);
+        self
+    }
+
+    /// The cache of call results, if calls are memoised.
+    pub fn memo(&self) -> Option<Rc<RefCell<Memo>>> {
+        match (&self.memo, &self.enclosing) {
+            (Some(memo), _) => Some(Rc::clone(memo)),
+            (None, Some(env)) => env.borrow().memo(),
+            (None, None) => None,
+        }
+    }
+
+    /// How integer overflow is handled, as set by this scope or the closest enclosing one.
+    pub fn overflow(&self) -> Overflow {
+        match (self.overflow, &self.enclosing) {
+            (Some(overflow), _) => overflow,
+            (None, Some(env)) => env.borrow().overflow(),
+            (None, None) => Overflow::default(),
+        }
+    }
+
+    /// Sets the lines after which the files of the program start.
+    pub fn with_offsets(mut self, offsets: Vec<usize>) -> Self {
+        self.offsets = offsets;
+        self
+    }
+
+    /// A line of the program relative to the file it is in.
+    pub fn file_line(&self, line: usize) -> usize {
+        match &self.enclosing {
+            Some(env) if self.offsets.is_empty() => env.borrow().file_line(line),
+            _ => self
+                .offsets
+                .iter()
+                .rev()
+                .find(|offset| **offset < line)
+                .map_or(line, |offset| line - offset),
+        }
+    }
+
+    /// Replaces the type variables bound in this scope or enclosing ones.
+    pub fn resolve(&self, t: &Type) -> Type {
+        let t = t.substitute(&self.type_variables);
+        match &self.enclosing {
+            Some(env) if t.variable().is_some() => env.borrow().resolve(&t),
+            _ => t,
+        }
+    }
+
+    pub fn type_definition(&self, name: &Identifier) -> Option<TypeDefinition> {
+        match self.types.get(name) {
+            Some(t) => Some(t.clone()),
+            None => self
+                .enclosing
+                .as_ref()
+                .and_then(|env| env.borrow().type_definition(name)),
+        }
+    }
+
+    /// Every type name visible from this scope.
+    pub fn type_names(&self) -> Vec<Identifier> {
+        let mut names: Vec<Identifier> = self.types.keys().cloned().collect();
+        if let Some(env) = &self.enclosing {
+            names.extend(env.borrow().type_names());
+        }
+        names
+    }
+
+    /// The constructor of a sum type visible from this scope, with the name of its type.
+    pub fn constructor(&self, name: &Identifier) -> Option<(Identifier, Constructor)> {
+        let found = self
+            .types
+            .iter()
+            .find_map(|(t, d)| d.constructor(name).map(|c| (t.clone(), c.clone())));
+        found.or_else(|| {
+            self.enclosing
+                .as_ref()
+                .and_then(|env| env.borrow().constructor(name))
+        })
+    }
+
+    /// Every constructor name visible from this scope.
+    pub fn constructor_names(&self) -> Vec<Identifier> {
+        let mut names: Vec<Identifier> = self
+            .types
+            .values()
+            .flat_map(|d| d.constructors().iter().map(|c| c.name.id.clone()))
+            .collect();
+        if let Some(env) = &self.enclosing {
+            names.extend(env.borrow().constructor_names());
+        }
+        names
+    }
+
+    /// Every name visible from this scope, including those of enclosing scopes.
+    pub fn names(&self) -> Vec<Identifier> {
+        let mut names: Vec<Identifier> = self
+            .scope
+            .keys()
+            .chain(self.thunks.keys())
+            .cloned()
+            .collect();
+        if let Some(env) = &self.enclosing {
+            names.extend(env.borrow().names());
+        }
+        names
+    }
+
+    /// Closest visible name to an undefined one.
+    pub fn suggest(&self, id: &Identifier) -> Option<Identifier> {
+        utils::closest(id, self.names())
+    }
+
     pub fn get(&self, id: &Identifier) -> Option<Function> {
         match self.scope.get(id) {
             Some(f) => Some(f.clone()),
+            // A parameter bound to a thunk shadows the functions of enclosing scopes
+            None if self.thunks.contains_key(id) => None,
             None => match &self.enclosing {
                 Some(env) => env.borrow().get(id),
                 None => None,
             },
         }
     }
+
+    /// The scope of the prelude, if a name refers to one of its functions rather than to one the
+    /// program defines or binds.
+    pub fn prelude(env: &Rc<RefCell<Environment>>, id: &Identifier) -> Option<Rc<RefCell<Self>>> {
+        let scope = env.borrow();
+        match &scope.enclosing {
+            _ if scope.thunks.contains_key(id) => None,
+            Some(_) if scope.scope.contains_key(id) => None,
+            Some(enclosing) => Environment::prelude(enclosing, id),
+            None => scope.scope.contains_key(id).then(|| Rc::clone(env)),
+        }
+    }
+
+    /// The thunk bound to a parameter, unless a closer scope binds the name to a function.
+    pub fn thunk(&self, id: &Identifier) -> Option<Rc<Thunk>> {
+        match self.thunks.get(id) {
+            Some(t) => Some(Rc::clone(t)),
+            None if self.scope.contains_key(id) => None,
+            None => match &self.enclosing {
+                Some(env) => env.borrow().thunk(id),
+                None => None,
+            },
+        }
+    }
 }
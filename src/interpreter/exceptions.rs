@@ -0,0 +1,53 @@
+use {
+    crate::{
+        errors::{self, CompilerError},
+        grammar::{Pattern, Raise, Try},
+        interpreter::{calls, data, environment::Environment, expressions},
+        tokens::{Identifier, Type, Value},
+    },
+    std::{cell::RefCell, collections::HashMap, rc::Rc},
+};
+
+/// Evaluates the body, or the handler if it fails, with the error's message and line in its file
+/// bound by the handler's pattern. Lazy lists in the body's value are computed, so that their errors are
+/// caught too.
+pub fn eval_try(
+    env: Rc<RefCell<Environment>>,
+    attempt: &Try,
+) -> Result<Option<Value>, CompilerError> {
+    let body = expressions::eval(Rc::clone(&env), &attempt.body)
+        .and_then(|value| value.map(data::force).transpose());
+    let (err, line) = match body {
+        Err(CompilerError::Interpreter(err, line)) => (err, line),
+        result => return result,
+    };
+
+    let line = env.borrow().file_line(line);
+    let error = [Value::String(err.to_string()), Value::Integer(line as i64)];
+    let scope: HashMap<Identifier, _> = match &attempt.pattern {
+        Pattern::Tuple(bindings) => bindings
+            .iter()
+            .zip(error)
+            .filter(|(binding, _)| binding.id != "_")
+            .map(|(binding, value)| (binding.id.clone(), calls::to_fn(value)))
+            .collect(),
+        _ => HashMap::new(),
+    };
+    let env = Environment::with_enclosing(scope, env);
+    expressions::eval(Rc::new(RefCell::new(env)), &attempt.handler)
+}
+
+pub fn eval_raise(
+    env: Rc<RefCell<Environment>>,
+    raise: &Raise,
+) -> Result<Option<Value>, CompilerError> {
+    match expressions::eval(env, &raise.message)? {
+        Some(Value::String(message)) => Err(errors::raised(&message, raise.line)),
+        Some(value) => Err(errors::unexpected_type(
+            &Type::Str,
+            &value.get_type(),
+            raise.line,
+        )),
+        None => Err(errors::missing_value("raised message", raise.line)),
+    }
+}
@@ -3,7 +3,10 @@ use {
         errors,
         errors::CompilerError,
         grammar::{Call, Conditional, Expression},
-        interpreter::{calls, data, environment::Environment, operations},
+        interpreter::{
+            calls, comprehensions, data, environment::Environment, exceptions, operations, records,
+            tuples, variants,
+        },
         tokens::{Type, Value},
     },
     std::{cell::RefCell, rc::Rc},
@@ -25,8 +28,17 @@ pub fn eval(
             Call::Operation(op) => operations::eval(env, op),
             Call::FunctionCall(fc) => calls::eval(env, fc),
             Call::Pipe(p) => calls::eval_pipe(env, p),
+            Call::Construction(c) => records::construct(env, c),
+            Call::Access(a) => records::access(env, a),
+            Call::Variant(v) => variants::construct(env, v),
+            Call::Tuple(t) => tuples::construct(env, t),
         },
         Expression::Conditional(c) => eval_conditional(env, c),
+        Expression::Case(c) => variants::eval_case(env, c),
+        Expression::Try(t) => exceptions::eval_try(env, t),
+        Expression::Raise(r) => exceptions::eval_raise(env, r),
+        Expression::Comprehension(c) => comprehensions::eval(env, c),
+        Expression::Builtin(_) => unreachable!("builtins are evaluated by the calls to them"),
     }
 }
 
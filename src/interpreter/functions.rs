@@ -1,6 +1,6 @@
 use {
     crate::{
-        errors::CompilerError,
+        errors::{self, CompilerError},
         grammar::{Definition, Function, Signature},
         interpreter::{expressions, Environment},
         tokens::Value,
@@ -12,9 +12,10 @@ pub fn eval(
     env: Rc<RefCell<Environment>>,
     function: &Function,
 ) -> Result<Option<Value>, CompilerError> {
-    let signature = &function.signature;
     let definition = &function.definition;
-    validate_arity(signature, definition)?;
+    if let Some(signature) = &function.signature {
+        validate_arity(signature, definition)?;
+    }
 
     let expr = &definition.body;
     expressions::eval(env, expr)
@@ -22,13 +23,11 @@ pub fn eval(
 
 fn validate_arity(signature: &Signature, definition: &Definition) -> Result<(), CompilerError> {
     if signature.parameters.len() != definition.parameters.len() {
-        let error = format!(
-            "function signature and definition arity mismatch: expected {} parameters, found {}",
+        return Err(errors::arity_mismatch(
             signature.parameters.len(),
-            definition.parameters.len()
-        );
-
-        return Err(CompilerError::Interpreter(error, definition.name.line));
+            definition.parameters.len(),
+            definition.name.line,
+        ));
     }
     Ok(())
 }
@@ -0,0 +1,325 @@
+use {
+    crate::{
+        grammar::{Argument, Call, Clause, Element, Expression, Function, Pattern},
+        tokens::{Identifier, Value},
+    },
+    num_bigint::BigInt,
+    num_rational::BigRational,
+    std::collections::{HashMap, HashSet, VecDeque},
+};
+
+/// Results of function calls, keyed on the function and its arguments. Once full, the oldest result
+/// is evicted for each new one.
+#[derive(Debug, Default)]
+pub struct Memo {
+    capacity: usize,
+    functions: HashSet<Identifier>,
+    results: HashMap<Key, Option<Value>>,
+    order: VecDeque<Key>,
+    pub hits: usize,
+    pub misses: usize,
+    pub evictions: usize,
+}
+
+impl Memo {
+    /// A memo for calls of the given functions.
+    pub fn new(capacity: usize, functions: HashSet<Identifier>) -> Self {
+        Self {
+            capacity,
+            functions,
+            ..Self::default()
+        }
+    }
+
+    /// Whether the results of a function are kept.
+    pub fn caches(&self, function: &Identifier) -> bool {
+        self.functions.contains(function)
+    }
+
+    pub fn get(&mut self, key: &Key) -> Option<Option<Value>> {
+        let result = self.results.get(key).cloned();
+        match result {
+            Some(_) => self.hits += 1,
+            None => self.misses += 1,
+        }
+        result
+    }
+
+    pub fn insert(&mut self, key: Key, result: Option<Value>) {
+        if self.capacity == 0 || self.results.contains_key(&key) {
+            return;
+        }
+        if self.results.len() == self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.results.remove(&oldest);
+                self.evictions += 1;
+            }
+        }
+        self.order.push_back(key.clone());
+        self.results.insert(key, result);
+    }
+}
+
+/// The functions whose results depend only on their arguments. Names are looked up where a
+/// function is called, so a function whose body uses a name that a parameter, pattern or generator
+/// of the program binds may give a different result for each caller, and so may the functions
+/// calling it.
+pub fn pure(functions: &HashMap<Identifier, Function>) -> HashSet<Identifier> {
+    let mut bound = HashSet::new();
+    let free: HashMap<&Identifier, HashSet<Identifier>> = functions
+        .iter()
+        .map(|(id, f)| {
+            let mut names = Names {
+                scope: f
+                    .definition
+                    .parameters
+                    .iter()
+                    .map(|p| p.id.clone())
+                    .collect(),
+                free: HashSet::new(),
+                bound: &mut bound,
+            };
+            names.bound.extend(names.scope.iter().cloned());
+            names.expression(&f.definition.body);
+            (id, names.free)
+        })
+        .collect();
+
+    // Names that are not functions of the program are those of the prelude
+    let mut pure: HashSet<Identifier> = functions.keys().cloned().collect();
+    loop {
+        let impure: Vec<Identifier> = pure
+            .iter()
+            .filter(|id| {
+                free[id].iter().any(|name| {
+                    bound.contains(name) || (functions.contains_key(name) && !pure.contains(name))
+                })
+            })
+            .cloned()
+            .collect();
+        if impure.is_empty() {
+            return pure;
+        }
+        for id in impure {
+            pure.remove(&id);
+        }
+    }
+}
+
+/// The names a function body uses that it does not bind itself, and the names bound anywhere.
+struct Names<'a> {
+    scope: Vec<Identifier>,
+    free: HashSet<Identifier>,
+    bound: &'a mut HashSet<Identifier>,
+}
+
+impl Names<'_> {
+    fn used(&mut self, id: &Identifier) {
+        if !self.scope.contains(id) {
+            self.free.insert(id.clone());
+        }
+    }
+
+    /// Visits an expression with the bindings of a pattern in scope.
+    fn within(&mut self, pattern: &Pattern, expr: &Expression) {
+        let bindings: Vec<Identifier> = match pattern {
+            Pattern::Variant { bindings, .. } | Pattern::Tuple(bindings) => {
+                bindings.iter().map(|b| b.id.clone()).collect()
+            }
+            Pattern::Wildcard => Vec::new(),
+        };
+        let depth = self.scope.len();
+        self.bound.extend(bindings.iter().cloned());
+        self.scope.extend(bindings);
+        self.expression(expr);
+        self.scope.truncate(depth);
+    }
+
+    fn expression(&mut self, expr: &Expression) {
+        match expr {
+            Expression::Value(v) => self.value(v),
+            Expression::Call(Call::Operation(op)) => {
+                op.arguments.iter().for_each(|arg| self.argument(arg))
+            }
+            Expression::Call(Call::FunctionCall(fc)) => {
+                self.used(&fc.name.id);
+                fc.arguments.iter().for_each(|arg| self.argument(arg));
+            }
+            Expression::Call(Call::Pipe(p)) => {
+                self.expression(&p.left);
+                self.used(&p.right);
+            }
+            Expression::Call(Call::Construction(c)) => c
+                .fields
+                .iter()
+                .for_each(|(_, value)| self.expression(value)),
+            Expression::Call(Call::Access(a)) => self.argument(&a.record),
+            Expression::Call(Call::Variant(v)) => {
+                v.arguments.iter().for_each(|arg| self.argument(arg))
+            }
+            Expression::Call(Call::Tuple(t)) => t.elements.iter().for_each(|e| self.expression(e)),
+            Expression::Conditional(c) => {
+                self.expression(&c.condition);
+                self.expression(&c.then);
+                self.expression(&c.otherwise);
+            }
+            Expression::Case(c) => {
+                self.expression(&c.subject);
+                for arm in c.arms.iter() {
+                    self.within(&arm.pattern, &arm.body);
+                }
+            }
+            Expression::Try(t) => {
+                self.expression(&t.body);
+                self.within(&t.pattern, &t.handler);
+            }
+            Expression::Raise(r) => self.expression(&r.message),
+            Expression::Comprehension(c) => {
+                // Generators bind their names in the clauses after them and in the element
+                let depth = self.scope.len();
+                for clause in c.clauses.iter() {
+                    match clause {
+                        Clause::Generator { name, list } => {
+                            self.expression(list);
+                            self.bound.insert(name.id.clone());
+                            self.scope.push(name.id.clone());
+                        }
+                        Clause::Guard(condition) => self.expression(condition),
+                    }
+                }
+                self.expression(&c.element);
+                self.scope.truncate(depth);
+            }
+            Expression::Builtin(_) => {}
+        }
+    }
+
+    fn argument(&mut self, arg: &Argument) {
+        match arg {
+            Argument::Value(v) => self.value(v),
+            Argument::Identifier(id) => self.used(&id.id),
+            Argument::ParenExpression(expr) => self.expression(expr),
+        }
+    }
+
+    fn value(&mut self, value: &Value) {
+        if let Value::List(list) = value {
+            for e in list.elements.iter() {
+                match e {
+                    Element::Value(v) => self.value(v),
+                    Element::Identifier(id) => self.used(id),
+                    Element::Range(r) => r.bounds().for_each(|arg| self.argument(arg)),
+                }
+            }
+        }
+    }
+}
+
+/// A function and the values of its arguments.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Key {
+    function: Identifier,
+    arguments: Vec<Plain>,
+}
+
+/// A value as part of a key, without the source positions of its lists. Fractions are compared by
+/// their bits, so that a key is always equal to itself.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Plain {
+    Integer(i64),
+    BigInteger(BigInt),
+    Fractional(u64),
+    Decimal(BigRational),
+    String(String),
+    Boolean(bool),
+    List(String, Vec<Plain>),
+    Record(Identifier, Vec<(Identifier, Plain)>),
+    Variant(Identifier, Identifier, Vec<Plain>),
+    Tuple(Vec<Plain>),
+    Maybe(Option<Box<Plain>>),
+}
+
+/// The key of a call, unless an argument is a lazy list, whose elements are not known.
+pub fn key<'a>(function: &Identifier, arguments: impl Iterator<Item = &'a Value>) -> Option<Key> {
+    Some(Key {
+        function: function.clone(),
+        arguments: arguments.map(plain).collect::<Option<_>>()?,
+    })
+}
+
+fn plain(value: &Value) -> Option<Plain> {
+    let all = |values: &[Value]| values.iter().map(plain).collect::<Option<Vec<_>>>();
+    Some(match value {
+        Value::Integer(i) => Plain::Integer(*i),
+        Value::BigInteger(i) => Plain::BigInteger(i.clone()),
+        Value::Fractional(f) => Plain::Fractional(f.to_bits()),
+        Value::Decimal(d) => Plain::Decimal(d.clone()),
+        Value::String(s) => Plain::String(s.clone()),
+        Value::Boolean(b) => Plain::Boolean(*b),
+        Value::List(list) => {
+            let elements = list
+                .elements
+                .iter()
+                .map(|e| match e {
+                    Element::Value(v) => plain(v),
+                    _ => None,
+                })
+                .collect::<Option<_>>()?;
+            Plain::List(list.list_type.to_string(), elements)
+        }
+        Value::Lazy(_) => return None,
+        Value::Record(record) => {
+            let fields = record
+                .fields
+                .iter()
+                .map(|(id, v)| Some((id.clone(), plain(v)?)))
+                .collect::<Option<_>>()?;
+            Plain::Record(record.name.clone(), fields)
+        }
+        Value::Variant(variant) => Plain::Variant(
+            variant.type_name.clone(),
+            variant.name.clone(),
+            all(&variant.values)?,
+        ),
+        Value::Tuple(values) => Plain::Tuple(all(values)?),
+        Value::Maybe(maybe) => match maybe {
+            Some(v) => Plain::Maybe(Some(Box::new(plain(v)?))),
+            None => Plain::Maybe(None),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{grammar::List, tokens::Type},
+    };
+
+    fn list(line: usize) -> Value {
+        Value::List(List {
+            list_type: Type::Int,
+            elements: vec![Element::Value(Value::Integer(1))],
+            line,
+        })
+    }
+
+    #[test]
+    fn positions_ignored() {
+        let f = "f".to_string();
+        let mut memo = Memo::new(10, HashSet::from([f.clone()]));
+        memo.insert(key(&f, [list(1)].iter()).unwrap(), None);
+        assert_eq!(memo.get(&key(&f, [list(7)].iter()).unwrap()), Some(None));
+    }
+
+    #[test]
+    fn values_told_apart() {
+        let f = "f".to_string();
+        let integer = key(&f, [Value::Integer(1)].iter());
+        assert_ne!(integer, key(&f, [Value::Fractional(1.0)].iter()));
+        assert_ne!(integer, key(&"g".to_string(), [Value::Integer(1)].iter()));
+
+        let nan = key(&f, [Value::Fractional(f64::NAN)].iter());
+        assert_eq!(nan, nan.clone());
+    }
+}
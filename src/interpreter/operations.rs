@@ -1,16 +1,14 @@
 use {
     crate::{
-        errors,
-        errors::CompilerError,
+        errors::{self, CompilerError, InterpreterError},
         grammar::Operation,
-        interpreter::{arguments, Environment},
-        tokens::{
-            Operator, OperatorType,
-            Type::{self, Bool},
-            Value,
-        },
+        interpreter::{arguments, Environment, Overflow},
+        tokens::{Operator, OperatorType, Type::Bool, Value},
     },
-    std::{cell::RefCell, rc::Rc},
+    num_bigint::BigInt,
+    num_rational::BigRational,
+    num_traits::{ToPrimitive, Zero},
+    std::{cell::RefCell, cmp::Ordering, rc::Rc},
 };
 
 pub fn eval(
@@ -64,15 +62,53 @@ fn eval_binary(
         return Err(errors::wrong_operator_arity(&op.operator, op.line));
     }
 
-    let value = arguments::eval(Rc::clone(&env), op.arguments.first().unwrap())?;
+    let args = op
+        .arguments
+        .iter()
+        .map(|arg| arguments::eval(Rc::clone(&env), arg))
+        .collect::<Result<Vec<Value>, CompilerError>>()?;
+    if let Some((i, value)) = args.iter().enumerate().find(|(_, v)| !numeric(v)) {
+        return Err(match i {
+            0 => errors::unexpected_type_class("Numeric", &value.get_type(), line),
+            _ => errors::unexpected_type(&args[0].get_type(), &value.get_type(), line),
+        });
+    }
+
+    // Operands are promoted to the widest of their types, from `Int` to `Decimal` to `Frac`
+    if args.iter().any(|v| matches!(v, Value::Fractional(_))) {
+        eval_frac_op(op, &args)
+    } else if args.iter().any(|v| matches!(v, Value::Decimal(_))) {
+        eval_decimal_op(op, &args)
+    } else {
+        eval_int_op(env, op, args)
+    }
+}
+
+fn numeric(value: &Value) -> bool {
+    matches!(
+        value,
+        Value::Integer(_) | Value::BigInteger(_) | Value::Fractional(_) | Value::Decimal(_)
+    )
+}
+
+/// A numeric value as an exact `Decimal`, unless it is a `Frac`.
+pub fn exact(value: &Value) -> Option<BigRational> {
     match value {
-        Value::Integer(i) => Ok(eval_int_op(Rc::clone(&env), op, i)?),
-        Value::Fractional(f) => Ok(eval_frac_op(Rc::clone(&env), op, f)?),
-        _ => Err(errors::unexpected_type_class(
-            "Numeric",
-            &value.get_type(),
-            line,
-        )),
+        Value::Integer(i) => Some(BigRational::from_integer(BigInt::from(*i))),
+        Value::BigInteger(i) => Some(BigRational::from_integer(i.clone())),
+        Value::Decimal(d) => Some(d.clone()),
+        _ => None,
+    }
+}
+
+/// A numeric value as a `Frac`.
+pub fn fractional(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(i) => Some(*i as f64),
+        Value::BigInteger(i) => Some(i.to_f64().unwrap_or(f64::NAN)),
+        Value::Fractional(f) => Some(*f),
+        Value::Decimal(d) => Some(d.to_f64().unwrap_or(f64::NAN)),
+        _ => None,
     }
 }
 
@@ -98,12 +134,17 @@ fn eval_relational(
             };
             Ok(Some(Value::Boolean(result)))
         }
-        (Value::Fractional(f), Value::Fractional(g)) => {
+        (f, g) if numeric(f) && numeric(g) => {
+            // Compared exactly unless either is a `Frac`
+            let ordering = match (exact(f), exact(g)) {
+                (Some(f), Some(g)) => f.partial_cmp(&g),
+                _ => fractional(f).partial_cmp(&fractional(g)),
+            };
             let result = match op.operator {
-                Operator::Lt => f < g,
-                Operator::Lte => f <= g,
-                Operator::Gt => f > g,
-                Operator::Gte => f >= g,
+                Operator::Lt => ordering == Some(Ordering::Less),
+                Operator::Lte => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+                Operator::Gt => ordering == Some(Ordering::Greater),
+                Operator::Gte => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
                 _ => panic!("not a relational operator"),
             };
             Ok(Some(Value::Boolean(result)))
@@ -125,6 +166,14 @@ fn eval_logical(
     }
 
     let first = arguments::eval(Rc::clone(&env), op.arguments.first().unwrap())?;
+
+    // `&&` and `||` leave the second operand unevaluated when the first decides the result
+    match (&op.operator, &first) {
+        (Operator::And, Value::Boolean(false)) | (Operator::Or, Value::Boolean(true)) => {
+            return Ok(Some(first));
+        }
+        _ => {}
+    }
     let second = arguments::eval(Rc::clone(&env), op.arguments.get(1).unwrap())?;
 
     if let Value::Boolean(_) = first {
@@ -148,18 +197,68 @@ fn eval_logical(
                 };
                 Ok(Some(Value::Boolean(result)))
             }
-            (Value::Fractional(f), Value::Fractional(g))
+            (f, g)
+                if numeric(&f)
+                    && numeric(&g)
+                    && (op.operator == Operator::Eq || op.operator == Operator::Neq) =>
+            {
+                let equal = match (exact(&f), exact(&g)) {
+                    (Some(f), Some(g)) => f == g,
+                    _ => fractional(&f) == fractional(&g),
+                };
+                let result = if op.operator == Operator::Eq {
+                    equal
+                } else {
+                    !equal
+                };
+                Ok(Some(Value::Boolean(result)))
+            }
+            (Value::Record(a), Value::Record(b))
+                if a.name == b.name
+                    && (op.operator == Operator::Eq || op.operator == Operator::Neq) =>
+            {
+                let result = if op.operator == Operator::Eq {
+                    a == b
+                } else {
+                    a != b
+                };
+                Ok(Some(Value::Boolean(result)))
+            }
+            (Value::Variant(a), Value::Variant(b))
+                if a.type_name == b.type_name
+                    && (op.operator == Operator::Eq || op.operator == Operator::Neq) =>
+            {
+                let result = if op.operator == Operator::Eq {
+                    a == b
+                } else {
+                    a != b
+                };
+                Ok(Some(Value::Boolean(result)))
+            }
+            (Value::Tuple(a), Value::Tuple(b))
+                if Value::Tuple(a.clone()).get_type() == Value::Tuple(b.clone()).get_type()
+                    && (op.operator == Operator::Eq || op.operator == Operator::Neq) =>
+            {
+                let result = if op.operator == Operator::Eq {
+                    a == b
+                } else {
+                    a != b
+                };
+                Ok(Some(Value::Boolean(result)))
+            }
+            (Value::Maybe(a), Value::Maybe(b))
                 if op.operator == Operator::Eq || op.operator == Operator::Neq =>
             {
                 let result = if op.operator == Operator::Eq {
-                    f == g
+                    a == b
                 } else {
-                    f != g
+                    a != b
                 };
                 Ok(Some(Value::Boolean(result)))
             }
-            _ => Err(CompilerError::Interpreter(
-                "type mismatch".to_string(),
+            (first, second) => Err(errors::incomparable(
+                &first.get_type(),
+                &second.get_type(),
                 op.line,
             )),
         }
@@ -172,12 +271,18 @@ fn eval_unary_numeric(
 ) -> Result<Option<Value>, CompilerError> {
     let line = op.line;
     let arg = op.arguments.first().unwrap();
+    let env_overflow = env.borrow().overflow();
     let value = arguments::eval(env, arg)?;
 
     if op.operator == Operator::Sub {
         match value {
-            Value::Integer(i) => Ok(Some(Value::Integer(-i))),
+            Value::Integer(i) => match i.checked_neg() {
+                Some(i) => Ok(Some(Value::Integer(i))),
+                None => promote(env_overflow, -BigInt::from(i), line),
+            },
+            Value::BigInteger(i) => promote(env_overflow, -i, line),
             Value::Fractional(f) => Ok(Some(Value::Fractional(-f))),
+            Value::Decimal(d) => Ok(Some(Value::Decimal(-d))),
             _ => Err(errors::unexpected_type_class(
                 "Numeric",
                 &value.get_type(),
@@ -186,8 +291,8 @@ fn eval_unary_numeric(
         }
     } else {
         match value {
-            Value::Integer(i) => Ok(Some(Value::Integer(i))),
-            Value::Fractional(f) => Ok(Some(Value::Fractional(f))),
+            Value::Integer(_) | Value::BigInteger(_) => Ok(Some(value)),
+            Value::Fractional(_) | Value::Decimal(_) => Ok(Some(value)),
             _ => Err(errors::unexpected_type_class(
                 "Numeric",
                 &value.get_type(),
@@ -200,49 +305,121 @@ fn eval_unary_numeric(
 fn eval_int_op(
     env: Rc<RefCell<Environment>>,
     op: &Operation,
-    first: i64,
+    args: Vec<Value>,
 ) -> Result<Option<Value>, CompilerError> {
-    let mut args: Vec<i64> = Vec::new();
-    args.push(first);
-
-    for arg in op.arguments.iter().skip(1) {
-        let v = arguments::eval(Rc::clone(&env), arg)?;
-        if let Value::Integer(i) = v {
-            args.push(i);
-        } else {
-            return Err(errors::unexpected_type(&Type::Int, &v.get_type(), op.line));
+    // Arbitrary precision is only needed once an `i64` overflows
+    let small: Option<Vec<i64>> = args
+        .iter()
+        .map(|v| match v {
+            Value::Integer(i) => Some(*i),
+            _ => None,
+        })
+        .collect();
+    if let Some(small) = small {
+        match eval_checked_arithmetic(&op.operator, &small) {
+            Ok(Some(result)) => return Ok(Some(Value::Integer(result))),
+            Ok(None) => {}
+            Err(e) => return Err(CompilerError::Interpreter(e, op.line)),
         }
     }
 
-    match eval_arithmetic(&op.operator, &args) {
-        Ok(result) => Ok(Some(Value::Integer(result))),
+    let args: Vec<BigInt> = args.iter().filter_map(big).collect();
+    match eval_big_arithmetic(&op.operator, &args) {
+        Ok(result) => promote(env.borrow().overflow(), result, op.line),
         Err(e) => Err(CompilerError::Interpreter(e, op.line)),
     }
 }
 
-fn eval_frac_op(
-    env: Rc<RefCell<Environment>>,
-    op: &Operation,
-    first: f64,
+/// An integer result, which is an error if it only fits in a `BigInteger` and overflow is not
+/// promoted.
+pub fn promote(
+    overflow: Overflow,
+    result: BigInt,
+    line: usize,
 ) -> Result<Option<Value>, CompilerError> {
-    let mut args: Vec<f64> = Vec::new();
-    args.push(first);
+    match Value::from(result) {
+        Value::BigInteger(_) if overflow == Overflow::Error => Err(CompilerError::Interpreter(
+            InterpreterError::IntegerOverflow,
+            line,
+        )),
+        value => Ok(Some(value)),
+    }
+}
 
-    for arg in op.arguments.iter().skip(1) {
-        let v = arguments::eval(Rc::clone(&env), arg)?;
-        if let Value::Fractional(f) = v {
-            args.push(f);
-        } else {
-            return Err(errors::unexpected_type(&Type::Frac, &v.get_type(), op.line));
+/// An integer value with arbitrary precision.
+pub fn big(value: &Value) -> Option<BigInt> {
+    match value {
+        Value::Integer(i) => Some(BigInt::from(*i)),
+        Value::BigInteger(i) => Some(i.clone()),
+        _ => None,
+    }
+}
+
+/// Integer arithmetic, which is `None` if it overflows.
+fn eval_checked_arithmetic(
+    operator: &Operator,
+    args: &[i64],
+) -> Result<Option<i64>, InterpreterError> {
+    let mut rest = args[1..].iter();
+    Ok(match operator {
+        Operator::Add => rest.try_fold(args[0], |acc, &arg| acc.checked_add(arg)),
+        Operator::Sub => rest.try_fold(args[0], |acc, &arg| acc.checked_sub(arg)),
+        Operator::Mul => rest.try_fold(args[0], |acc, &arg| acc.checked_mul(arg)),
+        Operator::Div => {
+            if args[1..].contains(&0) {
+                return Err(InterpreterError::DivisionByZero);
+            }
+            rest.try_fold(args[0], |acc, &arg| acc.checked_div(arg))
+        }
+        _ => panic!("not a binary operator"),
+    })
+}
+
+fn eval_big_arithmetic(operator: &Operator, args: &[BigInt]) -> Result<BigInt, InterpreterError> {
+    let rest = args[1..].iter();
+    match operator {
+        Operator::Add => Ok(rest.fold(args[0].clone(), |acc, arg| acc + arg)),
+        Operator::Sub => Ok(rest.fold(args[0].clone(), |acc, arg| acc - arg)),
+        Operator::Mul => Ok(rest.fold(args[0].clone(), |acc, arg| acc * arg)),
+        Operator::Div => {
+            if args[1..].contains(&BigInt::from(0)) {
+                return Err(InterpreterError::DivisionByZero);
+            }
+            Ok(rest.fold(args[0].clone(), |acc, arg| acc / arg))
         }
+        _ => panic!("not a binary operator"),
     }
+}
 
+fn eval_frac_op(op: &Operation, args: &[Value]) -> Result<Option<Value>, CompilerError> {
+    let args: Vec<f64> = args.iter().filter_map(fractional).collect();
     match eval_arithmetic(&op.operator, &args) {
         Ok(result) => Ok(Some(Value::Fractional(result))),
         Err(e) => Err(CompilerError::Interpreter(e, op.line)),
     }
 }
 
+fn eval_decimal_op(op: &Operation, args: &[Value]) -> Result<Option<Value>, CompilerError> {
+    let args: Vec<BigRational> = args.iter().filter_map(exact).collect();
+    let rest = args[1..].iter();
+    let result = match op.operator {
+        Operator::Add => rest.fold(args[0].clone(), |acc, arg| acc + arg),
+        Operator::Sub => rest.fold(args[0].clone(), |acc, arg| acc - arg),
+        Operator::Mul => rest.fold(args[0].clone(), |acc, arg| acc * arg),
+        Operator::Div => {
+            if args[1..].iter().any(Zero::is_zero) {
+                return Err(CompilerError::Interpreter(
+                    InterpreterError::DivisionByZero,
+                    op.line,
+                ));
+            }
+            rest.fold(args[0].clone(), |acc, arg| acc / arg)
+        }
+        _ => panic!("not a binary operator"),
+    };
+    Ok(Some(Value::Decimal(result)))
+}
+
 fn eval_arithmetic<
     T: std::ops::Add<Output = T>
         + std::ops::Sub<Output = T>
@@ -254,14 +431,14 @@ fn eval_arithmetic<
 >(
     operator: &Operator,
     args: &[T],
-) -> Result<T, String> {
+) -> Result<T, InterpreterError> {
     match operator {
         Operator::Add => Ok(args.iter().fold(T::from(0), |acc, &arg| acc + arg)),
         Operator::Sub => Ok(args[1..].iter().fold(args[0], |acc, &arg| acc - arg)),
         Operator::Mul => Ok(args.iter().fold(T::from(1), |acc, &arg| acc * arg)),
         Operator::Div => {
             if args[1..].iter().any(|&arg| arg == T::from(0)) {
-                return Err("division by zero".to_string());
+                return Err(InterpreterError::DivisionByZero);
             }
             Ok(args[1..].iter().fold(args[0], |acc, &arg| acc / arg))
         }
@@ -1,8 +1,205 @@
-use std::collections::HashMap;
+use {
+    crate::{
+        ast,
+        errors::{self, CompilerError},
+        grammar::{Argument, Definition, Element, Expression, Function, Signature},
+        interpreter::{arguments, environment::Environment, operations, Overflow},
+        lexer,
+        tokens::{Identifier, Lazy, LocatedIdentifier, Type, Value},
+    },
+    num_bigint::BigInt,
+    num_traits::{FromPrimitive, ToPrimitive, Zero},
+    std::{cell::RefCell, collections::HashMap, rc::Rc},
+};
 
-use crate::{grammar::Function, tokens::Identifier};
+/// Prelude functions written in Lemma.
+const SOURCE: &str = include_str!("prelude.lm");
 
-// TODO
 pub fn functions() -> HashMap<Identifier, Function> {
-    HashMap::new()
+    let program = lexer::tokens(SOURCE)
+        .and_then(ast::build)
+        .expect("the prelude is valid");
+    let var = || Type::Var("a".into());
+    let builtins = [
+        builtin(
+            "head",
+            &[("list", Type::List(Box::new(var())))],
+            Type::Maybe(Box::new(var())),
+        ),
+        builtin(
+            "parse-int",
+            &[("string", Type::Str)],
+            Type::Maybe(Box::new(Type::Int)),
+        ),
+        builtin(
+            "divide",
+            &[("dividend", Type::Int), ("divisor", Type::Int)],
+            Type::Maybe(Box::new(Type::Int)),
+        ),
+        builtin(
+            "take",
+            &[("count", Type::Int), ("list", Type::List(Box::new(var())))],
+            Type::List(Box::new(var())),
+        ),
+        builtin(
+            "drop",
+            &[("count", Type::Int), ("list", Type::List(Box::new(var())))],
+            Type::List(Box::new(var())),
+        ),
+        builtin("to-frac", &[("n", Type::Int)], Type::Frac),
+        builtin("to-decimal", &[("n", Type::Int)], Type::Decimal),
+        builtin("round", &[("x", Type::Frac)], Type::Int),
+        builtin("floor", &[("x", Type::Frac)], Type::Int),
+        builtin("ceiling", &[("x", Type::Frac)], Type::Int),
+        builtin("truncate", &[("x", Type::Frac)], Type::Int),
+    ];
+    program
+        .functions
+        .into_iter()
+        .chain(builtins)
+        .map(|f| (f.definition.name.id.clone(), f))
+        .collect()
+}
+
+/// A function whose body is implemented by `eval`.
+fn builtin(name: &str, parameters: &[(&str, Type)], returns: Type) -> Function {
+    let located = |id: &str| LocatedIdentifier {
+        id: id.to_string(),
+        line: 0,
+    };
+    Function {
+        signature: Some(Signature {
+            parameters: parameters.iter().map(|(_, t)| t.clone()).collect(),
+            returns,
+            line: 0,
+        }),
+        definition: Definition {
+            name: located(name),
+            parameters: parameters.iter().map(|(id, _)| located(id)).collect(),
+            body: Expression::Builtin(name.to_string()),
+        },
+    }
+}
+
+/// Evaluates a builtin called on `line`, whose arguments are bound to its parameters in `env`.
+pub fn eval(
+    env: Rc<RefCell<Environment>>,
+    name: &Identifier,
+    line: usize,
+) -> Result<Option<Value>, CompilerError> {
+    let argument = |id: &str| {
+        let id = LocatedIdentifier {
+            id: id.to_string(),
+            line: 0,
+        };
+        arguments::eval(Rc::clone(&env), &Argument::Identifier(id))
+    };
+    // Conversions only take values of the type they convert from
+    let integer = |n: Value| match n {
+        Value::Integer(_) | Value::BigInteger(_) => Ok(n),
+        n => Err(errors::unexpected_type(&Type::Int, &n.get_type(), line)),
+    };
+    match name.as_str() {
+        "to-frac" => {
+            let n = integer(argument("n")?)?;
+            let n = operations::fractional(&n).expect("an integer");
+            return Ok(Some(Value::Fractional(n)));
+        }
+        "to-decimal" => {
+            let n = integer(argument("n")?)?;
+            let n = operations::exact(&n).expect("an integer");
+            return Ok(Some(Value::Decimal(n)));
+        }
+        "round" | "floor" | "ceiling" | "truncate" => {
+            return match argument("x")? {
+                Value::Fractional(x) => round(name, x, env.borrow().overflow(), line),
+                x => Err(errors::unexpected_type(&Type::Frac, &x.get_type(), line)),
+            };
+        }
+        "take" | "drop" => {
+            let count = argument("count")?;
+            let count = operations::big(&count)
+                .ok_or_else(|| errors::unexpected_type(&Type::Int, &count.get_type(), line))?;
+            let count = count.max(BigInt::zero()).to_usize().unwrap_or(usize::MAX);
+            return Ok(Some(slice(name, count, argument("list")?)));
+        }
+        _ => {}
+    }
+
+    // The other builtins are partial, and return a `Maybe`
+    let result = match name.as_str() {
+        "head" => match argument("list")? {
+            Value::List(list) => match list.elements.into_iter().next() {
+                Some(Element::Value(v)) => Some(v),
+                Some(Element::Identifier(id)) => Some(argument(&id)?),
+                Some(Element::Range(_)) => unreachable!("ranges are evaluated lazily"),
+                None => None,
+            },
+            Value::Lazy(lazy) => lazy.iter().next().transpose()?,
+            _ => None,
+        },
+        "parse-int" => match argument("string")? {
+            Value::String(s) => s.trim().parse::<BigInt>().ok().map(Value::from),
+            v => return Err(errors::unexpected_type(&Type::Str, &v.get_type(), line)),
+        },
+        "divide" => {
+            let integer = |v: Value| {
+                operations::big(&v)
+                    .ok_or_else(|| errors::unexpected_type(&Type::Int, &v.get_type(), line))
+            };
+            let (a, b) = (
+                integer(argument("dividend")?)?,
+                integer(argument("divisor")?)?,
+            );
+            (!b.is_zero()).then(|| Value::from(a / b))
+        }
+        _ => unreachable!("unknown builtin `{}`", name),
+    };
+    Ok(Some(Value::Maybe(result.map(Box::new))))
+}
+
+/// The first `count` elements of a list, or the others, depending on the name of the builtin. Lazy
+/// lists stay lazy.
+fn slice(name: &str, count: usize, list: Value) -> Value {
+    match list {
+        Value::List(mut list) => {
+            if name == "take" {
+                list.elements.truncate(count);
+            } else {
+                list.elements.drain(..count.min(list.elements.len()));
+            }
+            Value::List(list)
+        }
+        Value::Lazy(lazy) => {
+            let take = name == "take";
+            Value::Lazy(Lazy::new(lazy.element_type.clone(), move || {
+                if take {
+                    Box::new(lazy.iter().take(count))
+                } else {
+                    Box::new(lazy.iter().skip(count))
+                }
+            }))
+        }
+        v => v,
+    }
+}
+
+/// Converts a `Frac` to an `Int`, in the direction given by the name of the builtin.
+fn round(
+    name: &str,
+    x: f64,
+    overflow: Overflow,
+    line: usize,
+) -> Result<Option<Value>, CompilerError> {
+    let rounded = match name {
+        "round" => x.round(),
+        "floor" => x.floor(),
+        "ceiling" => x.ceil(),
+        _ => x.trunc(),
+    };
+    // Infinity and NaN have no integer value
+    match BigInt::from_f64(rounded) {
+        Some(n) => operations::promote(overflow, n, line),
+        None => Err(errors::no_integer_value(&Value::Fractional(x), line)),
+    }
 }
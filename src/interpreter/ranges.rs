@@ -0,0 +1,165 @@
+use {
+    crate::{
+        errors::{self, CompilerError},
+        grammar::Range,
+        interpreter::{arguments, environment::Environment, operations},
+        tokens::{Lazy, Type, Value},
+    },
+    num_bigint::BigInt,
+    num_traits::{One, ToPrimitive, Zero},
+    std::{
+        cell::RefCell,
+        collections::HashMap,
+        iter,
+        ops::{Add, Neg, Sub},
+        rc::Rc,
+    },
+};
+
+/// Evaluates the bounds of a range into a lazy list of the values from its start to its end.
+pub fn eval(
+    env: Rc<RefCell<Environment>>,
+    range: &Range,
+    list_type: &Type,
+    line: usize,
+) -> Result<Lazy, CompilerError> {
+    let bound = |bound: &_| arguments::eval(Rc::clone(&env), bound);
+    let start = bound(&range.start)?;
+    let next = range.next.as_ref().map(bound).transpose()?;
+    let end = range.end.as_ref().map(bound).transpose()?;
+
+    let mut bindings = HashMap::new();
+    for bound in iter::once(&start).chain(&next).chain(&end) {
+        if !list_type.unify(&bound.get_type(), &mut bindings) {
+            let expected = list_type.substitute(&bindings);
+            return Err(errors::list_type_mismatch(
+                &expected,
+                &bound.get_type(),
+                line,
+            ));
+        }
+    }
+    let element_type = list_type.substitute(&bindings);
+
+    match start {
+        Value::Integer(_) | Value::BigInteger(_) => {
+            let integer = |v: &Value| operations::big(v).expect("an integer bound");
+            let start = integer(&start);
+            let end = end.as_ref().map(integer);
+            let step = step(&start, next.as_ref().map(integer), end.as_ref(), line)?;
+            // Values past 64 bits are handled like the results of arithmetic
+            let overflow = env.borrow().overflow();
+            Ok(Lazy::new(element_type, move || {
+                Box::new(
+                    steps(start.clone(), step.clone(), end.clone()).map(move |n| {
+                        operations::promote(overflow, n, line).map(|v| v.expect("an integer"))
+                    }),
+                )
+            }))
+        }
+        Value::Decimal(start) => {
+            let decimal = |v: &Value| operations::exact(v).expect("a decimal bound");
+            let end = end.as_ref().map(decimal);
+            let step = step(&start, next.as_ref().map(decimal), end.as_ref(), line)?;
+            Ok(Lazy::new(element_type, move || {
+                Box::new(
+                    steps(start.clone(), step.clone(), end.clone()).map(|d| Ok(Value::Decimal(d))),
+                )
+            }))
+        }
+        Value::Fractional(start) => {
+            let fractional = |v: &Value| operations::fractional(v).expect("a fractional bound");
+            let end = end.as_ref().map(fractional);
+            let step = match next {
+                Some(next) => fractional(&next) - start,
+                None if end.is_none_or(|end| start <= end) => 1.0,
+                None => -1.0,
+            };
+            if !start.is_finite() || !step.is_finite() || end.is_some_and(|end| !end.is_finite()) {
+                return Err(errors::invalid_range("bounds must be finite", line));
+            }
+            if step == 0.0 {
+                return Err(errors::invalid_range("step of zero", line));
+            }
+            // Each value is computed from the start so that rounding errors do not add up, and
+            // the end is allowed to be just missed through rounding, e.g. in `[0.0, 0.1 .. 0.3]`
+            let last = end.map(|end| (end - start) / step + 1e-9);
+            Ok(Lazy::new(element_type, move || {
+                Box::new(
+                    (0u64..)
+                        .take_while(move |&i| last.is_none_or(|last| i as f64 <= last))
+                        .map(move |i| Ok(Value::Fractional(start + i as f64 * step))),
+                )
+            }))
+        }
+        Value::String(_) => {
+            // Characters are ranged over by their code points
+            let start = character(&start, line)?;
+            let next = next.as_ref().map(|n| character(n, line)).transpose()?;
+            let end = end.as_ref().map(|e| character(e, line)).transpose()?;
+            let step = step(&start, next, end.as_ref(), line)?;
+            let end = end.unwrap_or_else(|| {
+                if step > BigInt::zero() {
+                    BigInt::from(char::MAX as u32)
+                } else {
+                    BigInt::zero()
+                }
+            });
+            Ok(Lazy::new(element_type, move || {
+                Box::new(
+                    steps(start.clone(), step.clone(), Some(end.clone()))
+                        .filter_map(|c| c.to_u32().and_then(char::from_u32))
+                        .map(|c| Ok(Value::String(c.to_string()))),
+                )
+            }))
+        }
+        v => Err(errors::unrangeable_type(&v.get_type(), line)),
+    }
+}
+
+/// The distance from `start` to `next`, or one towards `end` if there is no `next`.
+fn step<T>(start: &T, next: Option<T>, end: Option<&T>, line: usize) -> Result<T, CompilerError>
+where
+    T: PartialOrd + Zero + One + Neg<Output = T>,
+    for<'a> &'a T: Sub<&'a T, Output = T>,
+{
+    let step = match next {
+        Some(next) => &next - start,
+        None if end.is_none_or(|end| start <= end) => T::one(),
+        None => -T::one(),
+    };
+    if step.is_zero() {
+        return Err(errors::invalid_range("step of zero", line));
+    }
+    Ok(step)
+}
+
+/// Values from `start`, each one step from the last, up to `end` if there is one.
+fn steps<T>(start: T, step: T, end: Option<T>) -> impl Iterator<Item = T>
+where
+    T: PartialOrd + Zero,
+    for<'a> &'a T: Add<&'a T, Output = T>,
+{
+    let ascending = step > T::zero();
+    iter::successors(Some(start), move |value| Some(value + &step)).take_while(move |value| {
+        match &end {
+            Some(end) if ascending => value <= end,
+            Some(end) => value >= end,
+            None => true,
+        }
+    })
+}
+
+fn character(value: &Value, line: usize) -> Result<BigInt, CompilerError> {
+    let mut chars = match value {
+        Value::String(s) => s.chars(),
+        _ => unreachable!("bounds have the type of the start"),
+    };
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(BigInt::from(c as u32)),
+        _ => Err(errors::invalid_range(
+            "string bounds must be single characters",
+            line,
+        )),
+    }
+}
@@ -0,0 +1,90 @@
+use {
+    crate::{
+        errors::{self, CompilerError},
+        grammar::{Access, Construction, Record, TypeDefinition},
+        interpreter::{arguments, environment::Environment, expressions},
+        tokens::{Type, Value},
+        utils,
+    },
+    std::{cell::RefCell, rc::Rc},
+};
+
+pub fn construct(
+    env: Rc<RefCell<Environment>>,
+    construction: &Construction,
+) -> Result<Option<Value>, CompilerError> {
+    let name = &construction.name.id;
+    let definition = env.borrow().type_definition(name);
+    let fields = match definition {
+        Some(TypeDefinition::Record(fields)) => fields,
+        Some(TypeDefinition::Sum(_)) => {
+            return Err(errors::unexpected_type_class(
+                "record",
+                &Type::Named(name.clone()),
+                construction.line,
+            ))
+        }
+        None => {
+            let suggestion = utils::closest(name, env.borrow().type_names());
+            return Err(errors::unknown_type(name, suggestion, construction.line));
+        }
+    };
+
+    for (field, _) in construction.fields.iter() {
+        if !fields.iter().any(|f| f.name.id == field.id) {
+            let names = fields.iter().map(|f| &f.name.id);
+            let suggestion = utils::closest(&field.id, names);
+            return Err(errors::unknown_field(
+                name, &field.id, suggestion, field.line,
+            ));
+        }
+    }
+
+    // Fields are stored in declaration order, whatever order they were given in
+    let mut values = Vec::new();
+    for field in fields.iter() {
+        let (_, expr) = construction
+            .fields
+            .iter()
+            .find(|(f, _)| f.id == field.name.id)
+            .ok_or_else(|| errors::missing_field(name, &field.name.id, construction.line))?;
+        let value = expressions::eval(Rc::clone(&env), expr)?
+            .ok_or_else(|| errors::missing_value("record field", construction.line))?;
+        if value.get_type() != field.field_type {
+            return Err(errors::unexpected_type(
+                &field.field_type,
+                &value.get_type(),
+                construction.line,
+            ));
+        }
+        values.push((field.name.id.clone(), value));
+    }
+
+    Ok(Some(Value::Record(Record {
+        name: name.clone(),
+        fields: values,
+    })))
+}
+
+pub fn access(
+    env: Rc<RefCell<Environment>>,
+    access: &Access,
+) -> Result<Option<Value>, CompilerError> {
+    match arguments::eval(env, &access.record)? {
+        Value::Record(record) => record
+            .fields
+            .iter()
+            .find(|(name, _)| *name == access.field)
+            .map(|(_, value)| Some(value.clone()))
+            .ok_or_else(|| {
+                let names = record.fields.iter().map(|(name, _)| name);
+                let suggestion = utils::closest(&access.field, names);
+                errors::unknown_field(&record.name, &access.field, suggestion, access.line)
+            }),
+        value => Err(errors::unexpected_type_class(
+            "record",
+            &value.get_type(),
+            access.line,
+        )),
+    }
+}
@@ -0,0 +1,24 @@
+use {
+    crate::{
+        errors::{self, CompilerError},
+        grammar::Tuple,
+        interpreter::{environment::Environment, expressions},
+        tokens::Value,
+    },
+    std::{cell::RefCell, rc::Rc},
+};
+
+pub fn construct(
+    env: Rc<RefCell<Environment>>,
+    tuple: &Tuple,
+) -> Result<Option<Value>, CompilerError> {
+    let values = tuple
+        .elements
+        .iter()
+        .map(|e| {
+            expressions::eval(Rc::clone(&env), e)?
+                .ok_or_else(|| errors::missing_value("tuple element", tuple.line))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Some(Value::Tuple(values)))
+}
@@ -0,0 +1,161 @@
+use {
+    crate::{
+        errors::{self, CompilerError},
+        grammar::{Case, Pattern, Variant, VariantCall},
+        interpreter::{arguments, calls, environment::Environment, expressions},
+        tokens::{Identifier, Value},
+        utils,
+    },
+    std::{cell::RefCell, collections::HashMap, rc::Rc},
+};
+
+pub fn construct(
+    env: Rc<RefCell<Environment>>,
+    call: &VariantCall,
+) -> Result<Option<Value>, CompilerError> {
+    let name = &call.name.id;
+    match (name.as_str(), call.arguments.as_slice()) {
+        ("Some", [arg]) => {
+            let value = arguments::eval(env, arg)?;
+            return Ok(Some(Value::Maybe(Some(Box::new(value)))));
+        }
+        ("None", []) => return Ok(Some(Value::Maybe(None))),
+        ("Some", _) | ("None", _) => {
+            let expected = if name == "Some" { 1 } else { 0 };
+            return Err(errors::wrong_function_arity(
+                name,
+                expected,
+                call.arguments.len(),
+                call.line,
+            ));
+        }
+        _ => {}
+    }
+    let found = env.borrow().constructor(name);
+    let (type_name, constructor) = found.ok_or_else(|| {
+        let suggestion = utils::closest(name, env.borrow().constructor_names());
+        errors::unknown_constructor(name, suggestion, call.line)
+    })?;
+
+    if constructor.parameters.len() != call.arguments.len() {
+        return Err(errors::wrong_function_arity(
+            name,
+            constructor.parameters.len(),
+            call.arguments.len(),
+            call.line,
+        ));
+    }
+    let mut values = Vec::new();
+    for (expected, arg) in constructor.parameters.iter().zip(call.arguments.iter()) {
+        let value = arguments::eval(Rc::clone(&env), arg)?;
+        if value.get_type() != *expected {
+            return Err(errors::unexpected_type(
+                expected,
+                &value.get_type(),
+                call.line,
+            ));
+        }
+        values.push(value);
+    }
+
+    Ok(Some(Value::Variant(Variant {
+        type_name,
+        name: name.clone(),
+        values,
+    })))
+}
+
+/// Evaluates the first arm whose pattern matches, with its bindings in scope.
+pub fn eval_case(
+    env: Rc<RefCell<Environment>>,
+    case: &Case,
+) -> Result<Option<Value>, CompilerError> {
+    let subject = expressions::eval(Rc::clone(&env), &case.subject)?
+        .ok_or_else(|| errors::missing_value("case subject", case.line))?;
+
+    for arm in case.arms.iter() {
+        let (bindings, values) = match (&arm.pattern, &subject) {
+            (Pattern::Wildcard, _) => return expressions::eval(env, &arm.body),
+            (Pattern::Variant { name, bindings }, Value::Variant(variant))
+                if name.id == variant.name =>
+            {
+                if bindings.len() != variant.values.len() {
+                    return Err(errors::wrong_function_arity(
+                        &name.id,
+                        variant.values.len(),
+                        bindings.len(),
+                        arm.line,
+                    ));
+                }
+                (bindings, variant.values.clone())
+            }
+            (Pattern::Variant { .. }, Value::Variant(_)) => continue,
+            (Pattern::Variant { name, bindings }, Value::Maybe(maybe)) => {
+                let expected = match name.id.as_str() {
+                    "Some" => 1,
+                    "None" => 0,
+                    _ => return Err(errors::unknown_constructor(&name.id, None, name.line)),
+                };
+                if bindings.len() != expected {
+                    return Err(errors::wrong_function_arity(
+                        &name.id,
+                        expected,
+                        bindings.len(),
+                        arm.line,
+                    ));
+                }
+                match maybe {
+                    Some(value) if expected == 1 => (bindings, vec![*value.clone()]),
+                    None if expected == 0 => (bindings, Vec::new()),
+                    _ => continue,
+                }
+            }
+            (Pattern::Tuple(bindings), Value::Tuple(values)) if bindings.len() == values.len() => {
+                (bindings, values.clone())
+            }
+            (Pattern::Tuple(bindings), value) => {
+                let class = format!("{}-tuple", bindings.len());
+                return Err(errors::unexpected_type_class(
+                    &class,
+                    &value.get_type(),
+                    arm.line,
+                ));
+            }
+            (Pattern::Variant { .. }, value) => {
+                return Err(errors::unexpected_type_class(
+                    "sum type",
+                    &value.get_type(),
+                    arm.line,
+                ))
+            }
+        };
+        let scope: HashMap<Identifier, _> = bindings
+            .iter()
+            .zip(values)
+            .filter(|(binding, _)| binding.id != "_")
+            .map(|(binding, value)| (binding.id.clone(), calls::to_fn(value)))
+            .collect();
+        let env = Environment::with_enclosing(scope, env);
+        return expressions::eval(Rc::new(RefCell::new(env)), &arm.body);
+    }
+    match subject {
+        Value::Variant(variant) => Err(errors::non_exhaustive(
+            &variant.type_name,
+            vec![variant.name],
+            case.line,
+        )),
+        Value::Maybe(maybe) => {
+            let missing = if maybe.is_some() { "Some" } else { "None" };
+            Err(errors::non_exhaustive(
+                &"Maybe".into(),
+                vec![missing.into()],
+                case.line,
+            ))
+        }
+        value => Err(errors::unexpected_type_class(
+            "sum type",
+            &value.get_type(),
+            case.line,
+        )),
+    }
+}
@@ -1,14 +1,22 @@
-use crate::{
-    errors::CompilerError,
-    scanner::Scanner,
-    tokens::{
-        Conditional::{Else, If, Then},
-        Operator::{Add, And, Div, Eq, Gt, Gte, Lt, Lte, Mul, Neq, Not, Or, Sub},
-        Symbol::{Assign, Bar, Pipe, Range, Return, EOL, LB, LP, RB, RP},
-        Token,
-        Type::{Bool, Frac, Int, Str, Void},
-        Value::{self, Fractional, Integer},
+use {
+    crate::{
+        errors::{CompilerError, LexerError},
+        scanner::Scanner,
+        tokens::{
+            Conditional::{Else, If, Then},
+            Keyword::{self, As, Case, Catch, Data, Import, Of, Raise, Try},
+            Operator::{Add, And, Div, Eq, Gt, Gte, Lt, Lte, Mul, Neq, Not, Or, Sub},
+            Symbol::{
+                Assign, Backtick, Bar, Comma, Dot, Draw, Pipe, Range, Return, Wildcard, EOL, LB,
+                LC, LP, RB, RC, RP,
+            },
+            Token,
+            Type::{Bool, Decimal, Frac, Int, Str, Void},
+            Value::{self, Fractional},
+        },
     },
+    num_bigint::BigInt,
+    num_rational::BigRational,
 };
 
 pub fn tokens(source: &str) -> Result<Vec<Token>, CompilerError> {
@@ -67,6 +75,11 @@ impl Lexer {
                 ')' => self.push(Token::Symbol(RP, self.line())),
                 '[' => self.push(Token::Symbol(LB, self.line())),
                 ']' => self.push(Token::Symbol(RB, self.line())),
+                '{' => self.push(Token::Symbol(LC, self.line())),
+                '}' => self.push(Token::Symbol(RC, self.line())),
+                ',' => self.push(Token::Symbol(Comma, self.line())),
+                '_' => self.push(Token::Symbol(Wildcard, self.line())),
+                '`' => self.push(Token::Symbol(Backtick, self.line())),
                 '+' => self.push(Token::Operator(Add, self.line())),
                 '*' => self.push(Token::Operator(Mul, self.line())),
                 '/' => self.push(Token::Operator(Div, self.line())),
@@ -84,14 +97,19 @@ impl Lexer {
                         self.push(Token::Operator(Gt, self.line()));
                     }
                 },
-                '<' => {
-                    if let Some('=') = self.scanner.peek() {
+                '<' => match self.scanner.peek() {
+                    Some('=') => {
                         self.push(Token::Operator(Lte, self.line()));
                         self.scanner.advance();
-                    } else {
+                    }
+                    Some('-') => {
+                        self.push(Token::Symbol(Draw, self.line()));
+                        self.scanner.advance();
+                    }
+                    _ => {
                         self.push(Token::Operator(Lt, self.line()));
                     }
-                }
+                },
                 '-' => {
                     if let Some('>') = self.scanner.peek() {
                         self.push(Token::Symbol(Return, self.line()));
@@ -114,7 +132,7 @@ impl Lexer {
                         self.scanner.advance();
                     } else {
                         return Err(CompilerError::Lexer(
-                            "invalid token `&`".to_string(),
+                            LexerError::InvalidToken("&".to_string()),
                             self.scanner.line(),
                         ));
                     }
@@ -139,9 +157,12 @@ impl Lexer {
                     if let Some('.') = self.scanner.peek() {
                         self.push(Token::Symbol(Range, self.line()));
                         self.scanner.advance();
+                    } else if self.scanner.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+                        // Field accessor, e.g. `.x point`
+                        self.push(Token::Symbol(Dot, self.line()));
                     } else {
                         return Err(CompilerError::Lexer(
-                            "invalid token `.`".to_string(),
+                            LexerError::InvalidToken(".".to_string()),
                             self.scanner.line(),
                         ));
                     }
@@ -156,7 +177,7 @@ impl Lexer {
                         self.tokenize_other()?
                     } else {
                         return Err(CompilerError::Lexer(
-                            format!("invalid character `{}`", char),
+                            LexerError::InvalidCharacter(char),
                             self.scanner.line(),
                         ));
                     }
@@ -168,7 +189,7 @@ impl Lexer {
     }
 
     fn tokenize_numeric(&mut self) -> Result<(), CompilerError> {
-        while self.scanner.peek().map_or(false, |c| c.is_ascii_digit()) {
+        while self.scanner.peek().is_some_and(|c| c.is_ascii_digit()) {
             self.scanner.advance();
         }
 
@@ -176,10 +197,13 @@ impl Lexer {
         if self.scanner.peek() == Some('.') {
             self.scanner.advance(); // Consume '.'
 
-            if self.scanner.peek().map_or(false, |c| c.is_ascii_digit()) {
-                while self.scanner.peek().map_or(false, |c| c.is_ascii_digit()) {
+            if self.scanner.peek().is_some_and(|c| c.is_ascii_digit()) {
+                while self.scanner.peek().is_some_and(|c| c.is_ascii_digit()) {
                     self.scanner.advance();
                 }
+                if self.scanner.peek() == Some('d') {
+                    return self.tokenize_decimal();
+                }
 
                 let lexeme = self.scanner.take_lexeme();
                 return match lexeme.parse::<f64>() {
@@ -188,35 +212,59 @@ impl Lexer {
                         Ok(())
                     }
                     Err(_) => Err(CompilerError::Lexer(
-                        format!("could not parse numeric `{}`", lexeme),
+                        LexerError::InvalidNumeric(lexeme),
                         self.scanner.line(),
                     )),
                 };
             }
         }
 
+        if self.scanner.peek() == Some('d') {
+            return self.tokenize_decimal();
+        }
+
         // Handle integer part
         let lexeme = self.scanner.take_lexeme();
-        match lexeme.parse::<i64>() {
+        match lexeme.parse::<BigInt>() {
             Ok(int) => {
-                self.push(Token::Value(Integer(int), self.line()));
+                self.push(Token::Value(Value::from(int), self.line()));
                 Ok(())
             }
             Err(_) => Err(CompilerError::Lexer(
-                format!("could not parse numeric `{}`", lexeme),
+                LexerError::InvalidNumeric(lexeme),
+                self.scanner.line(),
+            )),
+        }
+    }
+
+    /// Tokenizes an exact decimal such as `0.1d`, whose digits have been scanned.
+    fn tokenize_decimal(&mut self) -> Result<(), CompilerError> {
+        self.scanner.advance(); // Consume 'd'
+        let lexeme = self.scanner.take_lexeme();
+        let digits = lexeme.trim_end_matches('d');
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        match format!("{}{}", whole, fraction).parse::<BigInt>() {
+            Ok(numerator) => {
+                let denominator = BigInt::from(10).pow(fraction.len() as u32);
+                let decimal = BigRational::new(numerator, denominator);
+                self.push(Token::Value(Value::Decimal(decimal), self.line()));
+                Ok(())
+            }
+            Err(_) => Err(CompilerError::Lexer(
+                LexerError::InvalidNumeric(lexeme),
                 self.scanner.line(),
             )),
         }
     }
 
     fn tokenize_string(&mut self) -> Result<(), CompilerError> {
-        while self.scanner.peek().map_or(false, |c| c != '\"') {
+        while self.scanner.peek().is_some_and(|c| c != '\"') {
             self.scanner.advance();
         }
 
         if self.scanner.peek() != Some('\"') {
             return Err(CompilerError::Lexer(
-                "unterminated string".to_string(),
+                LexerError::UnterminatedString,
                 self.scanner.line(),
             ));
         } else {
@@ -225,7 +273,6 @@ impl Lexer {
 
         let lexeme = self.scanner.take_lexeme();
         let stripped_lexeme = strip_string(&lexeme);
-        self.scanner.advance(); // Consume closing `"`
         self.push(Token::Value(
             Value::String(stripped_lexeme.to_string()),
             self.line(),
@@ -239,6 +286,14 @@ impl Lexer {
         while let Some(char) = self.scanner.peek() {
             if char.is_ascii_alphanumeric() || valid_chars.contains(&char) {
                 self.scanner.advance();
+            } else if char == '.'
+                && self
+                    .scanner
+                    .peek_next()
+                    .is_some_and(|c| c.is_ascii_alphabetic())
+            {
+                // Qualified names of imported functions, e.g. `math.rem`
+                self.scanner.advance();
             } else {
                 break;
             }
@@ -256,6 +311,15 @@ impl Lexer {
             "if" => Some(Token::Conditional(If, self.line())),
             "then" => Some(Token::Conditional(Then, self.line())),
             "else" => Some(Token::Conditional(Else, self.line())),
+            "import" => Some(Token::Keyword(Import, self.line())),
+            "as" => Some(Token::Keyword(As, self.line())),
+            "data" => Some(Token::Keyword(Data, self.line())),
+            "case" => Some(Token::Keyword(Case, self.line())),
+            "of" => Some(Token::Keyword(Of, self.line())),
+            "try" => Some(Token::Keyword(Try, self.line())),
+            "catch" => Some(Token::Keyword(Catch, self.line())),
+            "raise" => Some(Token::Keyword(Raise, self.line())),
+            "type" => Some(Token::Keyword(Keyword::Type, self.line())),
             _ => None,
         };
         if let Some(token) = token {
@@ -268,6 +332,7 @@ impl Lexer {
             // Types
             "Int" => Some(Token::Type(Int, self.line())),
             "Frac" => Some(Token::Type(Frac, self.line())),
+            "Decimal" => Some(Token::Type(Decimal, self.line())),
             "Str" => Some(Token::Type(Str, self.line())),
             "Bool" => Some(Token::Type(Bool, self.line())),
             "Void" => Some(Token::Type(Void, self.line())),
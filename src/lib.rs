@@ -1,10 +1,16 @@
 pub mod args;
 pub mod ast;
+pub mod checker;
+pub mod codegen;
+pub mod diagnostics;
 pub mod errors;
 pub mod grammar;
 pub mod interpreter;
 pub mod lexer;
 pub mod log;
+pub mod lsp;
+pub mod modules;
+pub mod optimiser;
 pub mod parser;
 pub mod printer;
 pub mod scanner;
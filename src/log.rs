@@ -1,140 +1,179 @@
 use {
     colored::{ColoredString, Colorize},
+    log::{Level, LevelFilter, Log, Metadata, Record},
+    serde_json::json,
     std::{
         env,
-        error::Error,
         fmt::{self, Display, Formatter},
-        process,
+        io::{self, Write},
         str::FromStr,
-        string::ToString,
+        time::{SystemTime, UNIX_EPOCH},
     },
 };
 
-use crate::errors::CompilerError;
+/// Level used when `LOG_LEVEL` is not set. Anything noisier has to be asked for.
+const DEFAULT_LEVEL: LevelFilter = LevelFilter::Warn;
 
-const SURROUNDING_LINES: usize = 3;
-
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
-pub enum LogLevel {
-    Debug,
-    Info,
-    Warn,
-    Error,
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LogFormat {
+    Text,
+    Json,
 }
 
-impl FromStr for LogLevel {
+impl FromStr for LogFormat {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_uppercase().as_str() {
-            "DEBUG" => Ok(LogLevel::Debug),
-            "INFO" => Ok(LogLevel::Info),
-            "WARN" => Ok(LogLevel::Warn),
-            "ERROR" => Ok(LogLevel::Error),
-            _ => Err(format!("Invalid log level: {}", s)),
+        match s.to_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!("Invalid log format: {}", s)),
         }
     }
 }
 
-impl Display for LogLevel {
+impl Display for LogFormat {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        let str = match self {
-            LogLevel::Debug => "DEBUG".to_string(),
-            LogLevel::Info => "INFO".to_string(),
-            LogLevel::Warn => "WARN".to_string(),
-            LogLevel::Error => "ERROR".to_string(),
-        };
-        write!(f, "{}", str)
+        match self {
+            LogFormat::Text => write!(f, "text"),
+            LogFormat::Json => write!(f, "json"),
+        }
     }
 }
 
-pub fn debug(message: &str) {
-    log(message, LogLevel::Debug);
+/// A `log` backend writing to stderr, so logs never mix with program output.
+#[derive(Debug, PartialEq)]
+pub struct Logger {
+    pub level: LevelFilter,
+    pub format: LogFormat,
+    pub timestamps: bool,
 }
 
-pub fn info(message: &str) {
-    log(message, LogLevel::Info);
-}
+impl Logger {
+    /// Configures a logger from `LOG_LEVEL`, `LOG_FORMAT` and `LOG_TIMESTAMPS`.
+    pub fn from_env() -> Result<Self, String> {
+        let level = match env::var("LOG_LEVEL") {
+            Ok(level) => LevelFilter::from_str(&level)
+                .map_err(|_| format!("Invalid log level: {}", level))?,
+            Err(_) => DEFAULT_LEVEL,
+        };
+        let format = match env::var("LOG_FORMAT") {
+            Ok(format) => format.parse()?,
+            Err(_) => LogFormat::Text,
+        };
+        let timestamps = env::var("LOG_TIMESTAMPS")
+            .is_ok_and(|t| matches!(t.to_lowercase().as_str(), "1" | "true" | "yes"));
+
+        Ok(Self {
+            level,
+            format,
+            timestamps,
+        })
+    }
 
-pub fn warn(message: &str) {
-    log(message, LogLevel::Warn);
-}
+    /// Installs the logger as the global `log` backend.
+    pub fn init(self) -> Result<(), String> {
+        let level = self.level;
+        log::set_logger(Box::leak(Box::new(self))).map_err(|err| err.to_string())?;
+        log::set_max_level(level);
+        Ok(())
+    }
 
-pub fn error(message: &str) {
-    log(message, LogLevel::Error);
+    /// Formats a record as a single line, without the trailing newline.
+    pub fn format(&self, record: &Record, now: SystemTime) -> String {
+        let timestamp = now
+            .duration_since(UNIX_EPOCH)
+            .map(|d| timestamp(d.as_millis()))
+            .unwrap_or_default();
+
+        match self.format {
+            LogFormat::Json => {
+                let mut line = json!({
+                    "level": record.level().to_string(),
+                    "target": record.target(),
+                    "message": record.args().to_string(),
+                });
+                if self.timestamps {
+                    line["timestamp"] = json!(timestamp);
+                }
+                line.to_string()
+            }
+            LogFormat::Text => {
+                let level = format!("{:>5}", record.level());
+                let message = format!("{} {}", color(&level, record.level()), record.args());
+                if self.timestamps {
+                    format!("{} {}", timestamp, message)
+                } else {
+                    message
+                }
+            }
+        }
+    }
 }
 
-fn log(message: &str, log_level: LogLevel) {
-    if log_level >= env_log_level() {
-        println!(
-            "{:>5} {}",
-            color(&log_level.to_string(), log_level),
-            message
-        );
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            let line = self.format(record, SystemTime::now());
+            let _ = writeln!(io::stderr().lock(), "{}", line);
+        }
     }
-}
 
-fn color(s: &str, log_level: LogLevel) -> ColoredString {
-    match log_level {
-        LogLevel::Debug => s.blue(),
-        LogLevel::Info => s.green(),
-        LogLevel::Warn => s.yellow(),
-        LogLevel::Error => s.red(),
+    fn flush(&self) {
+        let _ = io::stderr().flush();
     }
 }
 
-pub fn env_log_level() -> LogLevel {
-    let env_log_level = env::var("LOG_LEVEL").unwrap_or_else(|_| "DEBUG".to_string());
-    LogLevel::from_str(&env_log_level).unwrap_or_else(|err| {
-        eprintln!("{}", err);
-        process::exit(1)
-    })
+fn color(s: &str, level: Level) -> ColoredString {
+    match level {
+        Level::Trace => s.normal(),
+        Level::Debug => s.blue(),
+        Level::Info => s.green(),
+        Level::Warn => s.yellow(),
+        Level::Error => s.red(),
+    }
 }
 
-pub fn exit<T: Error>(err: T) -> ! {
-    let error_message = err.to_string();
-    error(&error_message);
-    process::exit(1)
+/// RFC 3339 UTC timestamp from milliseconds since the Unix epoch.
+fn timestamp(millis: u128) -> String {
+    let secs = (millis / 1000) as i64;
+    let (days, secs) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+
+    // Civil date from days since 1970-01-01, after Howard Hinnant's `civil_from_days`
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60,
+        millis % 1000
+    )
 }
 
-pub fn exit_with_info(err: CompilerError, source: &str) -> ! {
-    let full_message = err.to_string();
-    error(&full_message);
-    let error_line = match err {
-        CompilerError::Lexer(_, line) => line,
-        CompilerError::Parser(_, line) => line,
-        CompilerError::Interpreter(_, line) => line,
-    };
-
-    if error_line < 1 {
-        process::exit(1)
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let lines: Vec<&str> = source.split('\n').collect();
-    let start = if error_line <= SURROUNDING_LINES {
-        1
-    } else {
-        std::cmp::max(1, error_line - SURROUNDING_LINES)
-    };
-    let end = std::cmp::min(lines.len(), error_line + SURROUNDING_LINES);
-
-    let short_message = match err {
-        CompilerError::Lexer(e, _) => e,
-        CompilerError::Parser(e, _) => e,
-        CompilerError::Interpreter(e, _) => e,
-    };
-
-    for i in start..=end {
-        let line = lines[i - 1];
-        let padding = " ".repeat(5 - i.to_string().len());
-        let num = format!("{}{}| ", padding, i).blue();
-        println!("{}{}", num, line);
-        if i == error_line {
-            let padding = " ".repeat(num.len());
-            println!("{}{}", padding, "^".repeat(line.len()).red());
-            println!("{}{}", padding, short_message.red());
-        }
+    #[test]
+    fn timestamp_ok() {
+        assert_eq!(timestamp(0), "1970-01-01T00:00:00.000Z");
+        assert_eq!(timestamp(951_782_400_123), "2000-02-29T00:00:00.123Z");
+        assert_eq!(timestamp(1_792_329_296_500), "2026-10-18T13:14:56.500Z");
     }
-
-    process::exit(1)
 }
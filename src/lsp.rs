@@ -0,0 +1,174 @@
+use {
+    serde_json::{json, Value},
+    std::{
+        collections::HashMap,
+        io::{self, BufRead, Write},
+        path::Path,
+    },
+};
+
+use crate::lsp::document::{Document, Position};
+
+pub mod document;
+pub mod rpc;
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// Runs a Language Server Protocol server until the client sends `exit` or closes the input.
+pub fn run(input: &mut impl BufRead, output: &mut impl Write) -> io::Result<()> {
+    let mut server = Server::default();
+    while let Some(message) = rpc::read_message(input)? {
+        // The id of a message that cannot be parsed is unknown, so the error is sent without one
+        let message = match message {
+            Ok(message) => message,
+            Err(err) => {
+                let reply = json!({
+                    "jsonrpc": "2.0",
+                    "id": null,
+                    "error": { "code": PARSE_ERROR, "message": err }
+                });
+                rpc::write_message(output, &reply)?;
+                continue;
+            }
+        };
+        if message["method"] == "exit" {
+            break;
+        }
+        for reply in server.handle(&message) {
+            rpc::write_message(output, &reply)?;
+        }
+    }
+    Ok(())
+}
+
+#[derive(Default)]
+pub struct Server {
+    documents: HashMap<String, Document>,
+}
+
+impl Server {
+    /// Handles a request or notification, returning the messages to send back to the client.
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+
+        // Notifications have no id and never receive a response
+        let id = match message.get("id") {
+            Some(id) => id.clone(),
+            None => return self.notify(method, params),
+        };
+
+        let result = match method {
+            "initialize" => Ok(capabilities()),
+            "shutdown" => Ok(Value::Null),
+            "textDocument/definition" => self.with_position(params, |doc, uri, position| {
+                json!(doc
+                    .definition(position)
+                    .map(|range| json!({ "uri": uri, "range": range })))
+            }),
+            "textDocument/references" => self.with_position(params, |doc, uri, position| {
+                let include = params["context"]["includeDeclaration"]
+                    .as_bool()
+                    .unwrap_or(true);
+                let locations: Vec<Value> = doc
+                    .references(position, include)
+                    .into_iter()
+                    .map(|range| json!({ "uri": uri, "range": range }))
+                    .collect();
+                json!(locations)
+            }),
+            "textDocument/hover" => self.with_position(params, |doc, _, position| {
+                json!(doc.hover(position).map(|contents| json!({
+                    "contents": { "kind": "markdown", "value": contents }
+                })))
+            }),
+            "textDocument/completion" => {
+                self.with_position(params, |doc, _, _| json!(doc.completions()))
+            }
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method `{}`", method))),
+        };
+
+        let response = match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": code, "message": message }
+            }),
+        };
+        vec![response]
+    }
+
+    fn notify(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        let uri = match params["textDocument"]["uri"].as_str() {
+            Some(uri) => uri.to_string(),
+            None => return Vec::new(),
+        };
+        let text = match method {
+            "textDocument/didOpen" => params["textDocument"]["text"].as_str(),
+            // Only full document synchronisation is supported, so the last change has it all
+            "textDocument/didChange" => params["contentChanges"]
+                .as_array()
+                .and_then(|changes| changes.last())
+                .and_then(|change| change["text"].as_str()),
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                return vec![publish_diagnostics(&uri, json!([]))];
+            }
+            _ => None,
+        };
+
+        match text {
+            Some(text) => {
+                let path = uri.strip_prefix("file://").map(Path::new);
+                let document = Document::open(path, text);
+                let diagnostics = json!(document.diagnostics());
+                self.documents.insert(uri.clone(), document);
+                vec![publish_diagnostics(&uri, diagnostics)]
+            }
+            None => Vec::new(),
+        }
+    }
+
+    fn with_position(
+        &self,
+        params: &Value,
+        f: impl FnOnce(&Document, &str, &Position) -> Value,
+    ) -> Result<Value, (i64, String)> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let document = self
+            .documents
+            .get(uri)
+            .ok_or_else(|| (INVALID_PARAMS, format!("unknown document `{}`", uri)))?;
+        let position = Position {
+            line: params["position"]["line"].as_u64().unwrap_or_default() as usize,
+            character: params["position"]["character"].as_u64().unwrap_or_default() as usize,
+        };
+        Ok(f(document, uri, &position))
+    }
+}
+
+fn capabilities() -> Value {
+    json!({
+        "capabilities": {
+            // Full document synchronisation
+            "textDocumentSync": 1,
+            "definitionProvider": true,
+            "referencesProvider": true,
+            "hoverProvider": true,
+            "completionProvider": {}
+        },
+        "serverInfo": { "name": "lemma" }
+    })
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics }
+    })
+}
@@ -0,0 +1,379 @@
+use {
+    crate::{
+        ast, checker,
+        grammar::{Argument, Call, Clause, Element, Expression, Pattern, Program, Signature},
+        interpreter, lexer,
+        modules::{self, Sources},
+        tokens::{Identifier, Value},
+    },
+    serde::Serialize,
+    std::{
+        collections::{BTreeSet, HashMap},
+        path::Path,
+    },
+};
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Position {
+    pub line: usize,
+    pub character: usize,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Diagnostic {
+    pub range: Range,
+    pub severity: u8,
+    pub source: String,
+    pub message: String,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Completion {
+    pub label: Identifier,
+    pub kind: u8,
+    pub detail: String,
+}
+
+// Values defined by the LSP specification
+const SEVERITY_ERROR: u8 = 1;
+const COMPLETION_FUNCTION: u8 = 3;
+
+/// An open `.lm` file, analysed eagerly whenever its text changes.
+pub struct Document {
+    lines: Vec<Vec<char>>,
+    program: Option<Program>,
+    /// Line and message of each error.
+    errors: Vec<(usize, String)>,
+}
+
+impl Document {
+    pub fn new(source: &str) -> Self {
+        Self::open(None, source)
+    }
+
+    /// Analyses a document saved at `path`, whose imports are resolved relative to it.
+    pub fn open(path: Option<&Path>, source: &str) -> Self {
+        let lines = source.split('\n').map(|l| l.chars().collect()).collect();
+        let (program, errors) = match lexer::tokens(source).and_then(ast::build) {
+            Ok(program) if program.imports.is_empty() => {
+                let errors = checker::diagnostics(&program)
+                    .iter()
+                    .map(|err| (err.line(), err.message()))
+                    .collect();
+                (Some(program), errors)
+            }
+            Ok(program) => {
+                let errors = linked_diagnostics(path.unwrap_or(Path::new("")), source);
+                (Some(program), errors)
+            }
+            Err(err) => (None, vec![(err.line(), err.message())]),
+        };
+        Self {
+            lines,
+            program,
+            errors,
+        }
+    }
+
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.errors
+            .iter()
+            .map(|(line, message)| Diagnostic {
+                range: self.line_range(*line),
+                severity: SEVERITY_ERROR,
+                source: "lemma".to_string(),
+                message: message.clone(),
+            })
+            .collect()
+    }
+
+    pub fn definition(&self, position: &Position) -> Option<Range> {
+        let name = self.word_at(position)?;
+        let program = self.program.as_ref()?;
+        let function = program
+            .functions
+            .iter()
+            .find(|f| f.definition.name.id == name)?;
+        self.occurrences(&name, function.definition.name.line)
+            .into_iter()
+            .next()
+    }
+
+    pub fn references(&self, position: &Position, include_declaration: bool) -> Vec<Range> {
+        let (name, program) = match (self.word_at(position), &self.program) {
+            (Some(name), Some(program)) => (name, program),
+            _ => return Vec::new(),
+        };
+        if !program
+            .functions
+            .iter()
+            .any(|f| f.definition.name.id == name)
+        {
+            return Vec::new();
+        }
+
+        let mut lines: BTreeSet<usize> = BTreeSet::new();
+        for f in program.functions.iter() {
+            if include_declaration && f.definition.name.id == name {
+                lines.insert(f.definition.name.line);
+            }
+            // Parameters shadow functions of the same name
+            if f.definition.parameters.iter().all(|p| p.id != name) {
+                expression_lines(&f.definition.body, &name, &mut lines);
+            }
+        }
+
+        let mut ranges: Vec<Range> = lines
+            .into_iter()
+            .flat_map(|line| self.occurrences(&name, line))
+            .collect();
+        if !include_declaration {
+            let declaration = self.definition(position);
+            ranges.retain(|r| Some(r) != declaration.as_ref());
+        }
+        ranges
+    }
+
+    pub fn hover(&self, position: &Position) -> Option<String> {
+        let name = self.word_at(position)?;
+        let signature = self.signatures().remove(&name)?;
+        let parameters: Vec<Identifier> = self
+            .program
+            .iter()
+            .flat_map(|p| p.functions.iter())
+            .find(|f| f.definition.name.id == name)
+            .map(|f| {
+                f.definition
+                    .parameters
+                    .iter()
+                    .map(|p| p.id.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut definition = name;
+        for p in parameters {
+            definition.push(' ');
+            definition.push_str(&p);
+        }
+        Some(format!("```lemma\n{}\n{}\n```", signature, definition))
+    }
+
+    pub fn completions(&self) -> Vec<Completion> {
+        let mut signatures: Vec<(Identifier, Signature)> = self.signatures().into_iter().collect();
+        signatures.sort_by(|a, b| a.0.cmp(&b.0));
+        signatures
+            .into_iter()
+            .map(|(label, signature)| Completion {
+                label,
+                kind: COMPLETION_FUNCTION,
+                detail: signature.to_string(),
+            })
+            .collect()
+    }
+
+    /// Signatures of the prelude and of the program's functions, inferred where left out.
+    fn signatures(&self) -> HashMap<Identifier, Signature> {
+        match &self.program {
+            Some(program) => checker::signatures(program),
+            None => interpreter::prelude_signatures(),
+        }
+    }
+
+    /// Range covering the whole of a 1-indexed source line.
+    fn line_range(&self, line: usize) -> Range {
+        let line = line.saturating_sub(1);
+        let length = self.lines.get(line).map_or(0, |l| l.len());
+        Range {
+            start: Position { line, character: 0 },
+            end: Position {
+                line,
+                character: length,
+            },
+        }
+    }
+
+    fn word_at(&self, position: &Position) -> Option<Identifier> {
+        let line = self.lines.get(position.line)?;
+        let mut start = position.character.min(line.len());
+        while start > 0 && is_identifier_char(line[start - 1]) {
+            start -= 1;
+        }
+        let mut end = position.character.min(line.len());
+        while end < line.len() && is_identifier_char(line[end]) {
+            end += 1;
+        }
+        let word: String = line[start..end].iter().collect();
+        if word.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            Some(word)
+        } else {
+            None
+        }
+    }
+
+    /// Ranges of every whole-word occurrence of `name` in a 1-indexed source line.
+    fn occurrences(&self, name: &str, line: usize) -> Vec<Range> {
+        let index = line.saturating_sub(1);
+        let chars = match self.lines.get(index) {
+            Some(chars) => chars,
+            None => return Vec::new(),
+        };
+        let name: Vec<char> = name.chars().collect();
+        let mut ranges = Vec::new();
+        for start in 0..chars.len() {
+            let end = start + name.len();
+            if end > chars.len() || chars[start..end] != name[..] {
+                continue;
+            }
+            let before = start > 0 && is_identifier_char(chars[start - 1]);
+            let after = end < chars.len() && is_identifier_char(chars[end]);
+            if !before && !after {
+                ranges.push(Range {
+                    start: Position {
+                        line: index,
+                        character: start,
+                    },
+                    end: Position {
+                        line: index,
+                        character: end,
+                    },
+                });
+            }
+        }
+        ranges
+    }
+}
+
+/// Errors in a document and the files it imports. Errors in imported files are reported at the
+/// top of the document, naming the file they were raised in.
+fn linked_diagnostics(path: &Path, source: &str) -> Vec<(usize, String)> {
+    let mut sources = Sources::default();
+    let errors = match modules::load(path, source, &mut sources) {
+        Ok(program) => checker::diagnostics(&program),
+        Err(err) => vec![err],
+    };
+    errors
+        .into_iter()
+        .map(|err| match sources.resolve(err) {
+            (Some(file), err) if !file.is_root() => (
+                0,
+                format!("{}:{}: {}", file.path.display(), err.line(), err.message()),
+            ),
+            (_, err) => (err.line(), err.message()),
+        })
+        .collect()
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '-'
+}
+
+fn expression_lines(expr: &Expression, name: &str, lines: &mut BTreeSet<usize>) {
+    match expr {
+        Expression::Value(v) => value_lines(v, name, lines),
+        Expression::Call(Call::Operation(op)) => {
+            for arg in op.arguments.iter() {
+                argument_lines(arg, name, lines);
+            }
+        }
+        Expression::Call(Call::FunctionCall(fc)) => {
+            if fc.name.id == name {
+                lines.insert(fc.name.line);
+            }
+            for arg in fc.arguments.iter() {
+                argument_lines(arg, name, lines);
+            }
+        }
+        Expression::Call(Call::Pipe(p)) => {
+            expression_lines(&p.left, name, lines);
+            if p.right == name {
+                lines.insert(p.line);
+            }
+        }
+        Expression::Call(Call::Construction(c)) => {
+            for (_, value) in c.fields.iter() {
+                expression_lines(value, name, lines);
+            }
+        }
+        Expression::Call(Call::Access(a)) => argument_lines(&a.record, name, lines),
+        Expression::Call(Call::Variant(v)) => {
+            if v.name.id == name {
+                lines.insert(v.name.line);
+            }
+            for arg in v.arguments.iter() {
+                argument_lines(arg, name, lines);
+            }
+        }
+        Expression::Call(Call::Tuple(t)) => {
+            for e in t.elements.iter() {
+                expression_lines(e, name, lines);
+            }
+        }
+        Expression::Conditional(c) => {
+            expression_lines(&c.condition, name, lines);
+            expression_lines(&c.then, name, lines);
+            expression_lines(&c.otherwise, name, lines);
+        }
+        Expression::Try(t) => {
+            expression_lines(&t.body, name, lines);
+            expression_lines(&t.handler, name, lines);
+        }
+        Expression::Raise(r) => expression_lines(&r.message, name, lines),
+        Expression::Comprehension(c) => {
+            expression_lines(&c.element, name, lines);
+            for clause in c.clauses.iter() {
+                match clause {
+                    Clause::Generator { list, .. } => expression_lines(list, name, lines),
+                    Clause::Guard(condition) => expression_lines(condition, name, lines),
+                }
+            }
+        }
+        Expression::Builtin(_) => {}
+        Expression::Case(c) => {
+            expression_lines(&c.subject, name, lines);
+            for arm in c.arms.iter() {
+                if matches!(&arm.pattern, Pattern::Variant { name: n, .. } if n.id == name) {
+                    lines.insert(arm.line);
+                }
+                expression_lines(&arm.body, name, lines);
+            }
+        }
+    }
+}
+
+fn argument_lines(arg: &Argument, name: &str, lines: &mut BTreeSet<usize>) {
+    match arg {
+        Argument::Value(v) => value_lines(v, name, lines),
+        Argument::Identifier(l_id) => {
+            if l_id.id == name {
+                lines.insert(l_id.line);
+            }
+        }
+        Argument::ParenExpression(expr) => expression_lines(expr, name, lines),
+    }
+}
+
+fn value_lines(value: &Value, name: &str, lines: &mut BTreeSet<usize>) {
+    if let Value::List(list) = value {
+        for e in list.elements.iter() {
+            match e {
+                Element::Identifier(id) if id == name => {
+                    lines.insert(list.line);
+                }
+                Element::Identifier(_) => {}
+                Element::Value(v) => value_lines(v, name, lines),
+                Element::Range(r) => {
+                    for bound in r.bounds() {
+                        argument_lines(bound, name, lines);
+                    }
+                }
+            }
+        }
+    }
+}
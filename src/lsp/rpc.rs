@@ -0,0 +1,41 @@
+use {
+    serde_json::Value,
+    std::io::{self, BufRead, Write},
+};
+
+/// Reads a single JSON-RPC message framed by a `Content-Length` header. Returns `None` once the
+/// input is exhausted, and the parse error if the body is not valid JSON.
+pub fn read_message(input: &mut impl BufRead) -> io::Result<Option<Result<Value, String>>> {
+    let mut length: Option<usize> = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse().ok();
+        }
+    }
+
+    let length = length.ok_or_else(|| invalid_data("missing Content-Length header"))?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    // The body has been consumed, so the next message can still be read after an invalid one
+    Ok(Some(
+        serde_json::from_slice(&body).map_err(|err| err.to_string()),
+    ))
+}
+
+pub fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    output.flush()
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
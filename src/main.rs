@@ -1,52 +1,146 @@
-use std::{env, fmt::Write};
+use {
+    colored::Colorize,
+    log::{debug, info},
+    std::{env, error::Error, fs, path::Path, process},
+};
 
 use lemma::{
-    args::Args,
-    ast, interpreter, lexer,
-    log::{self, env_log_level, exit, exit_with_info},
-    printer,
-    tokens::{Symbol, Token},
+    args::{ApplicationError, Args, Command},
+    checker, codegen,
+    diagnostics::{self, Diagnostic, DiagnosticsFormat},
+    errors::{self, CompilerError},
+    interpreter::{self, Options},
+    log::Logger,
+    modules::{self, Sources},
+    optimiser, printer,
 };
 
 fn main() {
-    log::debug("Starting application");
-    log::debug(&format!("Log level set to {}", env_log_level()));
+    let logger = Logger::from_env().unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(1)
+    });
+    let level = logger.level;
+    if let Err(err) = logger.init() {
+        eprintln!("{}", err);
+    }
+    debug!("Starting application");
+    debug!("Log level set to {}", level);
 
-    log::debug("Parsing arguments...");
+    debug!("Parsing arguments...");
     let args = Args::build(&mut env::args()).unwrap_or_else(|err| exit(err));
-    log::debug(&format!("{:?}", args));
+    debug!("{:?}", args);
 
-    log::debug(&format!("Reading source code from {}", args.file_path));
+    if let Some(code) = &args.explain {
+        match errors::explain(code) {
+            Some(explanation) => {
+                print!("{}", explanation);
+                process::exit(0)
+            }
+            None => exit(ApplicationError::Args(format!(
+                "unknown error code `{}`",
+                code
+            ))),
+        }
+    }
+
+    debug!("Reading source code from {}", args.file_path);
     let source = args.source().unwrap_or_else(|err| exit(err));
-    log::debug(&format!("Source:\n{}", source));
-
-    log::debug("Starting lexical analysis...");
-    let tokens = lexer::tokens(&source).unwrap_or_else(|err| exit_with_info(err, &source));
-    let tokens_str = tokens
-        .iter()
-        .filter(|t| !matches!(*t, Token::Symbol(Symbol::EOL, _)))
-        .fold(String::new(), |mut acc, t| {
-            writeln!(acc, "{:?}", t).unwrap();
-            acc
-        });
-    log::debug(&format!("Tokens:\n{}", tokens_str));
+    debug!("Source:\n{}", source);
+
+    debug!("Loading modules...");
+    let mut sources = Sources::default();
+    let program = modules::load(Path::new(&args.file_path), &source, &mut sources)
+        .unwrap_or_else(|err| report(err, &args, &sources));
+    if log::log_enabled!(log::Level::Debug) {
+        let json = serde_json::to_value(&program).unwrap();
+        debug!("AST:\n{}", printer::pretty_print_ast(json));
+    }
+
+    if args.command == Command::Check {
+        debug!("Checking program...");
+        if let Err(err) = checker::check(&program) {
+            report(err, &args, &sources);
+        }
+        let signatures = checker::signatures(&program);
+        for function in program.functions.iter() {
+            let name = &function.definition.name.id;
+            println!("{} : {}", name, signatures[name]);
+        }
+        info!("Exiting");
+        return;
+    }
+
+    // Code is generated from the optimised program, but checked as written
+    if args.command == Command::Build {
+        debug!("Checking program...");
+        if let Err(err) = checker::check(&program) {
+            report(err, &args, &sources);
+        }
+    }
+
+    debug!("Optimising program...");
+    let program = optimiser::optimise(program);
+    if args.print_optimised {
+        let json = serde_json::to_value(&program).unwrap();
+        print!("{}", printer::pretty_print_ast(json));
+        info!("Exiting");
+        return;
+    }
 
-    log::debug("Building AST...");
-    let program = ast::build(tokens).unwrap_or_else(|err| exit_with_info(err, &source));
-    let json = serde_json::to_value(&program).unwrap();
-    let tree = printer::pretty_print_ast(json);
-    log::debug(&format!("AST:\n{}", tree));
+    if args.command == Command::Build {
+        debug!("Generating C...");
+        let c = codegen::generate(&program).unwrap_or_else(|err| report(err, &args, &sources));
+        let output = args.output.clone().unwrap_or_else(|| {
+            let path = Path::new(&args.file_path).with_extension("c");
+            path.display().to_string()
+        });
+        if let Err(error) = fs::write(&output, c) {
+            let message = format!("could not write file `{}` ({})", output, error.kind());
+            exit(ApplicationError::Args(message));
+        }
+        info!("Wrote {}", output);
+        info!("Exiting");
+        return;
+    }
 
-    log::debug("Evaluating program...");
+    debug!("Evaluating program...");
 
     let now = std::time::Instant::now();
-    let out = interpreter::evaluate(program).unwrap_or_else(|err| exit_with_info(err, &source));
+    let options = Options {
+        overflow: args.overflow,
+        evaluation: args.evaluation,
+        memoise: args.memoise,
+    };
+    let out = interpreter::evaluate_with(program, options)
+        .unwrap_or_else(|err| report(err, &args, &sources));
 
     if let Some(output) = out {
         println!("{}", output);
     }
     let elapsed = now.elapsed();
-    log::info(&format!("Execution time: {}s", elapsed.as_secs_f64()));
+    info!("Execution time: {}s", elapsed.as_secs_f64());
 
-    log::info("Exiting");
+    info!("Exiting");
+}
+
+fn exit(err: impl Error) -> ! {
+    eprintln!("{:>5} {}", "ERROR".red(), err);
+    process::exit(1)
+}
+
+fn report(err: CompilerError, args: &Args, sources: &Sources) -> ! {
+    let (source, err) = sources.resolve(err);
+    let (file, source) = match source {
+        Some(source) => (source.path.display().to_string(), source.text.as_str()),
+        None => (args.file_path.clone(), ""),
+    };
+    match args.diagnostics_format {
+        DiagnosticsFormat::Human => eprint!("{}", diagnostics::human(&err, &file, source)),
+        format => {
+            let diagnostic = Diagnostic::new(&err, &file, source);
+            println!("{}", diagnostics::render(format, &[diagnostic]));
+        }
+    }
+    process::exit(err.exit_code())
 }
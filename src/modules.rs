@@ -0,0 +1,518 @@
+use {
+    crate::{
+        ast,
+        errors::{CompilerError, ModuleError},
+        grammar::{
+            Argument, Call, Clause, Element, Expression, Function, Import, Pattern, Program,
+        },
+        lexer,
+        tokens::{Identifier, Symbol, Token, Value},
+        utils,
+    },
+    log::debug,
+    std::{
+        collections::{hash_map::Entry, HashMap},
+        env, fs,
+        path::{Path, PathBuf},
+    },
+};
+
+/// A file making up part of a program.
+#[derive(Debug)]
+pub struct Source {
+    pub path: PathBuf,
+    pub text: String,
+    offset: usize,
+    lines: usize,
+}
+
+/// Every file loaded for a program. Lines of each file are offset so that a line number in the
+/// linked program identifies both the file and the line within it.
+#[derive(Debug, Default)]
+pub struct Sources {
+    files: Vec<Source>,
+}
+
+impl Source {
+    /// Whether this is the file the program was loaded from, rather than an import.
+    pub fn is_root(&self) -> bool {
+        self.offset == 0
+    }
+}
+
+impl Sources {
+    fn add(&mut self, path: &Path, text: &str) -> usize {
+        let offset = self.files.last().map_or(0, |f| f.offset + f.lines + 1);
+        // Report paths relative to the working directory where possible
+        let directory = env::current_dir().unwrap_or_default();
+        self.files.push(Source {
+            path: path.strip_prefix(directory).unwrap_or(path).to_path_buf(),
+            text: text.to_string(),
+            offset,
+            lines: text.split('\n').count(),
+        });
+        offset
+    }
+
+    /// File containing a line of the linked program, and the line within that file. Line 0 is
+    /// not tied to any line and is reported against the first file.
+    pub fn locate(&self, line: usize) -> Option<(&Source, usize)> {
+        if line == 0 {
+            return self.files.first().map(|f| (f, 0));
+        }
+        self.files
+            .iter()
+            .find(|f| line > f.offset && line <= f.offset + f.lines + 1)
+            .map(|f| (f, line - f.offset))
+    }
+
+    /// Splits an error on the linked program into the file it was raised in and the error with
+    /// its line relative to that file.
+    pub fn resolve(&self, err: CompilerError) -> (Option<&Source>, CompilerError) {
+        match self.locate(err.line()) {
+            Some((source, line)) => (Some(source), err.with_line(line)),
+            None => (None, err),
+        }
+    }
+}
+
+struct Module {
+    path: PathBuf,
+    /// Qualifier for the linked names of the module's functions, `None` for the root file.
+    prefix: Option<String>,
+    program: Program,
+    /// Index of the module loaded for each of `program.imports`.
+    imports: Vec<usize>,
+}
+
+impl Module {
+    fn linked_name(&self, name: &str) -> Identifier {
+        match &self.prefix {
+            Some(prefix) => format!("{}.{}", prefix, name),
+            None => name.to_string(),
+        }
+    }
+
+    fn defines(&self, name: &str) -> bool {
+        self.program
+            .functions
+            .iter()
+            .any(|f| f.definition.name.id == name)
+    }
+}
+
+/// Loads a program and every file it imports, linking them into a single program. Functions
+/// of imported files are renamed to `file.name`, after the file stem, so that they never clash
+/// with each other or with the importer.
+pub fn load(path: &Path, source: &str, sources: &mut Sources) -> Result<Program, CompilerError> {
+    let mut loader = Loader {
+        sources,
+        modules: Vec::new(),
+        loaded: HashMap::new(),
+        stack: Vec::new(),
+    };
+    let root = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    loader.visit(&root, Some(source), 0)?;
+    let mut program = loader.link()?;
+    program.offsets = sources.files.iter().map(|f| f.offset).collect();
+    Ok(program)
+}
+
+struct Loader<'a> {
+    sources: &'a mut Sources,
+    modules: Vec<Module>,
+    loaded: HashMap<PathBuf, usize>,
+    /// Files currently being loaded, to detect cycles.
+    stack: Vec<PathBuf>,
+}
+
+impl Loader<'_> {
+    fn visit(
+        &mut self,
+        path: &Path,
+        source: Option<&str>,
+        line: usize,
+    ) -> Result<usize, CompilerError> {
+        if let Some(start) = self.stack.iter().position(|p| p == path) {
+            let mut chain: Vec<String> = self.stack[start..].iter().map(|p| display(p)).collect();
+            chain.push(display(path));
+            return Err(CompilerError::Module(ModuleError::ImportCycle(chain), line));
+        }
+        if let Some(index) = self.loaded.get(path) {
+            return Ok(*index);
+        }
+
+        let text = match source {
+            Some(source) => source.to_string(),
+            None => fs::read_to_string(path).map_err(|_| {
+                CompilerError::Module(ModuleError::ImportNotFound(display(path)), line)
+            })?,
+        };
+        debug!("Loading {}", path.display());
+        let program = self.parse(path, &text, source.is_none())?;
+
+        self.stack.push(path.to_path_buf());
+        let directory = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        let mut imports = Vec::new();
+        for import in program.imports.iter() {
+            let imported = directory.join(&import.path);
+            let imported = fs::canonicalize(&imported).map_err(|_| {
+                CompilerError::Module(
+                    ModuleError::ImportNotFound(import.path.clone()),
+                    import.line,
+                )
+            })?;
+            imports.push(self.visit(&imported, None, import.line)?);
+        }
+        self.stack.pop();
+
+        let prefix = match source {
+            Some(_) => None,
+            None => Some(self.prefix(path)),
+        };
+        self.modules.push(Module {
+            path: path.to_path_buf(),
+            prefix,
+            program,
+            imports,
+        });
+        let index = self.modules.len() - 1;
+        self.loaded.insert(path.to_path_buf(), index);
+        Ok(index)
+    }
+
+    fn parse(&mut self, path: &Path, text: &str, imported: bool) -> Result<Program, CompilerError> {
+        let offset = self.sources.add(path, text);
+        let shift = |err: CompilerError| {
+            let line = err.line() + offset;
+            err.with_line(line)
+        };
+
+        let mut tokens = lexer::tokens(text).map_err(shift)?;
+        for token in tokens.iter_mut() {
+            *token.line_mut() += offset;
+        }
+        // A file with nothing to export is only an error if it is the program itself
+        if imported && tokens.is_empty() {
+            return Ok(Program {
+                imports: Vec::new(),
+                types: Vec::new(),
+                aliases: Vec::new(),
+                functions: Vec::new(),
+                offsets: Vec::new(),
+            });
+        }
+        if log::log_enabled!(log::Level::Debug) {
+            let tokens: Vec<String> = tokens
+                .iter()
+                .filter(|t| !matches!(*t, Token::Symbol(Symbol::EOL, _)))
+                .map(|t| format!("{:?}", t))
+                .collect();
+            debug!("Tokens:\n{}", tokens.join("\n"));
+        }
+        ast::build(tokens)
+    }
+
+    /// Unique qualifier for an imported file, after its stem.
+    fn prefix(&self, path: &Path) -> String {
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let taken = |p: &str| self.modules.iter().any(|m| m.prefix.as_deref() == Some(p));
+        let mut prefix = stem.clone();
+        let mut n = 2;
+        while taken(&prefix) {
+            prefix = format!("{}-{}", stem, n);
+            n += 1;
+        }
+        prefix
+    }
+
+    /// Declared types and aliases are not namespaced, and those of imported files are visible to
+    /// the importer whichever of their functions are imported.
+    fn link(self) -> Result<Program, CompilerError> {
+        let mut linked = Program {
+            imports: Vec::new(),
+            types: Vec::new(),
+            aliases: Vec::new(),
+            functions: Vec::new(),
+            offsets: Vec::new(),
+        };
+        let mut declared: HashMap<&Identifier, &Path> = HashMap::new();
+        for module in self.modules.iter() {
+            let types = module.program.types.iter().map(|t| &t.name);
+            let aliases = module.program.aliases.iter().map(|a| &a.name);
+            for name in types.chain(aliases) {
+                if let Some(first) = declared.insert(&name.id, &module.path) {
+                    return Err(CompilerError::Module(
+                        ModuleError::DuplicateDefinition {
+                            name: name.id.clone(),
+                            first: display(first),
+                            second: display(&module.path),
+                        },
+                        name.line,
+                    ));
+                }
+            }
+            linked.types.extend(module.program.types.iter().cloned());
+            linked
+                .aliases
+                .extend(module.program.aliases.iter().cloned());
+        }
+
+        for module in self.modules.iter() {
+            if !module.imports.is_empty() {
+                let imported: Vec<Identifier> = module
+                    .imports
+                    .iter()
+                    .map(|i| &self.modules[*i].program)
+                    .flat_map(|p| {
+                        let types = p.types.iter().map(|t| &t.name);
+                        types.chain(p.aliases.iter().map(|a| &a.name))
+                    })
+                    .map(|name| name.id.clone())
+                    .collect();
+                ast::check_types(&module.program, &imported)?;
+            }
+
+            let names = self.names(module)?;
+            for function in module.program.functions.iter() {
+                linked.functions.push(rename(function, &names));
+            }
+        }
+        let aliases = linked.aliases.clone();
+        ast::expand_aliases(&mut linked, &aliases)?;
+        Ok(linked)
+    }
+
+    /// Linked name of every function visible in a module, by the name used within it.
+    fn names(&self, module: &Module) -> Result<HashMap<Identifier, Identifier>, CompilerError> {
+        // Local name to linked name and the file defining it
+        let mut names: HashMap<Identifier, (Identifier, &Path)> = HashMap::new();
+        for function in module.program.functions.iter() {
+            let name = &function.definition.name.id;
+            names.insert(name.clone(), (module.linked_name(name), &module.path));
+        }
+
+        for (import, index) in module.program.imports.iter().zip(module.imports.iter()) {
+            let imported = &self.modules[*index];
+            for name in exported(import, imported)? {
+                let local = match &import.alias {
+                    Some(alias) => format!("{}.{}", alias, name),
+                    None => name.clone(),
+                };
+                let linked = imported.linked_name(&name);
+                match names.entry(local) {
+                    Entry::Vacant(e) => {
+                        e.insert((linked, &imported.path));
+                    }
+                    Entry::Occupied(e) if e.get().0 == linked => {}
+                    Entry::Occupied(e) => {
+                        return Err(CompilerError::Module(
+                            ModuleError::DuplicateDefinition {
+                                name: e.key().clone(),
+                                first: display(e.get().1),
+                                second: display(&imported.path),
+                            },
+                            import.line,
+                        ))
+                    }
+                }
+            }
+        }
+
+        Ok(names.into_iter().map(|(k, (v, _))| (k, v)).collect())
+    }
+}
+
+/// Names brought into scope by an import, checking that selected names exist.
+fn exported(import: &Import, module: &Module) -> Result<Vec<Identifier>, CompilerError> {
+    match &import.names {
+        Some(names) => names
+            .iter()
+            .map(|name| {
+                if module.defines(&name.id) {
+                    Ok(name.id.clone())
+                } else {
+                    let candidates = module
+                        .program
+                        .functions
+                        .iter()
+                        .map(|f| &f.definition.name.id);
+                    Err(CompilerError::Module(
+                        ModuleError::UnknownImport {
+                            name: name.id.clone(),
+                            module: import.path.clone(),
+                            suggestion: utils::closest(&name.id, candidates),
+                        },
+                        name.line,
+                    ))
+                }
+            })
+            .collect(),
+        None => Ok(module
+            .program
+            .functions
+            .iter()
+            .map(|f| f.definition.name.id.clone())
+            .collect()),
+    }
+}
+
+fn display(path: &Path) -> String {
+    path.file_name().map_or_else(
+        || path.display().to_string(),
+        |n| n.to_string_lossy().to_string(),
+    )
+}
+
+/// Rewrites the function names in a function to their linked names. Parameters shadow
+/// functions, so their names are left alone.
+fn rename(function: &Function, names: &HashMap<Identifier, Identifier>) -> Function {
+    let mut function = function.clone();
+    let parameters: Vec<Identifier> = function
+        .definition
+        .parameters
+        .iter()
+        .map(|p| p.id.clone())
+        .collect();
+    let resolve = |id: &mut Identifier| {
+        if !parameters.contains(id) {
+            if let Some(linked) = names.get(id) {
+                *id = linked.clone();
+            }
+        }
+    };
+    resolve(&mut function.definition.name.id);
+    rename_expression(&mut function.definition.body, &resolve);
+    function
+}
+
+fn rename_expression(expr: &mut Expression, resolve: &dyn Fn(&mut Identifier)) {
+    match expr {
+        Expression::Value(v) => rename_value(v, resolve),
+        Expression::Call(Call::Operation(op)) => {
+            for arg in op.arguments.iter_mut() {
+                rename_argument(arg, resolve);
+            }
+        }
+        Expression::Call(Call::FunctionCall(fc)) => {
+            resolve(&mut fc.name.id);
+            for arg in fc.arguments.iter_mut() {
+                rename_argument(arg, resolve);
+            }
+        }
+        Expression::Call(Call::Pipe(p)) => {
+            rename_expression(&mut p.left, resolve);
+            resolve(&mut p.right);
+        }
+        Expression::Call(Call::Construction(c)) => {
+            for (_, value) in c.fields.iter_mut() {
+                rename_expression(value, resolve);
+            }
+        }
+        Expression::Call(Call::Access(a)) => rename_argument(&mut a.record, resolve),
+        Expression::Call(Call::Variant(v)) => {
+            for arg in v.arguments.iter_mut() {
+                rename_argument(arg, resolve);
+            }
+        }
+        Expression::Call(Call::Tuple(t)) => {
+            for e in t.elements.iter_mut() {
+                rename_expression(e, resolve);
+            }
+        }
+        Expression::Conditional(c) => {
+            rename_expression(&mut c.condition, resolve);
+            rename_expression(&mut c.then, resolve);
+            rename_expression(&mut c.otherwise, resolve);
+        }
+        Expression::Try(t) => {
+            rename_expression(&mut t.body, resolve);
+            // Like pattern bindings, the error's bindings shadow functions
+            let bindings: Vec<Identifier> = match &t.pattern {
+                Pattern::Tuple(bindings) => bindings.iter().map(|b| b.id.clone()).collect(),
+                _ => Vec::new(),
+            };
+            let shadowed = |id: &mut Identifier| {
+                if !bindings.contains(id) {
+                    resolve(id);
+                }
+            };
+            rename_expression(&mut t.handler, &shadowed);
+        }
+        Expression::Raise(r) => rename_expression(&mut r.message, resolve),
+        Expression::Comprehension(c) => {
+            // Generator bindings shadow functions in the clauses after them and in the element
+            let mut bindings: Vec<Identifier> = Vec::new();
+            for clause in c.clauses.iter_mut() {
+                let shadowed = |id: &mut Identifier| {
+                    if !bindings.contains(id) {
+                        resolve(id);
+                    }
+                };
+                match clause {
+                    Clause::Generator { name, list } => {
+                        rename_expression(list, &shadowed);
+                        bindings.push(name.id.clone());
+                    }
+                    Clause::Guard(condition) => rename_expression(condition, &shadowed),
+                }
+            }
+            let shadowed = |id: &mut Identifier| {
+                if !bindings.contains(id) {
+                    resolve(id);
+                }
+            };
+            rename_expression(&mut c.element, &shadowed);
+        }
+        Expression::Builtin(_) => {}
+        Expression::Case(c) => {
+            rename_expression(&mut c.subject, resolve);
+            for arm in c.arms.iter_mut() {
+                // Pattern bindings shadow functions, like parameters
+                let bindings: Vec<Identifier> = match &arm.pattern {
+                    Pattern::Variant { bindings, .. } | Pattern::Tuple(bindings) => {
+                        bindings.iter().map(|b| b.id.clone()).collect()
+                    }
+                    Pattern::Wildcard => Vec::new(),
+                };
+                let shadowed = |id: &mut Identifier| {
+                    if !bindings.contains(id) {
+                        resolve(id);
+                    }
+                };
+                rename_expression(&mut arm.body, &shadowed);
+            }
+        }
+    }
+}
+
+fn rename_argument(arg: &mut Argument, resolve: &dyn Fn(&mut Identifier)) {
+    match arg {
+        Argument::Value(v) => rename_value(v, resolve),
+        Argument::Identifier(l_id) => resolve(&mut l_id.id),
+        Argument::ParenExpression(expr) => rename_expression(expr, resolve),
+    }
+}
+
+fn rename_value(value: &mut Value, resolve: &dyn Fn(&mut Identifier)) {
+    if let Value::List(list) = value {
+        for e in list.elements.iter_mut() {
+            match e {
+                Element::Identifier(id) => resolve(id),
+                Element::Value(v) => rename_value(v, resolve),
+                Element::Range(r) => {
+                    rename_argument(&mut r.start, resolve);
+                    if let Some(next) = r.next.as_mut() {
+                        rename_argument(next, resolve);
+                    }
+                    if let Some(end) = r.end.as_mut() {
+                        rename_argument(end, resolve);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use {
+    crate::{
+        grammar::{Argument, Call, Clause, Element, Expression, Operation, Pattern, Program},
+        interpreter,
+        tokens::{Identifier, Operator, Value},
+    },
+    std::collections::{HashMap, HashSet},
+};
+
+/// Simplifies a program without changing what it does: operations on literals are computed,
+/// conditionals on literals are replaced by the branch they take, and functions of no parameters
+/// whose bodies are literals are replaced by their values where they are used.
+pub fn optimise(mut program: Program) -> Program {
+    // Names are looked up where a function is called, so a constant is only inlined if no
+    // parameter or pattern of the program could stand in for it
+    let bound = bound(&program);
+    // Inlining may make more functions constant, so the passes are repeated until none are left
+    let mut known = constants(&program, &bound);
+    loop {
+        for function in program.functions.iter_mut() {
+            expression(&mut function.definition.body, &known);
+        }
+        let found = constants(&program, &bound);
+        if found.len() == known.len() {
+            return program;
+        }
+        known = found;
+    }
+}
+
+/// The values of the functions of no parameters whose bodies are literals and whose names are not
+/// bound anywhere.
+fn constants(program: &Program, bound: &HashSet<Identifier>) -> HashMap<Identifier, Value> {
+    program
+        .functions
+        .iter()
+        .filter(|f| f.definition.parameters.is_empty())
+        .filter(|f| !bound.contains(&f.definition.name.id))
+        .filter_map(|f| match &f.definition.body {
+            Expression::Value(v) if constant(v) => Some((f.definition.name.id.clone(), v.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Whether a value is the same wherever it is used. Lists may refer to variables, and the type
+/// variables of their types are bound by the function they are evaluated in.
+fn constant(value: &Value) -> bool {
+    match value {
+        Value::List(list) => {
+            list.list_type.variable().is_none()
+                && list.elements.iter().all(|e| match e {
+                    Element::Value(v) => constant(v),
+                    _ => false,
+                })
+        }
+        Value::Lazy(_) => false,
+        _ => true,
+    }
+}
+
+/// The names bound by the parameters, patterns and generators of a program.
+fn bound(program: &Program) -> HashSet<Identifier> {
+    let mut names = HashSet::new();
+    for function in program.functions.iter() {
+        names.extend(function.definition.parameters.iter().map(|p| p.id.clone()));
+        bindings(&function.definition.body, &mut names);
+    }
+    names
+}
+
+fn bindings(expr: &Expression, names: &mut HashSet<Identifier>) {
+    let pattern = |pattern: &Pattern, names: &mut HashSet<Identifier>| match pattern {
+        Pattern::Variant { bindings, .. } | Pattern::Tuple(bindings) => {
+            names.extend(bindings.iter().map(|b| b.id.clone()))
+        }
+        Pattern::Wildcard => {}
+    };
+    let argument = |arg: &Argument, names: &mut HashSet<Identifier>| {
+        if let Argument::ParenExpression(expr) = arg {
+            bindings(expr, names);
+        }
+    };
+    match expr {
+        Expression::Call(Call::Operation(op)) => {
+            op.arguments.iter().for_each(|arg| argument(arg, names))
+        }
+        Expression::Call(Call::FunctionCall(fc)) => {
+            fc.arguments.iter().for_each(|arg| argument(arg, names))
+        }
+        Expression::Call(Call::Pipe(p)) => bindings(&p.left, names),
+        Expression::Call(Call::Construction(c)) => c
+            .fields
+            .iter()
+            .for_each(|(_, value)| bindings(value, names)),
+        Expression::Call(Call::Access(a)) => argument(&a.record, names),
+        Expression::Call(Call::Variant(v)) => {
+            v.arguments.iter().for_each(|arg| argument(arg, names))
+        }
+        Expression::Call(Call::Tuple(t)) => t.elements.iter().for_each(|e| bindings(e, names)),
+        Expression::Conditional(c) => {
+            bindings(&c.condition, names);
+            bindings(&c.then, names);
+            bindings(&c.otherwise, names);
+        }
+        Expression::Case(c) => {
+            bindings(&c.subject, names);
+            for arm in c.arms.iter() {
+                pattern(&arm.pattern, names);
+                bindings(&arm.body, names);
+            }
+        }
+        Expression::Try(t) => {
+            bindings(&t.body, names);
+            pattern(&t.pattern, names);
+            bindings(&t.handler, names);
+        }
+        Expression::Raise(r) => bindings(&r.message, names),
+        Expression::Comprehension(c) => {
+            for clause in c.clauses.iter() {
+                match clause {
+                    Clause::Generator { name, list } => {
+                        names.insert(name.id.clone());
+                        bindings(list, names);
+                    }
+                    Clause::Guard(condition) => bindings(condition, names),
+                }
+            }
+            bindings(&c.element, names);
+        }
+        Expression::Value(Value::List(list)) => {
+            for e in list.elements.iter() {
+                if let Element::Range(r) = e {
+                    r.bounds().for_each(|arg| argument(arg, names));
+                }
+            }
+        }
+        Expression::Value(_) | Expression::Builtin(_) => {}
+    }
+}
+
+fn expression(expr: &mut Expression, constants: &HashMap<Identifier, Value>) {
+    match expr {
+        Expression::Value(v) => value(v, constants),
+        Expression::Call(Call::Operation(op)) => {
+            for arg in op.arguments.iter_mut() {
+                argument(arg, constants);
+            }
+            if let Some(v) = fold(op) {
+                *expr = Expression::Value(v);
+            }
+        }
+        Expression::Call(Call::FunctionCall(fc)) => {
+            if fc.arguments.is_empty() {
+                if let Some(v) = constants.get(&fc.name.id) {
+                    *expr = Expression::Value(v.clone());
+                    return;
+                }
+            }
+            for arg in fc.arguments.iter_mut() {
+                argument(arg, constants);
+            }
+        }
+        Expression::Call(Call::Pipe(p)) => expression(&mut p.left, constants),
+        Expression::Call(Call::Construction(c)) => {
+            for (_, value) in c.fields.iter_mut() {
+                expression(value, constants);
+            }
+        }
+        Expression::Call(Call::Access(a)) => argument(&mut a.record, constants),
+        Expression::Call(Call::Variant(v)) => {
+            for arg in v.arguments.iter_mut() {
+                argument(arg, constants);
+            }
+        }
+        Expression::Call(Call::Tuple(t)) => {
+            for e in t.elements.iter_mut() {
+                expression(e, constants);
+            }
+        }
+        Expression::Conditional(c) => {
+            expression(&mut c.condition, constants);
+            // Only the branch taken is kept
+            if let Expression::Value(Value::Boolean(b)) = *c.condition {
+                let mut branch = if b {
+                    *c.then.clone()
+                } else {
+                    *c.otherwise.clone()
+                };
+                expression(&mut branch, constants);
+                *expr = branch;
+                return;
+            }
+            expression(&mut c.then, constants);
+            expression(&mut c.otherwise, constants);
+        }
+        Expression::Case(c) => {
+            expression(&mut c.subject, constants);
+            for arm in c.arms.iter_mut() {
+                expression(&mut arm.body, constants);
+            }
+        }
+        Expression::Try(t) => {
+            expression(&mut t.body, constants);
+            expression(&mut t.handler, constants);
+        }
+        Expression::Raise(r) => expression(&mut r.message, constants),
+        Expression::Comprehension(c) => {
+            for clause in c.clauses.iter_mut() {
+                match clause {
+                    Clause::Generator { list, .. } => expression(list, constants),
+                    Clause::Guard(condition) => expression(condition, constants),
+                }
+            }
+            expression(&mut c.element, constants);
+        }
+        Expression::Builtin(_) => {}
+    }
+}
+
+fn argument(arg: &mut Argument, constants: &HashMap<Identifier, Value>) {
+    match arg {
+        Argument::Value(v) => value(v, constants),
+        Argument::Identifier(l_id) => {
+            if let Some(v) = constants.get(&l_id.id) {
+                *arg = Argument::Value(v.clone());
+            }
+        }
+        Argument::ParenExpression(expr) => {
+            expression(expr, constants);
+            if let Expression::Value(v) = expr {
+                *arg = Argument::Value(v.clone());
+            }
+        }
+    }
+}
+
+fn value(v: &mut Value, constants: &HashMap<Identifier, Value>) {
+    if let Value::List(list) = v {
+        for e in list.elements.iter_mut() {
+            match e {
+                Element::Identifier(id) => {
+                    if let Some(v) = constants.get(id) {
+                        *e = Element::Value(v.clone());
+                    }
+                }
+                Element::Value(v) => value(v, constants),
+                Element::Range(r) => {
+                    argument(&mut r.start, constants);
+                    if let Some(next) = r.next.as_mut() {
+                        argument(next, constants);
+                    }
+                    if let Some(end) = r.end.as_mut() {
+                        argument(end, constants);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The value of an operation on literals, or of `&&` and `||` when their first operand decides it.
+fn fold(op: &Operation) -> Option<Value> {
+    if op.arguments.len() == 2 {
+        match (&op.operator, literal(&op.arguments[0])) {
+            (Operator::And, Some(v @ Value::Boolean(false)))
+            | (Operator::Or, Some(v @ Value::Boolean(true))) => return Some(v.clone()),
+            _ => {}
+        }
+    }
+    if op.arguments.iter().all(|arg| literal(arg).is_some()) {
+        interpreter::fold(op)
+    } else {
+        None
+    }
+}
+
+fn literal(arg: &Argument) -> Option<&Value> {
+    match arg {
+        Argument::Value(v) => constant(v).then_some(v),
+        _ => None,
+    }
+}
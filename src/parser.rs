@@ -26,24 +26,37 @@ impl Parser {
         }
     }
 
+    /// The token after the next one.
+    pub fn peek_next(&self) -> &Token {
+        self.tokens
+            .get(self.current + 1)
+            .unwrap_or(&Token::Symbol(Symbol::EOF, 0))
+    }
+
+    /// The next token that is not a newline, without consuming anything.
+    pub fn peek_past_newlines(&self) -> &Token {
+        self.tokens[self.current..]
+            .iter()
+            .find(|t| !matches!(t, Token::Symbol(Symbol::EOL, _)))
+            .unwrap_or(&Token::Symbol(Symbol::EOF, 0))
+    }
+
+    /// The tokens up to the end of the current line, without consuming anything.
+    pub fn rest_of_line(&self) -> &[Token] {
+        let rest = self.tokens.get(self.current..).unwrap_or(&[]);
+        let end = rest
+            .iter()
+            .position(|t| matches!(t, Token::Symbol(Symbol::EOL | Symbol::EOF, _)))
+            .unwrap_or(rest.len());
+        &rest[..end]
+    }
+
     pub fn has_more(&self) -> bool {
         !matches!(self.peek(), Token::Symbol(Symbol::EOF, _))
     }
 
     pub fn location(&self) -> usize {
-        let current = self.tokens.get(self.current);
-        if let Some(token) = current {
-            match token {
-                Token::Identifier(_, line) => *line,
-                Token::Symbol(_, line) => *line,
-                Token::Operator(_, line) => *line,
-                Token::Type(_, line) => *line,
-                Token::Value(_, line) => *line,
-                Token::Conditional(_, line) => *line,
-            }
-        } else {
-            0
-        }
+        self.tokens.get(self.current).map_or(0, Token::line)
     }
 
     pub fn advance_while(&mut self, condition: fn(&Token) -> bool) -> Vec<Token> {
@@ -31,6 +31,10 @@ impl Scanner {
         self.input.get(self.index).cloned()
     }
 
+    pub fn peek_next(&self) -> Option<char> {
+        self.input.get(self.index + 1).cloned()
+    }
+
     pub fn take_lexeme(&mut self) -> String {
         self.input[self.start..self.index].iter().collect()
     }
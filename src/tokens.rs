@@ -1,7 +1,16 @@
-use std::fmt::{self, Display, Formatter};
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    rc::Rc,
+};
 
 use {
-    crate::grammar::{Element, List},
+    crate::{
+        errors::CompilerError,
+        grammar::{Argument, Element, List, Record, Variant},
+    },
+    num_bigint::{BigInt, Sign},
+    num_rational::BigRational,
     serde::{Deserialize, Serialize},
     Operator::{Add, And, Div, Eq, Gt, Gte, Lt, Lte, Mul, Neq, Not, Or, Sub},
 };
@@ -14,6 +23,33 @@ pub enum Token {
     Type(Type, usize),
     Value(Value, usize),
     Conditional(Conditional, usize),
+    Keyword(Keyword, usize),
+}
+
+impl Token {
+    pub fn line(&self) -> usize {
+        match self {
+            Token::Identifier(_, line)
+            | Token::Symbol(_, line)
+            | Token::Operator(_, line)
+            | Token::Type(_, line)
+            | Token::Value(_, line)
+            | Token::Conditional(_, line)
+            | Token::Keyword(_, line) => *line,
+        }
+    }
+
+    pub fn line_mut(&mut self) -> &mut usize {
+        match self {
+            Token::Identifier(_, line)
+            | Token::Symbol(_, line)
+            | Token::Operator(_, line)
+            | Token::Type(_, line)
+            | Token::Value(_, line)
+            | Token::Conditional(_, line)
+            | Token::Keyword(_, line) => line,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -30,12 +66,20 @@ pub enum Symbol {
     RP,
     LB,
     RB,
+    LC,
+    RC,
+    Comma,
+    Dot,
     Comment,
     Assign,
     Return,
     Pipe,
     Bar,
+    Wildcard,
     Range,
+    /// `<-` in a list comprehension.
+    Draw,
+    Backtick,
     EOL,
     EOF,
 }
@@ -44,10 +88,20 @@ pub enum Symbol {
 pub enum Type {
     Int,
     Frac,
+    /// An exact decimal number, without the rounding errors of a `Frac`.
+    Decimal,
     Str,
     Bool,
     Void,
     List(Box<Type>),
+    /// A type declared with `data`.
+    Named(Identifier),
+    /// A lowercase type variable such as `a`, standing for any type in a signature.
+    Var(Identifier),
+    /// `(Int, Str)`, with at least two elements.
+    Tuple(Vec<Type>),
+    /// `Maybe Int`, a value that may be missing.
+    Maybe(Box<Type>),
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -100,20 +154,49 @@ impl Operator {
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum Value {
     Integer(i64),
+    /// An integer too large for an `i64`, produced when arithmetic overflows.
+    BigInteger(BigInt),
     Fractional(f64),
+    /// Exact value of a `Decimal`, such as `0.1d`.
+    Decimal(BigRational),
     String(String),
     Boolean(bool),
     List(List),
+    /// A list whose elements are computed as they are needed, which only exists at runtime.
+    #[serde(skip)]
+    Lazy(Lazy),
+    Record(Record),
+    Variant(Variant),
+    Tuple(Vec<Value>),
+    /// `Some value` or `None`.
+    Maybe(Option<Box<Value>>),
 }
 
 impl Value {
+    /// The value as written in an argument, in parentheses if it is a constructor with values.
+    pub fn as_argument(&self) -> String {
+        match self {
+            Value::Variant(v) if !v.values.is_empty() => format!("({})", v),
+            Value::Maybe(Some(_)) => format!("({})", self),
+            v => v.to_string(),
+        }
+    }
+
     pub fn get_type(&self) -> Type {
         match self {
-            Value::Integer(_) => Type::Int,
+            Value::Integer(_) | Value::BigInteger(_) => Type::Int,
             Value::Fractional(_) => Type::Frac,
+            Value::Decimal(_) => Type::Decimal,
             Value::String(_) => Type::Str,
             Value::Boolean(_) => Type::Bool,
             Value::List(list) => Type::List(Box::new(list.list_type.clone())),
+            Value::Lazy(lazy) => Type::List(Box::new(lazy.element_type.clone())),
+            Value::Record(record) => Type::Named(record.name.clone()),
+            Value::Variant(variant) => Type::Named(variant.type_name.clone()),
+            Value::Tuple(values) => Type::Tuple(values.iter().map(Value::get_type).collect()),
+            Value::Maybe(Some(value)) => Type::Maybe(Box::new(value.get_type())),
+            // The type of a missing value is not known at runtime
+            Value::Maybe(None) => Type::Maybe(Box::new(Type::Var("a".into()))),
         }
     }
 }
@@ -125,6 +208,19 @@ pub enum Conditional {
     Else,
 }
 
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum Keyword {
+    Import,
+    As,
+    Data,
+    Case,
+    Of,
+    Try,
+    Catch,
+    Raise,
+    Type,
+}
+
 impl Display for Token {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(f, "{}", String::from(self.clone()))
@@ -140,6 +236,7 @@ impl From<Token> for String {
             Token::Type(t, _line) => t.into(),
             Token::Value(t, _line) => t.into(),
             Token::Conditional(t, _line) => t.into(),
+            Token::Keyword(t, _line) => t.into(),
         }
     }
 }
@@ -151,12 +248,19 @@ impl From<Symbol> for String {
             Symbol::RP => ")".to_string(),
             Symbol::LB => "[".to_string(),
             Symbol::RB => "]".to_string(),
+            Symbol::LC => "{".to_string(),
+            Symbol::RC => "}".to_string(),
+            Symbol::Comma => ",".to_string(),
+            Symbol::Dot => ".".to_string(),
             Symbol::Comment => "#".to_string(),
             Symbol::Assign => "=".to_string(),
             Symbol::Return => "->".to_string(),
             Symbol::Pipe => ">>".to_string(),
             Symbol::Bar => "|".to_string(),
+            Symbol::Wildcard => "_".to_string(),
+            Symbol::Backtick => "`".to_string(),
             Symbol::Range => "..".to_string(),
+            Symbol::Draw => "<-".to_string(),
             Symbol::EOL => "\n".to_string(),
             Symbol::EOF => "".to_string(),
         }
@@ -200,10 +304,101 @@ impl From<Type> for String {
         match t {
             Type::Int => "Int".to_string(),
             Type::Frac => "Frac".to_string(),
+            Type::Decimal => "Decimal".to_string(),
             Type::Str => "Str".to_string(),
             Type::Bool => "Bool".to_string(),
             Type::Void => "Void".to_string(),
             Type::List(t) => format!("[{}]", t),
+            Type::Named(name) => name,
+            Type::Var(name) => name,
+            Type::Tuple(types) => format!("({})", join(&types)),
+            Type::Maybe(t) => format!("Maybe {}", t),
+        }
+    }
+}
+
+impl Type {
+    /// Types that can be named directly in a signature.
+    pub fn primitives() -> [Type; 6] {
+        [
+            Type::Int,
+            Type::Frac,
+            Type::Decimal,
+            Type::Str,
+            Type::Bool,
+            Type::Void,
+        ]
+    }
+
+    /// Names of the declared types this type refers to, including as list or tuple elements.
+    pub fn named(&self) -> Vec<&Identifier> {
+        match self {
+            Type::Named(name) => vec![name],
+            Type::List(t) => t.named(),
+            Type::Tuple(types) => types.iter().flat_map(Type::named).collect(),
+            Type::Maybe(t) => t.named(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Name of a type variable this type mentions, including as a list or tuple element.
+    pub fn variable(&self) -> Option<&Identifier> {
+        match self {
+            Type::Var(name) => Some(name),
+            Type::List(t) => t.variable(),
+            Type::Tuple(types) => types.iter().find_map(Type::variable),
+            Type::Maybe(t) => t.variable(),
+            _ => None,
+        }
+    }
+
+    /// Matches this type against an observed one, binding its unbound type variables. Returns
+    /// false if the two cannot be made equal. Variables in the observed type, such as the
+    /// element type of `None`, match anything.
+    pub fn unify(&self, observed: &Type, bindings: &mut HashMap<Identifier, Type>) -> bool {
+        match (self, observed) {
+            (Type::Var(name), t) => match bindings.get(name) {
+                Some(bound) => bound.clone().unify(t, &mut HashMap::new()),
+                None => {
+                    bindings.insert(name.clone(), t.clone());
+                    true
+                }
+            },
+            (_, Type::Var(_)) => true,
+            (Type::List(expected), Type::List(observed)) => expected.unify(observed, bindings),
+            (Type::Maybe(expected), Type::Maybe(observed)) => expected.unify(observed, bindings),
+            (Type::Tuple(expected), Type::Tuple(observed)) => {
+                expected.len() == observed.len()
+                    && expected
+                        .iter()
+                        .zip(observed.iter())
+                        .all(|(e, o)| e.unify(o, bindings))
+            }
+            (expected, observed) => expected == observed,
+        }
+    }
+
+    /// The type with its bound type variables replaced.
+    /// Replaces the aliases in a type with the types they stand for.
+    pub fn expand(&self, aliases: &HashMap<Identifier, Type>) -> Type {
+        match self {
+            Type::Named(name) => aliases.get(name).cloned().unwrap_or_else(|| self.clone()),
+            Type::List(t) => Type::List(Box::new(t.expand(aliases))),
+            Type::Tuple(types) => Type::Tuple(types.iter().map(|t| t.expand(aliases)).collect()),
+            Type::Maybe(t) => Type::Maybe(Box::new(t.expand(aliases))),
+            t => t.clone(),
+        }
+    }
+
+    pub fn substitute(&self, bindings: &HashMap<Identifier, Type>) -> Type {
+        match self {
+            Type::Var(name) => bindings.get(name).cloned().unwrap_or_else(|| self.clone()),
+            Type::List(t) => Type::List(Box::new(t.substitute(bindings))),
+            Type::Tuple(types) => {
+                Type::Tuple(types.iter().map(|t| t.substitute(bindings)).collect())
+            }
+            Type::Maybe(t) => Type::Maybe(Box::new(t.substitute(bindings))),
+            t => t.clone(),
         }
     }
 }
@@ -214,10 +409,41 @@ impl Display for Type {
     }
 }
 
+/// An integer, as a `BigInteger` only if it does not fit in an `i64`.
+impl From<BigInt> for Value {
+    fn from(n: BigInt) -> Self {
+        match i64::try_from(&n) {
+            Ok(n) => Value::Integer(n),
+            Err(_) => Value::BigInteger(n),
+        }
+    }
+}
+
+/// Decimal notation of an exact number, rounded to 28 places if its digits never end.
+fn decimal_string(n: &BigRational) -> String {
+    const PLACES: usize = 28;
+    let scaled = (n * BigRational::from_integer(BigInt::from(10).pow(PLACES as u32)))
+        .round()
+        .to_integer();
+    let digits = format!("{:0>width$}", scaled.magnitude(), width = PLACES + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - PLACES);
+    let fraction = match fraction.trim_end_matches('0') {
+        "" => "0",
+        fraction => fraction,
+    };
+    let sign = if scaled.sign() == Sign::Minus {
+        "-"
+    } else {
+        ""
+    };
+    format!("{}{}.{}", sign, whole, fraction)
+}
+
 impl From<Value> for String {
     fn from(value: Value) -> Self {
         match value {
             Value::Integer(t) => t.to_string(),
+            Value::BigInteger(t) => t.to_string(),
             Value::Fractional(t) => {
                 let s = t.to_string();
                 if !s.contains('.') {
@@ -226,11 +452,65 @@ impl From<Value> for String {
                     s
                 }
             }
+            Value::Decimal(t) => decimal_string(&t),
             Value::String(t) => t.to_string(),
             Value::Boolean(t) => t.to_string(),
             Value::List(t) => t.to_string(),
+            // Lazy lists may be infinite, and are made into lists before they are displayed
+            Value::Lazy(_) => "[...]".to_string(),
+            Value::Record(t) => t.to_string(),
+            Value::Variant(t) => t.to_string(),
+            Value::Tuple(t) => format!("({})", join(&t)),
+            Value::Maybe(Some(t)) => format!("Some {}", t.as_argument()),
+            Value::Maybe(None) => "None".to_string(),
+        }
+    }
+}
+
+/// Elements of a lazy list, computed one at a time.
+pub type Elements = Box<dyn Iterator<Item = Result<Value, CompilerError>>>;
+
+/// A list such as the range `Int [1 ..]`, which may be infinite.
+#[derive(Clone)]
+pub struct Lazy {
+    pub element_type: Type,
+    /// Starts a new pass over the elements.
+    pub elements: Rc<dyn Fn() -> Elements>,
+}
+
+impl Lazy {
+    pub fn new(element_type: Type, elements: impl Fn() -> Elements + 'static) -> Self {
+        Self {
+            element_type,
+            elements: Rc::new(elements),
         }
     }
+
+    pub fn iter(&self) -> Elements {
+        (self.elements)()
+    }
+}
+
+impl fmt::Debug for Lazy {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Lazy({})", self.element_type)
+    }
+}
+
+/// Lazy lists are only equal to themselves, as comparing elements may never finish.
+impl PartialEq for Lazy {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.elements, &other.elements)
+    }
+}
+
+/// Displays values separated by commas.
+fn join<T: Display>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(|t| t.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 impl Display for List {
@@ -247,11 +527,47 @@ impl Display for List {
     }
 }
 
+impl Display for Record {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|(name, value)| format!("{} {}", name, value))
+            .collect();
+        write!(f, "{} {{ {} }}", self.name, fields.join(", "))
+    }
+}
+
+impl Display for Variant {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        for value in self.values.iter() {
+            write!(f, " {}", value.as_argument())?;
+        }
+        Ok(())
+    }
+}
+
 impl Display for Element {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
             Element::Value(t) => write!(f, "{}", t),
             Element::Identifier(t) => write!(f, "{}", t),
+            Element::Range(r) => {
+                let bound = |bound: &Argument| match bound {
+                    Argument::Value(v) => v.as_argument(),
+                    Argument::Identifier(l_id) => l_id.id.clone(),
+                    Argument::ParenExpression(_) => "(...)".to_string(),
+                };
+                write!(f, "{}", bound(&r.start))?;
+                if let Some(next) = &r.next {
+                    write!(f, ", {}", bound(next))?;
+                }
+                match &r.end {
+                    Some(end) => write!(f, " .. {}", bound(end)),
+                    None => write!(f, " .."),
+                }
+            }
         }
     }
 }
@@ -277,3 +593,25 @@ impl Display for Value {
         write!(f, "{}", String::from(self.clone()))
     }
 }
+
+impl From<Keyword> for String {
+    fn from(keyword: Keyword) -> Self {
+        match keyword {
+            Keyword::Import => "import".to_string(),
+            Keyword::As => "as".to_string(),
+            Keyword::Data => "data".to_string(),
+            Keyword::Case => "case".to_string(),
+            Keyword::Of => "of".to_string(),
+            Keyword::Try => "try".to_string(),
+            Keyword::Catch => "catch".to_string(),
+            Keyword::Raise => "raise".to_string(),
+            Keyword::Type => "type".to_string(),
+        }
+    }
+}
+
+impl Display for Keyword {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", String::from(self.clone()))
+    }
+}
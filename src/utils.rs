@@ -29,6 +29,49 @@ where
     head
 }
 
+/// Edit distance between two strings, counting insertions, deletions, substitutions and
+/// transpositions of adjacent characters as one edit each.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+/// Closest candidate to `name`, if any is similar enough to be a plausible typo.
+pub fn closest<I>(name: &str, candidates: I) -> Option<String>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let length = name.chars().count();
+    // Replacing every character of a short name is not a typo
+    let threshold = std::cmp::max(1, length / 3).min(length.saturating_sub(1));
+    candidates
+        .into_iter()
+        .map(|c| (edit_distance(name, c.as_ref()), c.as_ref().to_string()))
+        .filter(|(distance, c)| *distance <= threshold && c != name)
+        .min()
+        .map(|(_, c)| c)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -49,6 +92,24 @@ mod tests {
         assert_eq!(tail, vec![4, 5]);
     }
 
+    #[test]
+    fn edit_distance_ok() {
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("Itn", "Int"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn closest_ok() {
+        let candidates = vec!["fibonacci", "fizzbuzz", "main"];
+        assert_eq!(
+            closest("fibonaci", &candidates),
+            Some("fibonacci".to_string())
+        );
+        assert_eq!(closest("mian", &candidates), Some("main".to_string()));
+        assert_eq!(closest("foo", &candidates), None);
+    }
+
     #[test]
     fn consume_while_ok() {
         let mut elems = vec![1, 2, 3, 4, 5];
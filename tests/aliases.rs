@@ -0,0 +1,46 @@
+use lemma::{
+    checker,
+    errors::{CompilerError, ParserError},
+    tokens::Value,
+};
+
+mod common;
+
+use common::{evaluate, parse};
+
+#[test]
+fn nested_lists() {
+    let source = "-> [[Int]]\nmain = [Int] [[1 2] row []]\n\n-> [Int]\nrow = Int [3 .. 5]";
+    assert_eq!(checker::check(&parse(source).unwrap()), Ok(()));
+    assert_eq!(
+        evaluate(source).unwrap().unwrap().to_string(),
+        "[[1, 2], [3, 4, 5], []]"
+    );
+
+    let source = "-> [[[Str]]]\nmain = [[Str]] [[[\"a\"]] [[] [\"b\" \"c\"]]]";
+    assert_eq!(
+        evaluate(source).unwrap().unwrap().to_string(),
+        "[[[a]], [[], [b, c]]]"
+    );
+}
+
+#[test]
+fn aliases_stand_for_their_types() {
+    let source = "type Matrix = [Row]\ntype Row = [Int]\n\n-> Int\nmain = size ([Int] [[1 2]])\n\nMatrix -> Int\nsize m = 1";
+    let program = parse(source).unwrap();
+    assert_eq!(checker::check(&program), Ok(()));
+    assert_eq!(
+        checker::signatures(&program)["size"].to_string(),
+        "[[Int]] -> Int"
+    );
+    assert_eq!(evaluate(source).unwrap(), Some(Value::Integer(1)));
+}
+
+#[test]
+fn recursive_alias() {
+    let source = "type A = [B]\ntype B = (Int, A)\n\n-> Int\nmain = 1";
+    assert_eq!(
+        parse(source).unwrap_err(),
+        CompilerError::Parser(ParserError::RecursiveAlias("A".into()), 1)
+    );
+}
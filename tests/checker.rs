@@ -0,0 +1,128 @@
+use {
+    lemma::{
+        ast, checker,
+        errors::{CompilerError, InterpreterError},
+        interpreter, lexer,
+        modules::{self, Sources},
+        tokens::Type,
+    },
+    std::{fs, path::Path},
+};
+
+fn check(source: &str) -> Result<(), CompilerError> {
+    let tokens = lexer::tokens(source).unwrap();
+    let program = ast::build(tokens).unwrap();
+    checker::check(&program)
+}
+
+#[test]
+fn well_typed_program_accepted() {
+    let source = "-> Void
+                  main = Int [ 1 .. 10 ] >> square
+
+                  Int -> Int
+                  square n = if > n 0 then * n n else 0";
+    assert!(check(source).is_ok());
+}
+
+#[test]
+fn wrong_argument_type_detected() {
+    let source = "-> Void
+                  main = square \"a\"
+
+                  Int -> Int
+                  square n = * n n";
+    let err = check(source).unwrap_err();
+    assert_eq!(
+        err,
+        CompilerError::Interpreter(
+            InterpreterError::UnexpectedType {
+                expected: Type::Int,
+                found: Type::Str
+            },
+            2
+        )
+    );
+}
+
+#[test]
+fn wrong_return_type_detected() {
+    let source = "Int -> Bool ; main n = + n 1";
+    assert!(matches!(
+        check(source),
+        Err(CompilerError::Interpreter(_, _))
+    ));
+}
+
+#[test]
+fn wrong_number_of_arguments_detected() {
+    let source = "-> Void
+                  main = add 1
+
+                  Int Int -> Int
+                  add a b = + a b";
+    let err = check(source).unwrap_err();
+    assert!(matches!(
+        err,
+        CompilerError::Interpreter(
+            InterpreterError::WrongFunctionArity {
+                expected: 2,
+                found: 1,
+                ..
+            },
+            2
+        )
+    ));
+}
+
+#[test]
+fn all_errors_collected() {
+    let source = "-> Void
+                  main = undefined-function 1
+
+                  Int -> Int
+                  foo a = && a true";
+    let tokens = lexer::tokens(source).unwrap();
+    let program = ast::build(tokens).unwrap();
+    assert_eq!(checker::diagnostics(&program).len(), 2);
+}
+
+#[test]
+fn examples_type_checked() {
+    let directories = [Path::new("examples"), Path::new("examples/modules")];
+    for entry in directories.iter().flat_map(|d| fs::read_dir(d).unwrap()) {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|e| e != "lm") {
+            continue;
+        }
+        let source = fs::read_to_string(&path).unwrap();
+        let program = modules::load(&path, &source, &mut Sources::default()).unwrap();
+        assert_eq!(checker::check(&program), Ok(()), "{}", path.display());
+    }
+}
+
+#[test]
+fn void_branches_may_differ() {
+    let source = "Int -> Void\nsign n = if > n 0 then \"positive\" else n";
+    assert_eq!(check(source), Ok(()));
+
+    // The condition is still checked
+    let source = "Int -> Void\nsign n = if n then \"positive\" else n";
+    assert_eq!(
+        check(source),
+        Err(CompilerError::Interpreter(
+            InterpreterError::UnexpectedType {
+                expected: Type::Bool,
+                found: Type::Int
+            },
+            2
+        ))
+    );
+
+    let path = Path::new("examples/fizzbuzz.lm");
+    let source = fs::read_to_string(path).unwrap();
+    let program = modules::load(path, &source, &mut Sources::default()).unwrap();
+    let output = interpreter::evaluate(program).unwrap().unwrap().to_string();
+    assert!(output
+        .starts_with("[1, 2, fizz, 4, buzz, fizz, 7, 8, fizz, buzz, 11, fizz, 13, 14, fizzbuzz,"));
+}
@@ -0,0 +1,102 @@
+use {
+    lemma::{
+        args::{Args, Command},
+        codegen,
+    },
+    std::{env, fs, path::PathBuf, process},
+};
+
+mod common;
+
+use common::{output, parse};
+
+/// Compiles the program with the system C compiler and returns what it prints and its exit code.
+fn run(name: &str, source: &str) -> (String, Option<i32>) {
+    let c = codegen::generate(&parse(source).unwrap()).unwrap();
+    let dir = env::temp_dir().join(format!("lemma-codegen-{}-{}", process::id(), name));
+    fs::create_dir_all(&dir).unwrap();
+    let (c_file, binary): (PathBuf, PathBuf) = (dir.join("main.c"), dir.join("main"));
+    fs::write(&c_file, c).unwrap();
+
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let status = process::Command::new(compiler)
+        .args(["-std=c99", "-Wall", "-Wextra", "-pedantic", "-Werror", "-o"])
+        .arg(&binary)
+        .arg(&c_file)
+        .arg("-lm")
+        .status()
+        .unwrap();
+    assert!(status.success(), "could not compile {}", c_file.display());
+
+    let output = process::Command::new(&binary).output().unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    (stdout.trim_end().to_string(), output.status.code())
+}
+
+#[test]
+fn output_matches_interpreter() {
+    let sources = [
+        (
+            "fibonacci",
+            "-> Int\nmain = fibonacci 20\n\nInt -> Int\nfibonacci n = if < n 2\n  then n\n  \
+             else + (fibonacci (- n 1)) (fibonacci (- n 2))",
+        ),
+        (
+            "fractions",
+            "-> [Frac]\nmain = Int [1 .. 5] >> third\n\nInt -> Frac\nthird n = / n 3.0",
+        ),
+        (
+            "strings",
+            "-> [Str]\nmain = Int [10, 7 .. 0] >> sign\n\n\
+             Int -> Str\nsign n = if && (> n 0) (!= n 7) then \"positive?\" else \"not so\"",
+        ),
+        (
+            "lists",
+            "-> [[Bool]]\nmain = [Bool] [[true false] flags []]\n\n-> [Bool]\nflags = Bool [true both]\n\n\
+             -> Bool\nboth = || false (! true)",
+        ),
+    ];
+    for (name, source) in sources {
+        assert_eq!(
+            run(name, source),
+            (output(source).unwrap(), Some(0)),
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn runtime_errors_exit() {
+    let (stdout, code) = run("errors", "-> Int\nmain = + 1 (/ 1 (- 2 2))");
+    assert_eq!((stdout.as_str(), code), ("", Some(4)));
+
+    let (_, code) = run("overflow", "-> Int\nmain = * 9223372036854775807 2");
+    assert_eq!(code, Some(4));
+}
+
+#[test]
+fn unsupported_constructs_rejected() {
+    let program = parse("-> Maybe Int\nmain = parse-int \"1\"").unwrap();
+    let err = codegen::generate(&program).unwrap_err();
+    assert_eq!(err.code(), "C0001");
+    assert_eq!(err.exit_code(), 6);
+
+    let program = parse("-> (Int, Int)\nmain = (1, 2)").unwrap();
+    let err = codegen::generate(&program).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Codegen error: cannot compile tuples to C (line 2)"
+    );
+}
+
+#[test]
+fn build_parsed() {
+    let mut args = ["lemma", "build", "main.lm", "-o", "out.c"]
+        .iter()
+        .map(|a| a.to_string());
+    let args = Args::build(&mut args).unwrap();
+    assert_eq!(args.command, Command::Build);
+    assert_eq!(args.output, Some("out.c".to_string()));
+}
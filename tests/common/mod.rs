@@ -0,0 +1,34 @@
+//! Helpers shared by the integration tests, each of which uses only some of them.
+#![allow(dead_code)]
+
+use lemma::{
+    ast,
+    errors::CompilerError,
+    grammar::Program,
+    interpreter::{self, Options},
+    lexer,
+    tokens::Value,
+};
+
+pub fn parse(source: &str) -> Result<Program, CompilerError> {
+    let tokens = lexer::tokens(source)?;
+    ast::build(tokens)
+}
+
+pub fn evaluate(source: &str) -> Result<Option<Value>, CompilerError> {
+    interpreter::evaluate(parse(source)?)
+}
+
+pub fn evaluate_with(source: &str, options: Options) -> Result<Option<Value>, CompilerError> {
+    interpreter::evaluate_with(parse(source)?, options)
+}
+
+/// The value of a program as it is printed.
+pub fn output(source: &str) -> Result<String, CompilerError> {
+    Ok(evaluate(source)?.unwrap().to_string())
+}
+
+/// A program whose `main` is an expression.
+pub fn program(expression: &str) -> String {
+    format!("-> Void\nmain = {}", expression)
+}
@@ -0,0 +1,45 @@
+use lemma::{checker, errors, tokens::Type};
+
+mod common;
+
+use common::{output, parse};
+
+#[test]
+fn filtered_and_transformed() {
+    let source = "-> [Int]\nmain = [ (* x x) | x <- [1 .. 10], == (rem x 2) 0 ]\n\n\
+                  Int Int -> Int\nrem a b = - a (* b (/ a b))";
+    assert_eq!(output(source), Ok("[4, 16, 36, 64, 100]".to_string()));
+
+    let source = "-> [Int]\nmain = [ n | n <- numbers, != n 2 ]\n\n-> [Int]\nnumbers = Int [1 2 3]";
+    assert_eq!(output(source), Ok("[1, 3]".to_string()));
+}
+
+#[test]
+fn generators_nested_in_order() {
+    let source = "-> [(Int, Int)]\nmain = pairs (Int [1 2 3])\n\n\
+                  [Int] -> [(Int, Int)]\npairs xs = [ (x, y) | x <- xs, y <- xs, < x y ]";
+    assert_eq!(output(source), Ok("[(1, 2), (1, 3), (2, 3)]".to_string()));
+
+    // Generators may be infinite, as comprehensions are lazy
+    let source = "-> [(Int, Str)]\nmain = take 3 [ (x, s) | x <- Int [1 ..], > x 1, s <- Str [\"a\" \"b\"] ]";
+    assert_eq!(output(source), Ok("[(2, a), (2, b), (3, a)]".to_string()));
+}
+
+#[test]
+fn comprehensions_type_checked() {
+    let source = "halves xs = [ / x 2.0 | x <- xs ]";
+    let signatures = checker::signatures(&parse(source).unwrap());
+    assert_eq!(signatures["halves"].to_string(), "[Frac] -> [Frac]");
+
+    let source = "-> [Int]\nmain = [ x | x <- Int [1 2], + x 1 ]";
+    assert_eq!(
+        checker::check(&parse(source).unwrap()),
+        Err(errors::unexpected_type(&Type::Bool, &Type::Int, 2))
+    );
+
+    let source = "-> [Int]\nmain = [ x | x <- 3 ]";
+    assert_eq!(
+        checker::check(&parse(source).unwrap()).unwrap_err().code(),
+        "E0001"
+    );
+}
@@ -0,0 +1,65 @@
+use lemma::{
+    checker,
+    errors::{self, CompilerError, InterpreterError},
+    tokens::Type,
+};
+
+mod common;
+
+use common::{output, parse, program};
+
+fn evaluate(expression: &str) -> Result<String, CompilerError> {
+    output(&program(expression))
+}
+
+#[test]
+fn decimal_arithmetic_is_exact() {
+    assert_eq!(evaluate("+ 0.1d 0.2d"), Ok("0.3".to_string()));
+    assert_eq!(evaluate("== (+ 0.1d 0.2d) 0.3d"), Ok("true".to_string()));
+    assert_eq!(evaluate("* (/ 1d 3d) 3d"), Ok("1.0".to_string()));
+    assert_eq!(
+        evaluate("/ 1d 3d"),
+        Ok("0.3333333333333333333333333333".to_string())
+    );
+    assert_eq!(evaluate("- 0 12.50d"), Ok("-12.5".to_string()));
+    assert_eq!(
+        evaluate("/ 1d 0d"),
+        Err(CompilerError::Interpreter(
+            InterpreterError::DivisionByZero,
+            2
+        ))
+    );
+}
+
+#[test]
+fn decimals_promoted() {
+    assert_eq!(evaluate("+ 1 0.5d"), Ok("1.5".to_string()));
+    assert_eq!(evaluate("+ 0.5d 0.25"), Ok("0.75".to_string()));
+    assert_eq!(evaluate("< 1 1.5d"), Ok("true".to_string()));
+    assert_eq!(evaluate("== 2 2.0d"), Ok("true".to_string()));
+    assert_eq!(evaluate("/ (to-decimal 7) 2"), Ok("3.5".to_string()));
+
+    // A `Frac` is not converted, as its value is not exact, and a `Decimal` already is one
+    assert_eq!(
+        evaluate("to-decimal 1.5"),
+        Err(errors::unexpected_type(&Type::Int, &Type::Frac, 2))
+    );
+    assert_eq!(
+        evaluate("to-decimal 1.5d"),
+        Err(errors::unexpected_type(&Type::Int, &Type::Decimal, 2))
+    );
+}
+
+#[test]
+fn decimals_type_checked() {
+    let source = "price n = * n 1.25d\n\nhalf x = / x 2d";
+    let signatures = checker::signatures(&parse(source).unwrap());
+    assert_eq!(signatures["price"].to_string(), "Decimal -> Decimal");
+    assert_eq!(signatures["half"].to_string(), "Decimal -> Decimal");
+
+    let source = "-> Int\nmain = + 1 0.5d";
+    assert_eq!(
+        checker::check(&parse(source).unwrap()),
+        Err(errors::unexpected_type(&Type::Int, &Type::Decimal, 2))
+    );
+}
@@ -0,0 +1,70 @@
+use {
+    lemma::{
+        args::Args,
+        ast,
+        diagnostics::{self, Diagnostic, DiagnosticsFormat, Span},
+        errors::{CompilerError, InterpreterError, LexerError},
+        lexer,
+    },
+    serde_json::Value,
+};
+
+fn args(args: &[&str]) -> Args {
+    let mut args = args.iter().map(|a| a.to_string());
+    Args::build(&mut args).unwrap()
+}
+
+#[test]
+fn diagnostics_format_parsed() {
+    let args = args(&["lemma", "--diagnostics-format", "sarif", "main.lm"]);
+    assert_eq!(args.file_path, "main.lm");
+    assert_eq!(args.diagnostics_format, DiagnosticsFormat::Sarif);
+
+    let args = self::args(&["lemma", "main.lm", "--diagnostics-format=json"]);
+    assert_eq!(args.diagnostics_format, DiagnosticsFormat::Json);
+}
+
+#[test]
+fn invalid_diagnostics_format_rejected() {
+    let mut args = ["lemma", "--diagnostics-format", "xml", "main.lm"]
+        .iter()
+        .map(|a| a.to_string());
+    assert!(Args::build(&mut args).is_err());
+}
+
+#[test]
+fn diagnostic_located() {
+    let source = "Int -> Int\n  foo a = & a";
+    let err = lexer::tokens(source).and_then(ast::build).unwrap_err();
+    let diagnostic = Diagnostic::new(&err, "foo.lm", source);
+    assert_eq!(diagnostic.phase, "lexer");
+    assert_eq!(diagnostic.file, "foo.lm");
+    assert_eq!(diagnostic.line, 2);
+    assert_eq!(diagnostic.span, Some(Span { start: 3, end: 14 }));
+    assert_eq!(diagnostic.message, "invalid token `&`");
+}
+
+#[test]
+fn json_rendered() {
+    let err = CompilerError::Lexer(LexerError::InvalidToken("&".into()), 1);
+    let diagnostic = Diagnostic::new(&err, "main.lm", "main = & a b");
+    let json = diagnostics::render(DiagnosticsFormat::Json, &[diagnostic]);
+    let json: Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(json["diagnostics"][0]["phase"], "lexer");
+    assert_eq!(json["diagnostics"][0]["code"], err.code());
+    assert_eq!(json["diagnostics"][0]["span"]["end"], 13);
+}
+
+#[test]
+fn sarif_rendered() {
+    let err = CompilerError::Interpreter(InterpreterError::DivisionByZero, 2);
+    let diagnostic = Diagnostic::new(&err, "main.lm", "-> Int\nmain = / 1 0");
+    let sarif = diagnostics::render(DiagnosticsFormat::Sarif, &[diagnostic]);
+    let sarif: Value = serde_json::from_str(&sarif).unwrap();
+    let result = &sarif["runs"][0]["results"][0];
+    assert_eq!(sarif["version"], "2.1.0");
+    assert_eq!(result["message"]["text"], "division by zero");
+    let location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "main.lm");
+    assert_eq!(location["region"]["startLine"], 2);
+}
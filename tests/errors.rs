@@ -0,0 +1,110 @@
+use lemma::{
+    ast,
+    errors::{self, CompilerError, InterpreterError, LexerError, ModuleError, ParserError},
+    interpreter, lexer,
+    tokens::{Type, Value},
+};
+
+fn evaluate(source: &str) -> Result<(), CompilerError> {
+    let tokens = lexer::tokens(source)?;
+    let program = ast::build(tokens)?;
+    interpreter::evaluate(program).map(|_| ())
+}
+
+#[test]
+fn lexer_error_typed() {
+    let err = evaluate("-> Int ; main = 1 @ 2").unwrap_err();
+    assert_eq!(
+        err,
+        CompilerError::Lexer(LexerError::InvalidCharacter('@'), 1)
+    );
+    assert_eq!(err.code(), "L0002");
+
+    let example = errors::explain("L0003").unwrap().example;
+    assert_eq!(evaluate(example).unwrap_err().code(), "L0003");
+}
+
+#[test]
+fn parser_error_typed() {
+    let err = evaluate("Int -> Int ; foo a a = + a a").unwrap_err();
+    assert_eq!(
+        err,
+        CompilerError::Parser(ParserError::DuplicateParameter("a".into()), 1)
+    );
+    assert_eq!(err.code(), "P0004");
+}
+
+#[test]
+fn interpreter_error_typed() {
+    let err = evaluate("-> Int ; main = / 1 0").unwrap_err();
+    assert_eq!(
+        err,
+        CompilerError::Interpreter(InterpreterError::DivisionByZero, 1)
+    );
+    assert_eq!(err.code(), "E0010");
+}
+
+#[test]
+fn undefined_variable_suggested() {
+    let err = evaluate("-> Int ; main = sqaure 2 ; Int -> Int ; square n = * n n").unwrap_err();
+    assert_eq!(
+        err,
+        errors::undefined_variable(&"sqaure".into(), Some("square".into()), 1)
+    );
+    assert!(err.message().ends_with("did you mean `square`?"));
+
+    let err = evaluate("-> Int ; main = xyz").unwrap_err();
+    assert_eq!(err, errors::undefined_variable(&"xyz".into(), None, 1));
+}
+
+#[test]
+fn unknown_type_suggested() {
+    let err = evaluate("[Itn] -> Int ; foo a = 1").unwrap_err();
+    assert_eq!(
+        err,
+        errors::unknown_type(&"Itn".into(), Some("Int".into()), 1)
+    );
+    assert_eq!(err.code(), "P0006");
+}
+
+#[test]
+fn explanations_found() {
+    let explanation = errors::explain("p0003").unwrap();
+    assert_eq!(explanation.code, "P0003");
+    assert!(explanation.to_string().contains("Example:"));
+    assert!(errors::explain("X9999").is_none());
+}
+
+#[test]
+fn every_code_explained() {
+    let errors = vec![
+        CompilerError::Lexer(LexerError::InvalidToken("&".into()), 0),
+        CompilerError::Lexer(LexerError::InvalidCharacter('@'), 0),
+        CompilerError::Lexer(LexerError::InvalidNumeric("1".into()), 0),
+        CompilerError::Lexer(LexerError::UnterminatedString, 0),
+        CompilerError::Parser(ParserError::EmptyProgram, 0),
+        CompilerError::Parser(ParserError::UnrangeableType(Type::Bool), 0),
+        CompilerError::Parser(ParserError::DuplicateParameter("a".into()), 0),
+        errors::unknown_type(&"Itn".into(), None, 0),
+        CompilerError::Parser(ParserError::DuplicateField("x".into()), 0),
+        errors::missing_field(&"Point".into(), &"x".into(), 0),
+        errors::unknown_field(&"Point".into(), &"z".into(), None, 0),
+        CompilerError::Parser(ParserError::DuplicateConstructor("A".into()), 0),
+        CompilerError::Parser(ParserError::RecursiveAlias("Tree".into()), 0),
+        errors::non_exhaustive(&"Shape".into(), Vec::new(), 0),
+        errors::unknown_constructor(&"Square".into(), None, 0),
+        errors::raised("not implemented", 0),
+        CompilerError::Module(ModuleError::ImportNotFound("a.lm".into()), 0),
+        CompilerError::Module(ModuleError::ImportCycle(Vec::new()), 0),
+        CompilerError::Interpreter(InterpreterError::MainNotFound, 0),
+        CompilerError::Interpreter(InterpreterError::DivisionByZero, 0),
+        CompilerError::Interpreter(InterpreterError::IntegerOverflow, 0),
+        errors::invalid_range("step of zero", 0),
+        errors::no_integer_value(&Value::Fractional(f64::NAN), 0),
+        CompilerError::Interpreter(InterpreterError::MissingValue("pipe".into()), 0),
+        errors::unsupported("tuples", 0),
+    ];
+    for err in errors {
+        assert!(errors::explain(err.code()).is_some(), "{}", err.code());
+    }
+}
@@ -0,0 +1,81 @@
+use lemma::{
+    args::Args,
+    errors::{CompilerError, InterpreterError},
+    interpreter::{Evaluation, Options},
+};
+
+mod common;
+
+use common::evaluate_with;
+
+fn evaluate(source: &str, evaluation: Evaluation) -> Result<String, CompilerError> {
+    let options = Options {
+        evaluation,
+        ..Options::default()
+    };
+    Ok(evaluate_with(source, options)?.unwrap().to_string())
+}
+
+#[test]
+fn logic_short_circuits() {
+    let source = "-> Bool\nmain = && (!= d 0) (== (/ 4 d) 2)\n\n-> Int\nd = 0";
+    assert_eq!(
+        evaluate(source, Evaluation::Strict),
+        Ok("false".to_string())
+    );
+
+    let source = "-> Bool\nmain = || (== d 0) (== (/ 4 d) 2)\n\n-> Int\nd = 0";
+    assert_eq!(evaluate(source, Evaluation::Strict), Ok("true".to_string()));
+
+    // The second operand is still evaluated when it decides the result
+    let source = "-> Bool\nmain = && (== d 0) (== (/ 4 d) 2)\n\n-> Int\nd = 0";
+    assert_eq!(
+        evaluate(source, Evaluation::Strict),
+        Err(CompilerError::Interpreter(
+            InterpreterError::DivisionByZero,
+            2
+        ))
+    );
+}
+
+#[test]
+fn unused_arguments_skipped() {
+    let source = "-> Int\nmain = first 1 (/ 1 0)\n\nInt Int -> Int\nfirst a b = a";
+    assert_eq!(evaluate(source, Evaluation::Lazy), Ok("1".to_string()));
+    assert_eq!(
+        evaluate(source, Evaluation::Strict),
+        Err(CompilerError::Interpreter(
+            InterpreterError::DivisionByZero,
+            2
+        ))
+    );
+
+    // Arguments are evaluated in the scope of the call, not of the function
+    let source = "-> Int\nmain = outer 2\n\nInt -> Int\nouter n = inner (* n 10) 1\n\n\
+                  Int Int -> Int\ninner x n = + x n";
+    assert_eq!(evaluate(source, Evaluation::Lazy), Ok("21".to_string()));
+}
+
+#[test]
+fn arguments_memoised() {
+    // Without memoisation, each level would evaluate its argument twice, 2^30 times in all
+    let nested = (0..30).fold("1".to_string(), |acc, _| format!("(twice {})", acc));
+    let source = format!("-> Int\nmain = {}\n\nInt -> Int\ntwice x = + x x", nested);
+    assert_eq!(
+        evaluate(&source, Evaluation::Lazy),
+        Ok((1i64 << 30).to_string())
+    );
+}
+
+#[test]
+fn evaluation_parsed() {
+    let mut args = ["lemma", "--evaluation=lazy", "main.lm"]
+        .iter()
+        .map(|a| a.to_string());
+    assert_eq!(Args::build(&mut args).unwrap().evaluation, Evaluation::Lazy);
+
+    let mut args = ["lemma", "--evaluation", "eager", "main.lm"]
+        .iter()
+        .map(|a| a.to_string());
+    assert!(Args::build(&mut args).is_err());
+}
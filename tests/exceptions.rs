@@ -0,0 +1,56 @@
+use lemma::{
+    checker, errors,
+    tokens::{Type, Value},
+};
+
+mod common;
+
+use common::{evaluate, parse};
+
+#[test]
+fn runtime_errors_are_caught() {
+    let source = "-> Str\nmain = try / 10 0 catch (message, line) -> message";
+    assert_eq!(
+        evaluate(source).unwrap(),
+        Some(Value::String("division by zero".to_string()))
+    );
+
+    let source = "-> Int\nmain = try / 10 0 catch (message, line) -> line";
+    assert_eq!(evaluate(source).unwrap(), Some(Value::Integer(2)));
+
+    let source = "-> Int\nmain = try / 10 5 catch _ -> 0";
+    assert_eq!(evaluate(source).unwrap(), Some(Value::Integer(2)));
+}
+
+#[test]
+fn raise_stops_the_program() {
+    let source = "-> Int\nmain = positive (- 0 1)\n\nInt -> Int\npositive n = if < n 0\n  then raise \"negative number\"\n  else n";
+    assert_eq!(
+        evaluate(source).unwrap_err(),
+        errors::raised("negative number", 6)
+    );
+
+    let source = "-> Str\nmain = try raise \"oops\"\n  catch (message, line) -> message";
+    assert_eq!(
+        evaluate(source).unwrap(),
+        Some(Value::String("oops".to_string()))
+    );
+}
+
+#[test]
+fn handlers_are_type_checked() {
+    let source = "-> Int\nmain = try / 10 0 catch (message, line) -> line";
+    assert_eq!(checker::check(&parse(source).unwrap()), Ok(()));
+
+    let source = "-> Int\nmain = try / 10 0 catch (message, line) -> message";
+    assert_eq!(
+        checker::check(&parse(source).unwrap()),
+        Err(errors::unexpected_type(&Type::Int, &Type::Str, 2))
+    );
+
+    let source = "fail n = raise \"never\"";
+    assert_eq!(
+        checker::signatures(&parse(source).unwrap())["fail"].to_string(),
+        "a -> b"
+    );
+}
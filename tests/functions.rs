@@ -1,4 +1,4 @@
-use lemma::{ast, interpreter, lexer, log::exit, tokens::Value::Integer};
+use lemma::{ast, interpreter, lexer, tokens::Value::Integer};
 
 #[test]
 fn remainder_function() {
@@ -8,8 +8,8 @@ fn remainder_function() {
                         Int Int -> Int
                         rem a b = - a (* b (/ a b))";
 
-    let tokens = lexer::tokens(&source).unwrap_or_else(|err| exit(err));
-    let program = ast::build(tokens).unwrap_or_else(|err| exit(err));
+    let tokens = lexer::tokens(source).unwrap_or_else(|err| panic!("{}", err));
+    let program = ast::build(tokens).unwrap_or_else(|err| panic!("{}", err));
 
     let output = interpreter::evaluate(program).unwrap().unwrap();
 
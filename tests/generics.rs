@@ -0,0 +1,68 @@
+use lemma::{
+    checker,
+    errors::{self, CompilerError},
+    tokens::Type,
+};
+
+mod common;
+
+use common::{evaluate, parse};
+
+const FIRST: &str = "\n\n[a] a -> a\nfirst xs y = y";
+const SINGLETON: &str = "\n\na -> [a]\nsingleton x = a [x]";
+
+#[test]
+fn type_variables_unified() {
+    let source = format!("-> Str\nmain = first (Str [\"a\"]) \"b\"{}", FIRST);
+    assert_eq!(checker::check(&parse(&source).unwrap()), Ok(()));
+
+    let source = format!("-> Int\nmain = first (Str [\"a\"]) \"b\"{}", FIRST);
+    assert_eq!(
+        checker::check(&parse(&source).unwrap()),
+        Err(errors::unexpected_type(&Type::Int, &Type::Str, 2))
+    );
+
+    let source = format!("-> Str\nmain = first (Str [\"a\"]) 1{}", FIRST);
+    assert_eq!(
+        checker::check(&parse(&source).unwrap()),
+        Err(errors::unexpected_type(&Type::Str, &Type::Int, 2))
+    );
+}
+
+#[test]
+fn type_variables_are_opaque_in_generic_functions() {
+    let source = "a -> a\nincrement x = + x 1";
+    assert!(matches!(
+        checker::check(&parse(source).unwrap()),
+        Err(CompilerError::Interpreter(_, 2))
+    ));
+}
+
+#[test]
+fn type_variables_bound_at_runtime() {
+    let source = format!("-> [Str]\nmain = singleton \"a\"{}", SINGLETON);
+    let list = evaluate(&source).unwrap().unwrap();
+    assert_eq!(list.get_type(), Type::List(Box::new(Type::Str)));
+
+    let source = format!("-> [[Int]]\nmain = Int [1 2] >> singleton{}", SINGLETON);
+    let list = evaluate(&source).unwrap().unwrap();
+    assert_eq!(list.to_string(), "[[1], [2]]");
+    assert_eq!(
+        list.get_type(),
+        Type::List(Box::new(Type::List(Box::new(Type::Int))))
+    );
+
+    let source = format!("-> Int\nmain = first (Str [\"a\"]) 1{}", FIRST);
+    assert_eq!(
+        evaluate(&source).unwrap_err(),
+        errors::unexpected_type(&Type::Str, &Type::Int, 2)
+    );
+}
+
+#[test]
+fn type_variables_only_in_signatures() {
+    assert_eq!(
+        parse("data Box = { value a }").unwrap_err(),
+        errors::unknown_type(&"a".into(), None, 1)
+    );
+}
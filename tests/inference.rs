@@ -0,0 +1,57 @@
+use lemma::{
+    checker,
+    errors::{self, CompilerError},
+    tokens::Type,
+};
+
+mod common;
+
+use common::{evaluate, parse};
+
+fn inferred(source: &str, name: &str) -> String {
+    checker::signatures(&parse(source).unwrap())[name].to_string()
+}
+
+#[test]
+fn signatures_inferred() {
+    let source = "pick c t e = if c then t else e\n\ndouble n = * n 2\n\nhalf x = / x 2.0";
+    assert_eq!(inferred(source, "pick"), "Bool a a -> a");
+    assert_eq!(inferred(source, "double"), "Int -> Int");
+    assert_eq!(inferred(source, "half"), "Frac -> Frac");
+
+    let source = "even n = if == n 0 then true else odd (- n 1)\n\n\
+                  odd n = if == n 0 then false else even (- n 1)";
+    assert_eq!(inferred(source, "even"), "Int -> Bool");
+    assert_eq!(inferred(source, "odd"), "Int -> Bool");
+}
+
+#[test]
+fn inferred_functions_are_generic() {
+    let source = "-> Str\nmain = if identity true then identity \"a\" else \"b\"\n\nidentity x = x";
+    assert_eq!(checker::check(&parse(source).unwrap()), Ok(()));
+    assert_eq!(evaluate(source).unwrap().unwrap().to_string(), "a");
+}
+
+#[test]
+fn inferred_types_checked() {
+    let source = "-> Str\nmain = double 2\n\ndouble n = * n 2";
+    assert_eq!(
+        checker::check(&parse(source).unwrap()),
+        Err(errors::unexpected_type(&Type::Str, &Type::Int, 2))
+    );
+
+    let source = "greet name = + \"hi \" name";
+    assert!(matches!(
+        checker::check(&parse(source).unwrap()),
+        Err(CompilerError::Interpreter(_, 1))
+    ));
+}
+
+#[test]
+fn signatures_verified() {
+    let source = "Int -> Str\ndouble n = * n 2";
+    assert_eq!(
+        checker::check(&parse(source).unwrap()),
+        Err(errors::unexpected_type(&Type::Str, &Type::Int, 2))
+    );
+}
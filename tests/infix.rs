@@ -0,0 +1,64 @@
+use lemma::errors;
+
+mod common;
+
+use common::{evaluate, parse};
+
+/// Asserts that an infix expression parses to the same program as a prefix one.
+fn assert_same(infix: &str, prefix: &str) {
+    let program = |expression: &str| {
+        let source = format!("Int Int -> Int\nf a b = {}", expression);
+        serde_json::to_value(parse(&source).unwrap()).unwrap()
+    };
+    assert_eq!(program(infix), program(prefix), "{}", infix);
+}
+
+#[test]
+fn same_ast_as_prefix() {
+    assert_same("`a - b * (a / b)`", "- a (* b (/ a b))");
+    assert_same("`a + b + 1`", "+ a b 1");
+    assert_same("`(a + b) * 2 - -a`", "- (* (+ a b) 2) (- a)");
+    assert_same(
+        "`a < b && !(a == 0) || b >= 1`",
+        "|| (&& (< a b) (! (== a 0))) (>= b 1)",
+    );
+    assert_same("`f a 1 * 2`", "* (f a 1) 2");
+    assert_same("if `a > b` then a else b", "if > a b then a else b");
+}
+
+#[test]
+fn precedence() {
+    let output = |expression: &str| {
+        let source = format!("-> Int\nmain = {}", expression);
+        evaluate(&source).unwrap().unwrap().to_string()
+    };
+    assert_eq!(output("`1 + 2 * 3`"), "7");
+    assert_eq!(output("`(1 + 2) * 3`"), "9");
+    assert_eq!(output("`10 - 4 - 3`"), "3");
+    assert_eq!(
+        output("`- 2 * 3 + square 2`\n\nInt -> Int\nsquare n = `n * n`"),
+        "-2"
+    );
+}
+
+#[test]
+fn unclosed_backtick() {
+    assert_eq!(
+        parse("-> Int\nmain = `1 + 2").unwrap_err(),
+        errors::unexpected_token("closing backtick", 2)
+    );
+}
+
+#[test]
+fn unexpected_backtick_described() {
+    let err = parse("-> Int\nmain = `1 + `").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Parser error: expected operand, found a backtick (line 2)"
+    );
+
+    let err = parse("-> Int\nmain = + 1 `").unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("expected operand, found the end of the program"));
+}
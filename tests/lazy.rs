@@ -0,0 +1,70 @@
+use lemma::{checker, errors, tokens::Type};
+
+mod common;
+
+use common::{evaluate, output, parse};
+
+#[test]
+fn infinite_ranges() {
+    let source = "-> [Int]\nmain = take 4 (Int [1, 3 ..])";
+    assert_eq!(output(source), Ok("[1, 3, 5, 7]".to_string()));
+
+    let source = "-> [Int]\nmain = take 3 (drop 10 (Int [0 ..]))";
+    assert_eq!(output(source), Ok("[10, 11, 12]".to_string()));
+
+    let source = "-> Maybe Str\nmain = head (Str [\"q\" ..])";
+    assert_eq!(output(source), Ok("Some q".to_string()));
+}
+
+#[test]
+fn elements_computed_when_needed() {
+    // Elements past the third are never mapped, or the program would fail
+    let source = "-> [Int]\nmain = take 3 (Int [1 .. 1000000000000] >> small)\n\n\
+                  Int -> Int\nsmall n = if > n 3 then raise \"too big\" else * n 10";
+    assert_eq!(output(source), Ok("[10, 20, 30]".to_string()));
+
+    let source = "-> [Int]\nmain = take 4 (Int [1 ..] >> small)\n\n\
+                  Int -> Int\nsmall n = if > n 3 then raise \"too big\" else * n 10";
+    assert_eq!(output(source), Err(errors::raised("too big", 5)));
+}
+
+#[test]
+fn lists_taken_and_dropped() {
+    let source = "-> ([Int], [Int], [Int])\n\
+                  main = (take 2 (Int [4 5 6]), drop 2 (Int [4 5 6]), drop (- 0 1) (Int [4]))";
+    assert_eq!(output(source), Ok("([4, 5], [6], [4])".to_string()));
+
+    let source = "firsts n = take n (Int [1 ..])";
+    let signatures = checker::signatures(&parse(source).unwrap());
+    assert_eq!(signatures["firsts"].to_string(), "Int -> [Int]");
+
+    let source = "-> [Int]\nmain = take \"a\" (Int [1 2])";
+    assert_eq!(
+        output(source),
+        Err(errors::unexpected_type(&Type::Int, &Type::Str, 2))
+    );
+}
+
+#[test]
+fn errors_caught_by_try() {
+    // The elements are computed inside the `try`, so their errors are caught there
+    let source = "-> [Int]\nmain = try (Int [1, 0 .. (- 0 1)] >> inv) catch _ -> Int [42]\n\n\
+                  Int -> Int\ninv n = / 10 n";
+    assert_eq!(output(source), Ok("[42]".to_string()));
+
+    let source = "-> [Int]\nmain = try (Int [1 .. 3] >> inv) catch _ -> Int [42]\n\n\
+                  Int -> Int\ninv n = / 12 n";
+    assert_eq!(output(source), Ok("[12, 6, 4]".to_string()));
+}
+
+#[test]
+fn mapped_types_known_without_mapping() {
+    // Functions without a signature are not called to find the type of their results
+    let source = "-> [Int]\nmain = take 0 (Int [1 ..] >> loud)\n\nloud n = raise \"called\"";
+    assert_eq!(output(source), Ok("[]".to_string()));
+
+    let source = "-> [Frac]\nmain = take 2 (Int [1 ..] >> half)\n\nhalf n = / n 2.0";
+    let value = evaluate(source).unwrap().unwrap();
+    assert_eq!(value.to_string(), "[0.5, 1.0]");
+    assert_eq!(value.get_type(), Type::List(Box::new(Type::Frac)));
+}
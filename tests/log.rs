@@ -0,0 +1,31 @@
+use {
+    lemma::log::{LogFormat, Logger},
+    log::{Level, LevelFilter, Record},
+    std::time::{Duration, UNIX_EPOCH},
+};
+
+#[test]
+fn json_lines() {
+    let logger = Logger {
+        level: LevelFilter::Debug,
+        format: LogFormat::Json,
+        timestamps: true,
+    };
+    let record = Record::builder()
+        .args(format_args!("Building AST..."))
+        .level(Level::Debug)
+        .target("lemma")
+        .build();
+    let line = logger.format(&record, UNIX_EPOCH + Duration::from_millis(1_500));
+    let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(value["level"], "DEBUG");
+    assert_eq!(value["message"], "Building AST...");
+    assert_eq!(value["timestamp"], "1970-01-01T00:00:01.500Z");
+    assert!(!line.contains('\n'));
+}
+
+#[test]
+fn format_parsed() {
+    assert_eq!("JSON".parse(), Ok(LogFormat::Json));
+    assert!("xml".parse::<LogFormat>().is_err());
+}
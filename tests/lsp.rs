@@ -0,0 +1,170 @@
+use {
+    lemma::lsp::{
+        self,
+        document::{Document, Position, Range},
+    },
+    serde_json::{json, Value},
+    std::io::BufReader,
+};
+
+const SOURCE: &str = "-> Void
+main = fibonacci 10
+
+Int -> Int
+fibonacci n = if < n 2
+                then n
+              else + (fibonacci (- n 1)) (fibonacci (- n 2))";
+
+fn range(line: usize, start: usize, end: usize) -> Range {
+    Range {
+        start: Position {
+            line,
+            character: start,
+        },
+        end: Position {
+            line,
+            character: end,
+        },
+    }
+}
+
+#[test]
+fn no_diagnostics_for_valid_document() {
+    let document = Document::new(SOURCE);
+    assert!(document.diagnostics().is_empty());
+}
+
+#[test]
+fn parser_error_published() {
+    let document = Document::new("Int -> Int\nfoo a = + 1 (+ 1 1");
+    let diagnostics = document.diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message, "expected closing parenthesis");
+}
+
+#[test]
+fn type_error_published() {
+    let document = Document::new("-> Void\nmain = fibonacci true\n\nInt -> Int\nfibonacci n = n");
+    let diagnostics = document.diagnostics();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].range, range(1, 0, 21));
+}
+
+#[test]
+fn definition_found() {
+    let document = Document::new(SOURCE);
+    let position = Position {
+        line: 1,
+        character: 10,
+    };
+    assert_eq!(document.definition(&position), Some(range(4, 0, 9)));
+}
+
+#[test]
+fn references_found() {
+    let document = Document::new(SOURCE);
+    let position = Position {
+        line: 4,
+        character: 2,
+    };
+    let references = document.references(&position, true);
+    assert_eq!(
+        references,
+        vec![
+            range(1, 7, 16),
+            range(4, 0, 9),
+            range(6, 22, 31),
+            range(6, 42, 51)
+        ]
+    );
+}
+
+#[test]
+fn hover_shows_signature() {
+    let document = Document::new(SOURCE);
+    let position = Position {
+        line: 1,
+        character: 8,
+    };
+    let hover = document.hover(&position).unwrap();
+    assert!(hover.contains("Int -> Int\nfibonacci n"));
+}
+
+#[test]
+fn completions_include_user_functions() {
+    let document = Document::new(SOURCE);
+    let labels: Vec<String> = document
+        .completions()
+        .into_iter()
+        .map(|c| c.label)
+        .collect();
+    assert!(labels.contains(&"fibonacci".to_string()));
+    assert!(labels.contains(&"main".to_string()));
+}
+
+#[test]
+fn server_session() {
+    let messages = [
+        json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": { "textDocument": { "uri": "file:///fib.lm", "text": SOURCE } }
+        }),
+        json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "textDocument/definition",
+            "params": {
+                "textDocument": { "uri": "file:///fib.lm" },
+                "position": { "line": 1, "character": 10 }
+            }
+        }),
+        json!({ "jsonrpc": "2.0", "method": "exit" }),
+    ];
+    let input: String = messages
+        .iter()
+        .map(|m| {
+            let body = m.to_string();
+            format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+        })
+        .collect();
+
+    let mut output: Vec<u8> = Vec::new();
+    lsp::run(&mut BufReader::new(input.as_bytes()), &mut output).unwrap();
+
+    let mut reader = BufReader::new(output.as_slice());
+    let mut replies: Vec<Value> = Vec::new();
+    while let Some(reply) = lsp::rpc::read_message(&mut reader).unwrap() {
+        replies.push(reply.unwrap());
+    }
+    assert_eq!(replies.len(), 3);
+    assert_eq!(replies[0]["result"]["capabilities"]["hoverProvider"], true);
+    assert_eq!(replies[1]["method"], "textDocument/publishDiagnostics");
+    assert_eq!(replies[2]["result"]["range"]["start"]["line"], 4);
+}
+
+#[test]
+fn invalid_json_answered() {
+    let initialize = json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} });
+    let body = initialize.to_string();
+    let input = format!(
+        "Content-Length: 5\r\n\r\n{{oopsContent-Length: {}\r\n\r\n{}",
+        body.len(),
+        body
+    );
+
+    let mut output: Vec<u8> = Vec::new();
+    lsp::run(&mut BufReader::new(input.as_bytes()), &mut output).unwrap();
+
+    // The server carries on with the next message
+    let mut reader = BufReader::new(output.as_slice());
+    let mut replies: Vec<Value> = Vec::new();
+    while let Some(reply) = lsp::rpc::read_message(&mut reader).unwrap() {
+        replies.push(reply.unwrap());
+    }
+    assert_eq!(replies.len(), 2);
+    assert_eq!(replies[0]["error"]["code"], -32700);
+    assert_eq!(replies[0]["id"], Value::Null);
+    assert_eq!(replies[1]["id"], 1);
+}
@@ -1,13 +1,13 @@
 use std::fs;
 
-use lemma::{self, ast, lexer, log::exit};
+use lemma::{self, ast, lexer};
 
 #[test]
 fn hello_world() {
     let source = fs::read_to_string("examples/hello-world.lm")
         .unwrap()
         .to_string();
-    let tokens = lexer::tokens(&source).unwrap_or_else(|err| exit(err));
-    let program = ast::build(tokens).unwrap_or_else(|err| exit(err));
+    let tokens = lexer::tokens(&source).unwrap_or_else(|err| panic!("{}", err));
+    let program = ast::build(tokens).unwrap_or_else(|err| panic!("{}", err));
     println!("{:#?}", program);
 }
@@ -0,0 +1,68 @@
+use lemma::{checker, errors, tokens::Type};
+
+mod common;
+
+use common::{evaluate, parse, program};
+
+fn output(expression: &str) -> String {
+    evaluate(&program(expression)).unwrap().unwrap().to_string()
+}
+
+#[test]
+fn partial_functions_return_maybe() {
+    assert_eq!(output("divide 7 2"), "Some 3");
+    assert_eq!(output("divide 7 0"), "None");
+    assert_eq!(output("head (Str [\"a\" \"b\"])"), "Some a");
+    assert_eq!(output("head (Int [])"), "None");
+    assert_eq!(output("parse-int \"42\""), "Some 42");
+    assert_eq!(output("parse-int \"forty-two\""), "None");
+    assert_eq!(output("Some (Some 1)"), "Some (Some 1)");
+
+    // Arguments of the wrong type are errors, not missing values
+    assert_eq!(
+        evaluate(&program("divide 5 2.0")),
+        Err(errors::unexpected_type(&Type::Int, &Type::Frac, 2))
+    );
+    assert_eq!(
+        evaluate(&program("parse-int 5")),
+        Err(errors::unexpected_type(&Type::Str, &Type::Int, 2))
+    );
+}
+
+#[test]
+fn maybe_helpers() {
+    assert_eq!(output("default (divide 7 0) 1"), "1");
+    assert_eq!(output("default (divide 7 2) 1"), "3");
+    assert_eq!(output("is-some (divide 7 0)"), "false");
+    assert_eq!(output("is-none (divide 7 0)"), "true");
+
+    // The helpers use the prelude, even when the program defines names they use
+    let source = "-> Bool\nmain = is-none None\n\nMaybe a -> Bool\nis-some x = true";
+    assert_eq!(evaluate(source).unwrap().unwrap().to_string(), "true");
+
+    let source = "-> Maybe Int\nmain = divide 6 2 >> increment\n\nInt -> Int\nincrement n = + n 1";
+    assert_eq!(checker::check(&parse(source).unwrap()), Ok(()));
+    let maybe = evaluate(source).unwrap().unwrap();
+    assert_eq!(maybe.to_string(), "Some 4");
+    assert_eq!(maybe.get_type(), Type::Maybe(Box::new(Type::Int)));
+}
+
+#[test]
+fn maybe_matched_exhaustively() {
+    let source = "describe m = case m of\n  | Some n -> n\n  | None -> 0";
+    assert_eq!(
+        checker::signatures(&parse(source).unwrap())["describe"].to_string(),
+        "Maybe Int -> Int"
+    );
+
+    let source = "-> Int\nmain = case divide 1 0 of Some n -> n";
+    let expected = || errors::non_exhaustive(&"Maybe".into(), vec!["None".into()], 2);
+    assert_eq!(checker::check(&parse(source).unwrap()), Err(expected()));
+    assert_eq!(evaluate(source).unwrap_err(), expected());
+
+    let source = "-> Int\nmain = default (divide 1 0) \"x\"";
+    assert_eq!(
+        checker::check(&parse(source).unwrap()),
+        Err(errors::unexpected_type(&Type::Int, &Type::Str, 2))
+    );
+}
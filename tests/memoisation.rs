@@ -0,0 +1,76 @@
+use lemma::{args::Args, errors::CompilerError, interpreter::Options};
+
+mod common;
+
+use common::evaluate_with;
+
+fn evaluate(source: &str, memoise: Option<usize>) -> Result<String, CompilerError> {
+    let options = Options {
+        memoise,
+        ..Options::default()
+    };
+    Ok(evaluate_with(source, options)?.unwrap().to_string())
+}
+
+const FIBONACCI: &str = "Int -> Int\nfibonacci n = if < n 2\n  then n\n  \
+                         else + (fibonacci (- n 1)) (fibonacci (- n 2))";
+
+#[test]
+fn results_cached() {
+    // Without a cache, this would take around 10^16 calls
+    let source = format!("-> Int\nmain = fibonacci 80\n\n{}", FIBONACCI);
+    assert_eq!(
+        evaluate(&source, Some(1000)),
+        Ok("23416728348467685".to_string())
+    );
+}
+
+#[test]
+fn oldest_results_evicted() {
+    let source = format!("-> Int\nmain = fibonacci 15\n\n{}", FIBONACCI);
+    assert_eq!(evaluate(&source, Some(1)), Ok("610".to_string()));
+    assert_eq!(evaluate(&source, Some(0)), Ok("610".to_string()));
+}
+
+#[test]
+fn arguments_told_apart() {
+    let source =
+        "-> (Int, Frac, Decimal)\nmain = (same 1, same 1.0, same 1d)\n\na -> a\nsame x = x";
+    assert_eq!(evaluate(source, Some(100)), Ok("(1, 1.0, 1.0)".to_string()));
+
+    // Lazy lists are not used as keys, as their elements are not known
+    let source = "-> (Maybe Int, Maybe Int)\nmain = (first (Int [1 ..]), first (Int [2 ..]))\n\n\
+                  [Int] -> Maybe Int\nfirst l = head l";
+    assert_eq!(
+        evaluate(source, Some(100)),
+        Ok("(Some 1, Some 2)".to_string())
+    );
+}
+
+#[test]
+fn results_independent_of_callers() {
+    // `n` in `g` is the parameter of whichever function called it, so `g` is not cached
+    let source = "-> Int\nmain = + (a 1) (b 2)\n\nInt -> Int\na n = g 0\n\n\
+                  Int -> Int\nb n = g 0\n\nInt -> Int\ng x = + x n";
+    assert_eq!(evaluate(source, None), Ok("3".to_string()));
+    assert_eq!(evaluate(source, Some(10)), Ok("3".to_string()));
+
+    // Nor is a function calling it, or one using a constant a parameter could stand in for
+    let source = "-> Int\nmain = + (a 1) (b 2)\n\nInt -> Int\na n = h 0\n\n\
+                  Int -> Int\nb n = h 0\n\nInt -> Int\nh x = g x\n\nInt -> Int\ng x = + x n\n\n\
+                  -> Int\nn = 10";
+    assert_eq!(evaluate(source, Some(10)), Ok("3".to_string()));
+}
+
+#[test]
+fn memoise_parsed() {
+    let mut args = ["lemma", "--memoise=500", "main.lm"]
+        .iter()
+        .map(|a| a.to_string());
+    assert_eq!(Args::build(&mut args).unwrap().memoise, Some(500));
+
+    let mut args = ["lemma", "--memoise", "lots", "main.lm"]
+        .iter()
+        .map(|a| a.to_string());
+    assert!(Args::build(&mut args).is_err());
+}
@@ -0,0 +1,96 @@
+use {
+    lemma::{
+        errors::{CompilerError, InterpreterError, ModuleError},
+        interpreter,
+        modules::{self, Sources},
+        tokens::Value,
+    },
+    std::{fs, path::Path},
+};
+
+fn load(path: &str, sources: &mut Sources) -> Result<Option<Value>, CompilerError> {
+    let source = fs::read_to_string(path).unwrap();
+    let program = modules::load(Path::new(path), &source, sources)?;
+    interpreter::evaluate(program)
+}
+
+#[test]
+fn namespaced_and_selective() {
+    let result = load("examples/modules/main.lm", &mut Sources::default()).unwrap();
+    assert_eq!(
+        result.unwrap().to_string(),
+        "[1, 4, 3, 2, 5, 1, 7, 1, 9, 2]"
+    );
+}
+
+#[test]
+fn cycle_detected() {
+    let mut sources = Sources::default();
+    let err = load("tests/modules/cycle-a.lm", &mut sources).unwrap_err();
+    let (source, err) = sources.resolve(err);
+    assert_eq!(source.unwrap().path, Path::new("tests/modules/cycle-b.lm"));
+    let chain = ["cycle-a.lm", "cycle-b.lm", "cycle-a.lm"].map(String::from);
+    assert_eq!(
+        err,
+        CompilerError::Module(ModuleError::ImportCycle(chain.to_vec()), 1)
+    );
+}
+
+#[test]
+fn duplicate_names_both_files() {
+    let err = load("tests/modules/duplicate.lm", &mut Sources::default()).unwrap_err();
+    assert_eq!(
+        err,
+        CompilerError::Module(
+            ModuleError::DuplicateDefinition {
+                name: "rem".into(),
+                first: "duplicate.lm".into(),
+                second: "math.lm".into(),
+            },
+            1
+        )
+    );
+}
+
+#[test]
+fn unknown_import_suggested() {
+    let mut sources = Sources::default();
+    let source = "import \"math.lm\" (evne)\n-> Int\nmain = 1";
+    let path = Path::new("examples/modules/importer.lm");
+    let err = modules::load(path, source, &mut sources).unwrap_err();
+    assert_eq!(
+        err,
+        CompilerError::Module(
+            ModuleError::UnknownImport {
+                name: "evne".into(),
+                module: "math.lm".into(),
+                suggestion: Some("even".into()),
+            },
+            1
+        )
+    );
+}
+
+#[test]
+fn error_located_in_imported_file() {
+    let mut sources = Sources::default();
+    let err = load("tests/modules/runtime.lm", &mut sources).unwrap_err();
+    let (source, err) = sources.resolve(err);
+    assert_eq!(source.unwrap().path, Path::new("tests/modules/broken.lm"));
+    assert_eq!(
+        err,
+        CompilerError::Interpreter(InterpreterError::DivisionByZero, 4)
+    );
+}
+
+#[test]
+fn caught_error_located_in_its_file() {
+    let result = load("tests/modules/handled.lm", &mut Sources::default()).unwrap();
+    assert_eq!(result.unwrap().to_string(), "(2, 4)");
+}
+
+#[test]
+fn imported_aliases() {
+    let result = load("tests/modules/aliases.lm", &mut Sources::default()).unwrap();
+    assert_eq!(result.unwrap().to_string(), "[1, 2]");
+}
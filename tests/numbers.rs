@@ -0,0 +1,86 @@
+use lemma::{
+    checker, errors,
+    tokens::{Type, Value},
+};
+
+mod common;
+
+use common::{evaluate, parse, program};
+
+fn output(expression: &str) -> String {
+    common::output(&program(expression)).unwrap()
+}
+
+#[test]
+fn integers_promoted_to_fractions() {
+    assert_eq!(output("+ 1 2.5"), "3.5");
+    assert_eq!(output("* 2.5 2 2"), "10.0");
+    assert_eq!(output("/ 7 2.0"), "3.5");
+    assert_eq!(output("/ 7 2"), "3");
+    assert_eq!(output("< 1 1.5"), "true");
+    assert_eq!(output(">= 2.0 2"), "true");
+    assert_eq!(output("== 2 2.0"), "true");
+    assert_eq!(output("!= 2 2.5"), "true");
+}
+
+#[test]
+fn conversions() {
+    assert_eq!(output("/ (to-frac 7) 2"), "3.5");
+    assert_eq!(output("round 2.5"), "3");
+    assert_eq!(output("floor (- 0 2.5)"), "-3");
+    assert_eq!(output("ceiling 2.1"), "3");
+    assert_eq!(output("truncate (- 0 2.7)"), "-2");
+}
+
+#[test]
+fn promotion_type_checked() {
+    let source = "-> Frac\nmain = + (length 1) 0.5\n\nInt -> Int\nlength n = n";
+    assert_eq!(checker::check(&parse(source).unwrap()), Ok(()));
+
+    let source = "half x = / x 2.0\n\nbelow n = < n 2";
+    let signatures = checker::signatures(&parse(source).unwrap());
+    assert_eq!(signatures["half"].to_string(), "Frac -> Frac");
+    assert_eq!(signatures["below"].to_string(), "Int -> Bool");
+
+    let source = "-> Int\nmain = + 1 2.5";
+    assert_eq!(
+        checker::check(&parse(source).unwrap()),
+        Err(errors::unexpected_type(&Type::Int, &Type::Frac, 2))
+    );
+}
+
+#[test]
+fn conversions_checked() {
+    assert_eq!(
+        evaluate(&program("round \"x\"")),
+        Err(errors::unexpected_type(&Type::Frac, &Type::Str, 2))
+    );
+    assert_eq!(
+        evaluate(&program("to-frac \"x\"")),
+        Err(errors::unexpected_type(&Type::Int, &Type::Str, 2))
+    );
+
+    // Nor are values already of the type converted to
+    assert_eq!(
+        evaluate(&program("round 1")),
+        Err(errors::unexpected_type(&Type::Frac, &Type::Int, 2))
+    );
+    assert_eq!(
+        evaluate(&program("to-frac 1.5")),
+        Err(errors::unexpected_type(&Type::Int, &Type::Frac, 2))
+    );
+    assert_eq!(
+        evaluate(&program("to-frac 9223372036854775808")),
+        Ok(Some(Value::Fractional(9223372036854775808.0)))
+    );
+
+    // Infinity and NaN are not rounded to any integer
+    let source = "-> Int\nmain = floor (largest 2.0)\n\n\
+                  Frac -> Frac\nlargest x = if == x (* x x) then x else largest (* x x)";
+    let err = evaluate(source).unwrap_err();
+    assert_eq!((err.code(), err.line()), ("E0021", 2));
+    assert_eq!(
+        err.to_string(),
+        "Interpreter error: `inf.0` has no integer value (line 2)"
+    );
+}
@@ -0,0 +1,115 @@
+use lemma::{
+    args::Args,
+    errors::{CompilerError, InterpreterError},
+    grammar::{Expression, Program},
+    interpreter, optimiser,
+    tokens::Value,
+};
+
+mod common;
+
+use common::{output, parse};
+
+fn optimise(source: &str) -> Program {
+    optimiser::optimise(parse(source).unwrap())
+}
+
+fn body<'a>(program: &'a Program, name: &str) -> &'a Expression {
+    let function = program
+        .functions
+        .iter()
+        .find(|f| f.definition.name.id == name);
+    &function.unwrap().definition.body
+}
+
+#[test]
+fn constants_folded() {
+    let program = optimise("-> Int\nmain = * 60 60 24\n\n-> Bool\nlate = && (> 2 1) (< 1.5 2)");
+    assert_eq!(
+        body(&program, "main"),
+        &Expression::Value(Value::Integer(86400))
+    );
+    assert_eq!(
+        body(&program, "late"),
+        &Expression::Value(Value::Boolean(true))
+    );
+
+    // Operations that fail are left to fail when they are evaluated
+    let source = "-> Int\nmain = + 1 (/ 1 0)";
+    assert_eq!(optimise(source), parse(source).unwrap());
+    assert_eq!(
+        interpreter::evaluate(optimise(source)),
+        Err(CompilerError::Interpreter(
+            InterpreterError::DivisionByZero,
+            2
+        ))
+    );
+
+    // So do those that overflow, which may promote or fail at runtime
+    let source = "-> Int\nmain = * 9223372036854775807 2";
+    assert_eq!(optimise(source), parse(source).unwrap());
+}
+
+#[test]
+fn dead_branches_removed() {
+    let program = optimise("Int -> Int\nf x = if == 1 1 then + x 1 else / x 0");
+    let expected = parse("Int -> Int\nf x = + x 1").unwrap();
+    assert_eq!(body(&program, "f"), body(&expected, "f"));
+
+    let program = optimise("Int -> Int\nf x = if || true (== x 0) then 1 else 2");
+    assert_eq!(body(&program, "f"), &Expression::Value(Value::Integer(1)));
+}
+
+#[test]
+fn constants_inlined() {
+    let source = "-> Int\nmain = + (f 1) minutes\n\n-> Int\nminutes = * hours 60\n\n\
+                  -> Int\nhours = 24\n\nInt -> Int\nf n = * n 2";
+    let program = optimise(source);
+    assert_eq!(
+        body(&program, "minutes"),
+        &Expression::Value(Value::Integer(1440))
+    );
+    assert_eq!(
+        body(&program, "main"),
+        &parse("-> Int\nmain = + (f 1) 1440").unwrap().functions[0]
+            .definition
+            .body
+    );
+    assert_eq!(
+        interpreter::evaluate(program),
+        Ok(Some(Value::Integer(1442)))
+    );
+}
+
+#[test]
+fn behaviour_preserved() {
+    // Names are looked up where a function is called, so `x` in `g` is the parameter of `f`, and
+    // a constant with the name of a parameter is never inlined
+    let source = "-> Int\nmain = f 5\n\nInt -> Int\nf x = g 0\n\n\
+                  Int -> Int\ng y = + y x\n\n-> Int\nx = 1";
+    let original = parse(source).unwrap();
+    assert_eq!(optimise(source), original);
+    assert_eq!(interpreter::evaluate(original), Ok(Some(Value::Integer(5))));
+    assert_eq!(
+        interpreter::evaluate(optimise(source)),
+        Ok(Some(Value::Integer(5)))
+    );
+
+    // So are names bound by patterns and generators
+    let source = "-> [Int]\nmain = [ scaled | k <- Int [1 2] ]\n\n-> Int\nscaled = * k 10\n\n\
+                  -> Int\nk = 3\n\n-> Int\nother = case Some 2 of\n  | Some k -> scaled\n  | None -> 0";
+    assert_eq!(optimise(source), parse(source).unwrap());
+    assert_eq!(output(source), Ok("[10, 20]".to_string()));
+    assert_eq!(
+        interpreter::evaluate(optimise(source)).map(|v| v.unwrap().to_string()),
+        Ok("[10, 20]".to_string())
+    );
+}
+
+#[test]
+fn print_optimised_parsed() {
+    let mut args = ["lemma", "--print-optimised", "main.lm"]
+        .iter()
+        .map(|a| a.to_string());
+    assert!(Args::build(&mut args).unwrap().print_optimised);
+}
@@ -0,0 +1,80 @@
+use lemma::{
+    args::Args,
+    errors::{CompilerError, InterpreterError},
+    interpreter::{Options, Overflow},
+    tokens::Value,
+};
+
+mod common;
+
+use common::evaluate_with;
+
+fn evaluate(source: &str, overflow: Overflow) -> Result<Option<Value>, CompilerError> {
+    let options = Options {
+        overflow,
+        ..Options::default()
+    };
+    evaluate_with(source, options)
+}
+
+const FACTORIAL: &str = "-> Int\nmain = factorial 25\n\nInt -> Int\nfactorial n = if <= n 1\n  then 1\n  else * n (factorial (- n 1))";
+
+#[test]
+fn promoted_to_big_integers() {
+    let result = evaluate(FACTORIAL, Overflow::Promote).unwrap().unwrap();
+    assert_eq!(result.to_string(), "15511210043330985984000000");
+
+    // Results that fit in 64 bits again are ordinary integers
+    let source = "-> Int\nmain = / (* 9223372036854775807 4) 2 9223372036854775807";
+    assert_eq!(
+        evaluate(source, Overflow::Promote).unwrap(),
+        Some(Value::Integer(2))
+    );
+}
+
+#[test]
+fn big_integers_compared() {
+    let output = |expression: &str| {
+        let source = format!("-> Bool\nmain = {}", expression);
+        evaluate(&source, Overflow::Promote)
+            .unwrap()
+            .unwrap()
+            .to_string()
+    };
+    assert_eq!(
+        output("> 100000000000000000000 9223372036854775807"),
+        "true"
+    );
+    assert_eq!(
+        output("== (+ 9223372036854775807 1) 9223372036854775808"),
+        "true"
+    );
+    assert_eq!(output("== (+ 9223372036854775807 1) 1"), "false");
+}
+
+#[test]
+fn overflow_error() {
+    assert_eq!(
+        evaluate(FACTORIAL, Overflow::Error).unwrap_err(),
+        CompilerError::Interpreter(InterpreterError::IntegerOverflow, 7)
+    );
+
+    let source = "-> Int\nmain = - 0 9223372036854775807 1";
+    assert_eq!(
+        evaluate(source, Overflow::Error).unwrap(),
+        Some(Value::Integer(i64::MIN))
+    );
+}
+
+#[test]
+fn overflow_parsed() {
+    let mut args = ["lemma", "--overflow=error", "main.lm"]
+        .iter()
+        .map(|a| a.to_string());
+    assert_eq!(Args::build(&mut args).unwrap().overflow, Overflow::Error);
+
+    let mut args = ["lemma", "--overflow", "wrap", "main.lm"]
+        .iter()
+        .map(|a| a.to_string());
+    assert!(Args::build(&mut args).is_err());
+}
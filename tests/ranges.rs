@@ -0,0 +1,118 @@
+use lemma::{
+    checker,
+    errors::{self, CompilerError, InterpreterError},
+    interpreter::{Options, Overflow},
+    tokens::Type,
+};
+
+mod common;
+
+use common::{evaluate_with, output, parse, program};
+
+fn list(list: &str) -> Result<String, CompilerError> {
+    output(&program(list))
+}
+
+#[test]
+fn steps_and_descending_ranges() {
+    assert_eq!(list("Int [1, 3 .. 9]"), Ok("[1, 3, 5, 7, 9]".to_string()));
+    assert_eq!(list("Int [5 .. 1]"), Ok("[5, 4, 3, 2, 1]".to_string()));
+    assert_eq!(list("Int [10, 7 .. 0]"), Ok("[10, 7, 4, 1]".to_string()));
+    assert_eq!(list("Int [1, 3 .. 0]"), Ok("[]".to_string()));
+    assert_eq!(
+        list("[Int] [[3 .. 1] [1, 4 .. 9]]"),
+        Ok("[[3, 2, 1], [1, 4, 7]]".to_string())
+    );
+}
+
+#[test]
+fn fractional_decimal_and_character_ranges() {
+    assert_eq!(
+        list("Frac [0.0, 0.5 .. 2.0]"),
+        Ok("[0.0, 0.5, 1.0, 1.5, 2.0]".to_string())
+    );
+    assert_eq!(
+        list("Decimal [0d, 0.1d .. 0.3d]"),
+        Ok("[0.0, 0.1, 0.2, 0.3]".to_string())
+    );
+    assert_eq!(
+        list("Str [\"a\" .. \"e\"]"),
+        Ok("[a, b, c, d, e]".to_string())
+    );
+    assert_eq!(
+        list("Str [\"z\", \"x\" .. \"t\"]"),
+        Ok("[z, x, v, t]".to_string())
+    );
+}
+
+#[test]
+fn bounds_evaluated_at_runtime() {
+    let source = "-> [Int]\nmain = upto 4\n\nInt -> [Int]\nupto n = Int [1 .. n]";
+    assert_eq!(output(source), Ok("[1, 2, 3, 4]".to_string()));
+    assert_eq!(
+        list("Int [(- 0 2), `0 - 1` .. (* 2 1)]"),
+        Ok("[-2, -1, 0, 1, 2]".to_string())
+    );
+}
+
+#[test]
+fn negative_bounds() {
+    assert_eq!(list("Int [1, 0 .. -1]"), Ok("[1, 0, -1]".to_string()));
+    assert_eq!(
+        list("Frac [-1.5, -1.0 .. 0.0]"),
+        Ok("[-1.5, -1.0, -0.5, 0.0]".to_string())
+    );
+    assert_eq!(
+        list("Int [1 .. -x]"),
+        Err(errors::expected_token(
+            "number after `-` in range bound",
+            "x".to_string(),
+            2
+        ))
+    );
+}
+
+#[test]
+fn values_past_64_bits_overflow() {
+    let source = program("take 3 (Int [9223372036854775806, 9223372036854775807 ..])");
+    assert_eq!(
+        output(&source),
+        Ok("[9223372036854775806, 9223372036854775807, 9223372036854775808]".to_string())
+    );
+    let options = Options {
+        overflow: Overflow::Error,
+        ..Options::default()
+    };
+    assert_eq!(
+        evaluate_with(&source, options),
+        Err(CompilerError::Interpreter(
+            InterpreterError::IntegerOverflow,
+            2
+        ))
+    );
+}
+
+#[test]
+fn invalid_ranges_rejected() {
+    assert_eq!(
+        list("Int [1, 1 .. 5]"),
+        Err(errors::invalid_range("step of zero", 2))
+    );
+    assert_eq!(
+        list("Str [\"ab\" .. \"c\"]"),
+        Err(errors::invalid_range(
+            "string bounds must be single characters",
+            2
+        ))
+    );
+    assert_eq!(
+        list("Bool [false .. true]"),
+        Err(errors::unrangeable_type(&Type::Bool, 2))
+    );
+
+    let source = "-> [Int]\nmain = Int [1 .. 2.0]";
+    assert_eq!(
+        checker::check(&parse(source).unwrap()),
+        Err(errors::list_type_mismatch(&Type::Int, &Type::Frac, 2))
+    );
+}
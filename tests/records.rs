@@ -0,0 +1,75 @@
+use lemma::{
+    checker,
+    errors::{self, CompilerError, InterpreterError, ParserError},
+    tokens::{Type, Value},
+};
+
+mod common;
+
+use common::{evaluate, parse};
+
+const POINT: &str = "data Point = { x Int, y Int }\n";
+
+#[test]
+fn record_constructed_and_accessed() {
+    let source = format!(
+        "{}-> Int\nmain = sum (Point {{ y 2, x 1 }})\n\nPoint -> Int\nsum p = + (.x p) (.y p)",
+        POINT
+    );
+    assert_eq!(evaluate(&source).unwrap(), Some(Value::Integer(3)));
+
+    let source = format!("{}-> Point\nmain = Point {{ y 2, x 1 }}", POINT);
+    let point = evaluate(&source).unwrap().unwrap();
+    assert_eq!(point.to_string(), "Point { x 1, y 2 }");
+    assert_eq!(point.get_type(), Type::Named("Point".into()));
+}
+
+#[test]
+fn record_fields_checked() {
+    let source = format!("{}-> Point\nmain = Point {{ x 1 }}", POINT);
+    assert_eq!(
+        evaluate(&source).unwrap_err(),
+        errors::missing_field(&"Point".into(), &"y".into(), 3)
+    );
+
+    let source = format!("{}-> Point\nmain = Point {{ x 1, z 2 }}", POINT);
+    assert!(matches!(
+        evaluate(&source).unwrap_err(),
+        CompilerError::Interpreter(InterpreterError::UnknownField { .. }, 3)
+    ));
+
+    let source = format!("{}-> Point\nmain = Point {{ x 1, y \"2\" }}", POINT);
+    assert_eq!(
+        evaluate(&source).unwrap_err(),
+        errors::unexpected_type(&Type::Int, &Type::Str, 3)
+    );
+
+    let source = "data Point = { x Int, x Int }";
+    assert_eq!(
+        parse(source).unwrap_err(),
+        CompilerError::Parser(ParserError::DuplicateField("x".into()), 1)
+    );
+}
+
+#[test]
+fn record_types_in_signatures() {
+    let source = format!("{}Piont -> Int\nfoo p = .x p", POINT);
+    assert_eq!(
+        parse(&source).unwrap_err(),
+        errors::unknown_type(&"Piont".into(), Some("Point".into()), 2)
+    );
+
+    let source = format!("{}Point -> Str\nfoo p = .x p", POINT);
+    let program = parse(&source).unwrap();
+    assert_eq!(
+        checker::check(&program),
+        Err(errors::unexpected_type(&Type::Str, &Type::Int, 3))
+    );
+
+    let source = format!("{}Point -> Int\nfoo p = .z p", POINT);
+    let program = parse(&source).unwrap();
+    assert_eq!(
+        checker::check(&program),
+        Err(errors::unknown_field(&"Point".into(), &"z".into(), None, 3))
+    );
+}
@@ -0,0 +1,59 @@
+use lemma::{
+    checker, errors,
+    tokens::{Type, Value},
+};
+
+mod common;
+
+use common::{evaluate, parse};
+
+const DIVMOD: &str = "\n\nInt Int -> (Int, Int)\ndivmod a b = (/ a b, - a (* b (/ a b)))";
+
+#[test]
+fn tuples_constructed_and_printed() {
+    let source = format!("-> (Int, Int)\nmain = divmod 17 5{}", DIVMOD);
+    assert_eq!(checker::check(&parse(&source).unwrap()), Ok(()));
+    let tuple = evaluate(&source).unwrap().unwrap();
+    assert_eq!(tuple.to_string(), "(3, 2)");
+    assert_eq!(tuple.get_type(), Type::Tuple(vec![Type::Int, Type::Int]));
+
+    let tuple = evaluate("-> (Int, (Str, Bool))\nmain = (1, (\"a\", true))")
+        .unwrap()
+        .unwrap();
+    assert_eq!(tuple.to_string(), "(1, (a, true))");
+}
+
+#[test]
+fn tuples_destructured() {
+    let source = format!(
+        "-> Int\nmain = case divmod 17 5 of (q, r) -> + q r{}",
+        DIVMOD
+    );
+    assert_eq!(checker::check(&parse(&source).unwrap()), Ok(()));
+    assert_eq!(evaluate(&source).unwrap(), Some(Value::Integer(5)));
+
+    let source = "swap p = case p of (x, y) -> (y, x)";
+    assert_eq!(
+        checker::signatures(&parse(source).unwrap())["swap"].to_string(),
+        "(a, b) -> (b, a)"
+    );
+}
+
+#[test]
+fn tuple_types_checked() {
+    let source = "-> Int\nmain = case (1, 2) of (a, b, c) -> a";
+    let expected =
+        || errors::unexpected_type_class("3-tuple", &Type::Tuple(vec![Type::Int, Type::Int]), 2);
+    assert_eq!(checker::check(&parse(source).unwrap()), Err(expected()));
+    assert_eq!(evaluate(source).unwrap_err(), expected());
+
+    let source = "-> (Int, Str)\nmain = (1, 2)";
+    assert_eq!(
+        checker::check(&parse(source).unwrap()),
+        Err(errors::unexpected_type(
+            &Type::Tuple(vec![Type::Int, Type::Str]),
+            &Type::Tuple(vec![Type::Int, Type::Int]),
+            2
+        ))
+    );
+}
@@ -0,0 +1,97 @@
+use lemma::{
+    checker,
+    errors::{self, CompilerError, ParserError},
+    tokens::{Type, Value},
+};
+
+mod common;
+
+use common::{evaluate, parse};
+
+const SHAPE: &str = "data Shape = Circle Int | Rect Int Int | Empty\n";
+
+#[test]
+fn variant_constructed_and_matched() {
+    let area = "Shape -> Int\narea s = case s of\n  | Circle r -> * 3 r r\n  | Rect w h -> * w h\n  | Empty -> 0";
+    let source = format!("{}-> Int\nmain = area (Rect 2 3)\n\n{}", SHAPE, area);
+    assert_eq!(evaluate(&source).unwrap(), Some(Value::Integer(6)));
+
+    let source = format!("{}-> Int\nmain = area Empty\n\n{}", SHAPE, area);
+    assert_eq!(evaluate(&source).unwrap(), Some(Value::Integer(0)));
+
+    let source = format!("{}-> Shape\nmain = Rect 2 3", SHAPE);
+    let shape = evaluate(&source).unwrap().unwrap();
+    assert_eq!(shape.to_string(), "Rect 2 3");
+    assert_eq!(shape.get_type(), Type::Named("Shape".into()));
+
+    let source = format!("{}-> Bool\nmain = == (Circle 1) (Circle 1)", SHAPE);
+    assert_eq!(evaluate(&source).unwrap(), Some(Value::Boolean(true)));
+}
+
+#[test]
+fn wildcards_match_anything() {
+    let source = format!(
+        "{}-> Int\nmain = case Rect 2 3 of Circle _ -> 1 | _ -> 2",
+        SHAPE
+    );
+    assert_eq!(evaluate(&source).unwrap(), Some(Value::Integer(2)));
+
+    let source = format!(
+        "{}-> Int\nmain = case Rect 2 3 of Rect _ h -> h | _ -> 0",
+        SHAPE
+    );
+    assert_eq!(evaluate(&source).unwrap(), Some(Value::Integer(3)));
+}
+
+#[test]
+fn case_exhaustiveness_checked() {
+    let source = format!(
+        "{}Shape -> Int\narea s = case s of\n  | Circle r -> r\n  | Empty -> 0",
+        SHAPE
+    );
+    let program = parse(&source).unwrap();
+    assert_eq!(
+        checker::check(&program),
+        Err(errors::non_exhaustive(
+            &"Shape".into(),
+            vec!["Rect".into()],
+            3
+        ))
+    );
+
+    let source = format!(
+        "{}Shape -> Int\narea s = case s of Circle r -> r | _ -> 0",
+        SHAPE
+    );
+    assert_eq!(checker::check(&parse(&source).unwrap()), Ok(()));
+}
+
+#[test]
+fn constructors_checked() {
+    let source = format!("{}-> Shape\nmain = Circel 1", SHAPE);
+    assert_eq!(
+        evaluate(&source).unwrap_err(),
+        errors::unknown_constructor(&"Circel".into(), Some("Circle".into()), 3)
+    );
+
+    let source = format!("{}-> Shape\nmain = Rect 1", SHAPE);
+    assert_eq!(
+        evaluate(&source).unwrap_err(),
+        errors::wrong_function_arity(&"Rect".into(), 2, 1, 3)
+    );
+
+    let source = format!(
+        "{}Shape -> Int\narea s = case s of Rect w -> w | _ -> 0",
+        SHAPE
+    );
+    assert_eq!(
+        checker::check(&parse(&source).unwrap()),
+        Err(errors::wrong_function_arity(&"Rect".into(), 2, 1, 3))
+    );
+
+    let source = "data Shape = Circle Int | Circle Frac";
+    assert_eq!(
+        parse(source).unwrap_err(),
+        CompilerError::Parser(ParserError::DuplicateConstructor("Circle".into()), 1)
+    );
+}